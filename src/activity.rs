@@ -0,0 +1,298 @@
+//! `stats`: activity figures for one channel over a window of history,
+//! computed from `conversations.history` pages or the local archive.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::slack::types::SlackMessage;
+use crate::time::Zone;
+
+/// How many posters and reactions the report lists.
+const TOP_N: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelActivity {
+    pub channel_id: String,
+    /// Start of the window, Unix seconds.
+    pub since: i64,
+    /// Top-level messages in the window.
+    pub messages: usize,
+    pub human_messages: usize,
+    pub bot_messages: usize,
+    pub bot_share: f64,
+    /// Top-level messages that started a thread.
+    pub threads: usize,
+    pub thread_ratio: f64,
+    /// Sum of `reply_count` over the window's threads.
+    pub replies: usize,
+    /// Median seconds from a thread's parent to its first reply by someone
+    /// other than the parent's author, over the threads whose replies were
+    /// available.
+    pub median_first_reply_secs: Option<i64>,
+    pub reactions: usize,
+    pub per_day: Vec<DayCount>,
+    pub per_hour: Vec<HourCount>,
+    pub top_posters: Vec<PosterCount>,
+    pub top_reactions: Vec<ReactionCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayCount {
+    pub date: String,
+    pub messages: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HourCount {
+    pub hour: u32,
+    pub messages: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PosterCount {
+    pub user: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub messages: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReactionCount {
+    pub name: String,
+    pub count: usize,
+}
+
+impl ChannelActivity {
+    /// Figures for top-level `messages`, whose `replies` (when attached)
+    /// give the time to first reply. Days and hours are counted in `zone`;
+    /// `name_of` resolves poster ids to display names.
+    pub fn compute(
+        channel_id: &str,
+        since: i64,
+        messages: &[SlackMessage],
+        zone: Zone,
+        name_of: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut per_day: BTreeMap<String, usize> = BTreeMap::new();
+        let mut per_hour = [0usize; 24];
+        let mut posters: HashMap<&str, usize> = HashMap::new();
+        let mut reactions: HashMap<&str, usize> = HashMap::new();
+        let mut first_replies = Vec::new();
+        let (mut bots, mut threads, mut replies) = (0, 0, 0);
+
+        for msg in messages {
+            let secs = ts_seconds(&msg.ts);
+            if let Some(secs) = secs.map(|s| s as i64) {
+                if let Some(date) = zone.format_date(secs) {
+                    *per_day.entry(date).or_default() += 1;
+                }
+                if let Some(hour) = zone.hour(secs) {
+                    per_hour[hour as usize] += 1;
+                }
+            }
+            if is_bot(msg) {
+                bots += 1;
+            }
+            if let Some(author) = msg.user.as_deref().or(msg.bot_id.as_deref()) {
+                *posters.entry(author).or_default() += 1;
+            }
+            for reaction in msg.reactions.iter().flatten() {
+                *reactions.entry(&reaction.name).or_default() += reaction.count.max(0) as usize;
+            }
+
+            let reply_count = msg.reply_count.unwrap_or(0).max(0) as usize;
+            if reply_count > 0 {
+                threads += 1;
+                replies += reply_count;
+            }
+            // Someone else answering; the author adding detail is not a reply.
+            let first_reply = msg
+                .replies
+                .iter()
+                .flatten()
+                .filter(|reply| reply.ts != msg.ts)
+                .filter(|reply| msg.user.is_none() || reply.user != msg.user)
+                .filter_map(|reply| ts_seconds(&reply.ts))
+                .min_by(f64::total_cmp);
+            if let (Some(parent), Some(reply)) = (secs, first_reply) {
+                first_replies.push((reply - parent).max(0.0) as i64);
+            }
+        }
+
+        let total = messages.len();
+        let share = |n: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                n as f64 / total as f64
+            }
+        };
+
+        let mut top_posters: Vec<PosterCount> = posters
+            .into_iter()
+            .map(|(user, messages)| PosterCount {
+                name: name_of(user),
+                user: user.to_string(),
+                messages,
+            })
+            .collect();
+        top_posters.sort_by(|a, b| b.messages.cmp(&a.messages).then(a.user.cmp(&b.user)));
+        top_posters.truncate(TOP_N);
+
+        let mut top_reactions: Vec<ReactionCount> = reactions
+            .iter()
+            .map(|(name, count)| ReactionCount {
+                name: name.to_string(),
+                count: *count,
+            })
+            .collect();
+        top_reactions.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+        top_reactions.truncate(TOP_N);
+
+        Self {
+            channel_id: channel_id.to_string(),
+            since,
+            messages: total,
+            human_messages: total - bots,
+            bot_messages: bots,
+            bot_share: share(bots),
+            threads,
+            thread_ratio: share(threads),
+            replies,
+            median_first_reply_secs: median(&mut first_replies),
+            reactions: reactions.values().sum(),
+            per_day: per_day
+                .into_iter()
+                .map(|(date, messages)| DayCount { date, messages })
+                .collect(),
+            per_hour: (0..24)
+                .map(|hour| HourCount {
+                    hour,
+                    messages: per_hour[hour as usize],
+                })
+                .collect(),
+            top_posters,
+            top_reactions,
+        }
+    }
+}
+
+fn ts_seconds(ts: &str) -> Option<f64> {
+    ts.parse().ok()
+}
+
+fn is_bot(msg: &SlackMessage) -> bool {
+    msg.bot_id.is_some() || msg.subtype.as_deref() == Some("bot_message")
+}
+
+fn median(values: &mut [i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(value: serde_json::Value) -> SlackMessage {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn utc() -> Zone {
+        "UTC".parse().unwrap()
+    }
+
+    #[test]
+    fn counts_posters_bots_threads_and_reactions() {
+        // 2024-03-01 09:00 UTC and later.
+        let messages = [
+            message(json!({"ts": "1709283600.000100", "user": "U1", "text": "a",
+                "reply_count": 2,
+                "reactions": [{"name": "eyes", "users": ["U2"], "count": 1}]})),
+            message(json!({"ts": "1709287200.000100", "user": "U1", "text": "b",
+                "reactions": [{"name": "eyes", "users": ["U2", "U3"], "count": 2},
+                              {"name": "tada", "users": ["U2"], "count": 1}]})),
+            message(
+                json!({"ts": "1709373600.000100", "bot_id": "B1", "text": "deploy ok",
+                "subtype": "bot_message"}),
+            ),
+            message(json!({"ts": "1709373700.000100", "user": "U2", "text": "c"})),
+        ];
+        let names = |id: &str| (id == "U1").then(|| "Alice".to_string());
+        let stats = ChannelActivity::compute("C1", 0, &messages, utc(), names);
+
+        assert_eq!(stats.messages, 4);
+        assert_eq!((stats.human_messages, stats.bot_messages), (3, 1));
+        assert_eq!(stats.bot_share, 0.25);
+        assert_eq!((stats.threads, stats.replies), (1, 2));
+        assert_eq!(stats.thread_ratio, 0.25);
+        assert_eq!(stats.reactions, 4);
+        assert_eq!(
+            stats.top_reactions[0],
+            ReactionCount {
+                name: "eyes".into(),
+                count: 3
+            }
+        );
+        assert_eq!(
+            stats.top_posters[0],
+            PosterCount {
+                user: "U1".into(),
+                name: Some("Alice".into()),
+                messages: 2
+            }
+        );
+        assert_eq!(
+            stats.per_day,
+            [
+                DayCount {
+                    date: "2024-03-01".into(),
+                    messages: 2
+                },
+                DayCount {
+                    date: "2024-03-02".into(),
+                    messages: 2
+                }
+            ]
+        );
+        assert_eq!(stats.per_hour[10].messages, 3);
+        assert_eq!(stats.per_hour.len(), 24);
+        assert_eq!(stats.median_first_reply_secs, None);
+    }
+
+    #[test]
+    fn median_first_reply_uses_attached_replies() {
+        let mut threads = Vec::new();
+        for (parent, first_reply) in [(1000, 1060), (2000, 2300), (3000, 3600), (4000, 4120)] {
+            let mut msg = message(json!({
+                "ts": format!("{parent}.000100"), "user": "U1", "text": "q", "reply_count": 1
+            }));
+            msg.replies = Some(vec![
+                // The author's own follow-up does not count as the answer.
+                message(json!({
+                    "ts": format!("{}.000100", parent + 5), "user": "U1", "text": "more"
+                })),
+                message(json!({
+                    "ts": format!("{first_reply}.000100"), "user": "U2", "text": "a"
+                })),
+            ]);
+            threads.push(msg);
+        }
+        let stats = ChannelActivity::compute("C1", 0, &threads, utc(), |_| None);
+        // 60, 120, 300, 600 seconds.
+        assert_eq!(stats.median_first_reply_secs, Some(210));
+
+        let empty = ChannelActivity::compute("C1", 0, &[], utc(), |_| None);
+        assert_eq!((empty.messages, empty.bot_share), (0, 0.0));
+    }
+}
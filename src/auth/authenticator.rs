@@ -4,7 +4,7 @@ use tokio::sync::RwLock;
 
 use super::env::EnvOverrides;
 use super::errors::AuthError;
-use super::policy::TokenPolicy;
+use super::policy::{TokenKind, TokenOverrides, TokenPolicy};
 use super::profile::Profile;
 use super::secret::Secret;
 use super::state::AuthState;
@@ -14,6 +14,7 @@ pub struct AuthLoadOptions {
     pub store_path: PathBuf,
     pub overrides: EnvOverrides,
     pub explicit_profile: Option<String>,
+    pub token_overrides: TokenOverrides,
 }
 
 pub struct Authenticator {
@@ -21,6 +22,7 @@ pub struct Authenticator {
     state: RwLock<AuthState>,
     overrides: EnvOverrides,
     explicit_profile: Option<String>,
+    token_overrides: TokenOverrides,
 }
 
 impl Authenticator {
@@ -32,20 +34,25 @@ impl Authenticator {
             state: RwLock::new(state),
             overrides: opts.overrides,
             explicit_profile: opts.explicit_profile,
+            token_overrides: opts.token_overrides,
         })
     }
 
-    pub async fn token_for(&self, policy: TokenPolicy) -> Result<Secret, AuthError> {
+    pub fn token_overrides(&self) -> &TokenOverrides {
+        &self.token_overrides
+    }
+
+    /// Resolves the token for `method`, whose `API_CONFIGS` default is
+    /// `default`; `--as` and `[auth.method_tokens]` take precedence.
+    pub async fn token_for(&self, method: &str, default: TokenPolicy) -> Result<Secret, AuthError> {
+        let policy = self.token_overrides.policy_for(method, default);
         if self.overrides.has_inline_tokens() {
             return policy
                 .pick(
                     self.overrides.user_token.clone(),
                     self.overrides.bot_token.clone(),
                 )
-                .ok_or_else(|| AuthError::NoTokenForPolicy {
-                    profile: "env".into(),
-                    policy,
-                });
+                .ok_or_else(|| no_token_error("env", policy));
         }
 
         let state = self.state.read().await;
@@ -61,10 +68,54 @@ impl Authenticator {
 
         policy
             .pick(profile.tokens.user.clone(), profile.tokens.bot.clone())
-            .ok_or_else(|| AuthError::NoTokenForPolicy {
-                profile: name.to_string(),
-                policy,
-            })
+            .ok_or_else(|| no_token_error(name, policy))
+    }
+
+    /// Scopes granted to the token `policy` resolves to, when they are known.
+    /// Only the stored user token has a recorded scope list (PKCE logins
+    /// request user scopes exclusively); env tokens, bot tokens and static
+    /// logins report `None`, and callers skip scope checks for them.
+    pub async fn granted_scopes(&self, method: &str, default: TokenPolicy) -> Option<Vec<String>> {
+        let policy = self.token_overrides.policy_for(method, default);
+        if self.overrides.has_inline_tokens() {
+            return None;
+        }
+
+        let state = self.state.read().await;
+        let name = self
+            .explicit_profile
+            .as_deref()
+            .or(state.active_profile.as_deref())?;
+        let profile = state.profiles.get(name)?;
+        profile_user_scopes(profile, policy)
+    }
+
+    /// Whether the selected profile holds an org-wide Enterprise Grid token.
+    /// Env tokens carry no workspace metadata and report `false`.
+    pub async fn is_enterprise_install(&self) -> bool {
+        if self.overrides.has_inline_tokens() {
+            return false;
+        }
+        let state = self.state.read().await;
+        self.explicit_profile
+            .as_deref()
+            .or(state.active_profile.as_deref())
+            .and_then(|name| state.profiles.get(name))
+            .is_some_and(|p| p.workspace.is_enterprise_install)
+    }
+
+    /// Workspace id of the selected profile; `None` for env tokens, which
+    /// carry no workspace metadata.
+    pub async fn team_id(&self) -> Option<String> {
+        if self.overrides.has_inline_tokens() {
+            return None;
+        }
+        let state = self.state.read().await;
+        self.explicit_profile
+            .as_deref()
+            .or(state.active_profile.as_deref())
+            .and_then(|name| state.profiles.get(name))
+            .map(|p| p.workspace.team_id.clone())
     }
 
     pub async fn snapshot(&self) -> AuthState {
@@ -115,6 +166,37 @@ impl Authenticator {
     }
 }
 
+fn no_token_error(profile: &str, policy: TokenPolicy) -> AuthError {
+    let kind = match policy {
+        TokenPolicy::UserRequired => Some(TokenKind::User),
+        TokenPolicy::BotRequired => Some(TokenKind::Bot),
+        TokenPolicy::UserPreferred | TokenPolicy::BotPreferred => None,
+    };
+    match kind {
+        Some(kind) => AuthError::MissingTokenKind {
+            profile: profile.to_string(),
+            kind,
+        },
+        None => AuthError::NoTokenForPolicy {
+            profile: profile.to_string(),
+            policy,
+        },
+    }
+}
+
+/// The profile's recorded scopes, if `policy` would pick its user token.
+pub(crate) fn profile_user_scopes(profile: &Profile, policy: TokenPolicy) -> Option<Vec<String>> {
+    if profile.scopes.is_empty() {
+        return None;
+    }
+    let picks_user = match policy {
+        TokenPolicy::UserRequired | TokenPolicy::UserPreferred => profile.tokens.user.is_some(),
+        TokenPolicy::BotPreferred => profile.tokens.bot.is_none() && profile.tokens.user.is_some(),
+        TokenPolicy::BotRequired => false,
+    };
+    picks_user.then(|| profile.scopes.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +239,107 @@ mod tests {
         let picked = TokenPolicy::BotPreferred.pick(Some(s("xoxp")), Some(s("xoxb")));
         assert_eq!(picked.unwrap().expose_secret(), "xoxb");
     }
+
+    #[test]
+    fn bot_required_ignores_user() {
+        assert!(
+            TokenPolicy::BotRequired
+                .pick(Some(s("xoxp")), None)
+                .is_none()
+        );
+    }
+
+    fn env_authenticator(
+        user: Option<&str>,
+        bot: Option<&str>,
+        forced: Option<TokenKind>,
+    ) -> (Authenticator, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let auth = Authenticator::load(AuthLoadOptions {
+            store_path: dir.path().join("auth.json"),
+            overrides: EnvOverrides {
+                user_token: user.map(s),
+                bot_token: bot.map(s),
+            },
+            explicit_profile: None,
+            token_overrides: TokenOverrides {
+                forced,
+                ..Default::default()
+            },
+        })
+        .unwrap();
+        (auth, dir)
+    }
+
+    #[tokio::test]
+    async fn forced_kind_overrides_method_default() {
+        let (auth, _dir) = env_authenticator(Some("xoxp"), Some("xoxb"), Some(TokenKind::User));
+        let token = auth
+            .token_for("chat.postMessage", TokenPolicy::BotPreferred)
+            .await
+            .unwrap();
+        assert_eq!(token.expose_secret(), "xoxp");
+    }
+
+    #[tokio::test]
+    async fn forced_kind_without_token_names_the_missing_type() {
+        let (auth, _dir) = env_authenticator(Some("xoxp"), None, Some(TokenKind::Bot));
+        let err = auth
+            .token_for("chat.postMessage", TokenPolicy::BotPreferred)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AuthError::MissingTokenKind {
+                kind: TokenKind::Bot,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("has no bot token"));
+    }
+
+    fn profile_with(user: bool, bot: bool, scopes: &[&str]) -> Profile {
+        use crate::auth::method::AuthMethod;
+        use crate::auth::profile::{TokenSet, WorkspaceInfo};
+
+        Profile {
+            method: AuthMethod::Pkce,
+            workspace: WorkspaceInfo {
+                team_id: "T1".into(),
+                team_name: "Acme".into(),
+                user_id: None,
+                enterprise: None,
+                is_enterprise_install: false,
+            },
+            tokens: TokenSet {
+                user: user.then(|| s("xoxp")),
+                bot: bot.then(|| s("xoxb")),
+            },
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            client_id: None,
+            authorized_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn user_scopes_apply_when_policy_picks_user_token() {
+        let profile = profile_with(true, false, &["chat:write"]);
+        assert_eq!(
+            profile_user_scopes(&profile, TokenPolicy::BotPreferred),
+            Some(vec!["chat:write".to_string()])
+        );
+    }
+
+    #[test]
+    fn user_scopes_do_not_apply_to_bot_token() {
+        let profile = profile_with(true, true, &["chat:write"]);
+        assert!(profile_user_scopes(&profile, TokenPolicy::BotPreferred).is_none());
+        assert!(profile_user_scopes(&profile, TokenPolicy::UserPreferred).is_some());
+    }
+
+    #[test]
+    fn unrecorded_scopes_are_unknown() {
+        let profile = profile_with(true, false, &[]);
+        assert!(profile_user_scopes(&profile, TokenPolicy::UserRequired).is_none());
+    }
 }
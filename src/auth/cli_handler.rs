@@ -6,9 +6,11 @@ use secrecy::ExposeSecret;
 
 use crate::cli::AuthAction;
 use crate::config::Config;
+use crate::format::print_json_line;
 use crate::slack::SlackClient;
 
 use super::Authenticator;
+use super::doctor::{self, CheckStatus, DoctorReport};
 use super::login::{pkce_login, static_login};
 use super::method::AuthMethod;
 use super::oauth::callback::DEFAULT_CALLBACK_PORT;
@@ -30,6 +32,7 @@ pub async fn handle(
             client_id,
             port,
             no_browser,
+            write_scopes,
         } => {
             let input = LoginInput {
                 method: method.map(Into::into),
@@ -39,6 +42,7 @@ pub async fn handle(
                 client_id: client_id.and_then(non_blank),
                 port: port.unwrap_or(DEFAULT_CALLBACK_PORT),
                 no_browser,
+                write_scopes,
             };
             let slack = SlackClient::new(config.clone(), authenticator.clone())?;
             login(input, config, &slack, &authenticator, json).await
@@ -62,6 +66,8 @@ pub async fn handle(
             status(profile, slack.as_ref(), &authenticator, json).await
         }
 
+        AuthAction::Doctor => doctor(profile, &authenticator, json).await,
+
         AuthAction::Profiles => list_profiles(&authenticator, json).await,
 
         AuthAction::Use { name } => set_active(name, &authenticator, json).await,
@@ -76,6 +82,7 @@ struct LoginInput {
     client_id: Option<String>,
     port: u16,
     no_browser: bool,
+    write_scopes: bool,
 }
 
 async fn login(
@@ -100,6 +107,7 @@ async fn login(
                 api_base_url: config.connection.api_base_url.clone(),
                 port: input.port,
                 no_browser: input.no_browser,
+                write_scopes: input.write_scopes,
             };
             pkce_login::run(request).await?
         }
@@ -210,15 +218,12 @@ fn slugify(input: &str) -> String {
 
 fn print_login_result(profile_name: &str, profile: &Profile, json: bool) {
     if json {
-        println!(
-            "{}",
-            serde_json::json!({
-                "profile": profile_name,
-                "team_id": profile.workspace.team_id,
-                "team_name": profile.workspace.team_name,
-                "method": profile.method.as_str(),
-            })
-        );
+        print_json_line(&serde_json::json!({
+            "profile": profile_name,
+            "team_id": profile.workspace.team_id,
+            "team_name": profile.workspace.team_name,
+            "method": profile.method.as_str(),
+        }));
     } else {
         println!(
             "✓ Logged in to {} via {} (profile: {})",
@@ -300,7 +305,7 @@ fn emit_logout_result(json: bool, outcome: LogoutOutcome) {
                 "active_profile": new_active,
             }),
         };
-        println!("{payload}");
+        print_json_line(&payload);
         return;
     }
 
@@ -346,7 +351,7 @@ async fn status(
     let snapshot = authenticator.snapshot().await;
     if snapshot.profiles.is_empty() {
         if json {
-            println!("{}", serde_json::json!({"profiles": []}));
+            print_json_line(&serde_json::json!({"profiles": []}));
         } else {
             println!("No profiles configured. Run: slack-cli auth login");
         }
@@ -414,7 +419,7 @@ fn print_status_json(
         }
         None => {}
     }
-    println!("{payload}");
+    print_json_line(&payload);
 }
 
 fn print_status_text(
@@ -427,6 +432,14 @@ fn print_status_text(
         "  workspace: {} ({})",
         profile.workspace.team_name, profile.workspace.team_id
     );
+    if let Some(org) = &profile.workspace.enterprise {
+        let scope = if profile.workspace.is_enterprise_install {
+            "org-wide token"
+        } else {
+            "workspace token"
+        };
+        println!("  enterprise: {} ({}, {scope})", org.name, org.id);
+    }
     if let Some(token) = &profile.tokens.user {
         println!("  user_token: {}", mask_secret(token));
     }
@@ -444,6 +457,55 @@ fn print_status_text(
     }
 }
 
+async fn doctor(profile: Option<String>, authenticator: &Authenticator, json: bool) -> Result<()> {
+    let snapshot = authenticator.snapshot().await;
+    let name = profile
+        .or_else(|| snapshot.active_profile.clone())
+        .context("no active profile selected. Run: slack-cli auth login")?;
+    let profile = snapshot
+        .profiles
+        .get(&name)
+        .with_context(|| format!("profile {name} not found"))?;
+
+    let report = doctor::diagnose(&name, profile, authenticator.token_overrides());
+    if json {
+        print_json_line(&report);
+    } else {
+        print_doctor_text(&report);
+    }
+    Ok(())
+}
+
+fn print_doctor_text(report: &DoctorReport) {
+    println!("profile: {} ({})", report.profile, report.method);
+    if !report.scopes_known {
+        println!("  scopes were not recorded at login; commands marked ? may still fail");
+    }
+    for check in &report.commands {
+        let marker = match check.status {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Unknown => "?",
+            CheckStatus::MissingScope | CheckStatus::MissingToken => "✗",
+        };
+        let detail = match check.status {
+            CheckStatus::MissingScope if check.optional => format!(
+                " (needs {}; log in with --write-scopes to enable)",
+                check.missing_scopes.join(", ")
+            ),
+            CheckStatus::MissingScope => format!(" (needs {})", check.missing_scopes.join(", ")),
+            CheckStatus::MissingToken => " (no token of the required type)".to_string(),
+            _ => String::new(),
+        };
+        println!("  {marker} {:<14}{detail}", check.command);
+    }
+    if !report.missing_scopes.is_empty() {
+        println!("missing scopes: {}", report.missing_scopes.join(", "));
+    }
+    if let Some(fix) = &report.fix {
+        println!("fix: {fix}");
+    }
+}
+
 async fn list_profiles(authenticator: &Authenticator, json: bool) -> Result<()> {
     let snapshot = authenticator.snapshot().await;
     if json {
@@ -459,7 +521,7 @@ async fn list_profiles(authenticator: &Authenticator, json: bool) -> Result<()>
                 })
             })
             .collect();
-        println!("{}", serde_json::json!({"profiles": payload}));
+        print_json_line(&serde_json::json!({"profiles": payload}));
     } else if snapshot.profiles.is_empty() {
         println!("No profiles configured. Run: slack-cli auth login");
     } else {
@@ -485,7 +547,7 @@ async fn set_active(name: String, authenticator: &Authenticator, json: bool) ->
     let name = non_blank(name).context("profile name must not be blank")?;
     authenticator.set_active(&name).await?;
     if json {
-        println!("{}", serde_json::json!({"active": name}));
+        print_json_line(&serde_json::json!({"active": name}));
     } else {
         println!("✓ Active profile: {name}");
     }
@@ -0,0 +1,355 @@
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::slack::api_config::get_api_config;
+
+use super::authenticator::profile_user_scopes;
+use super::method::AuthMethod;
+use super::oauth::scopes::{OPTIONAL_USER_SCOPES, REQUIRED_USER_SCOPES};
+use super::policy::TokenOverrides;
+use super::profile::Profile;
+
+/// CLI commands and the Slack methods each one calls. Keep in sync with
+/// the dispatch in `main.rs` when adding commands. `a|b` is satisfied by
+/// either method, for commands with a fallback.
+pub(crate) const COMMAND_METHODS: &[(&str, &[&str])] = &[
+    ("users", &["users.list"]),
+    ("channels", &["conversations.list"]),
+    ("send", &["chat.postMessage"]),
+    ("update", &["chat.update"]),
+    ("delete", &["chat.delete"]),
+    ("permalink", &["chat.getPermalink"]),
+    ("messages", &["conversations.history"]),
+    ("thread", &["conversations.replies"]),
+    ("stats", &["conversations.history", "conversations.replies"]),
+    ("inbox", &["conversations.info", "assistant.search.context"]),
+    (
+        "inbox mark-read",
+        &["conversations.history", "conversations.mark"],
+    ),
+    ("members", &["conversations.members"]),
+    (
+        "user-channels",
+        &["conversations.list", "conversations.members"],
+    ),
+    // The Real-time Search API, or legacy `search.messages` with search:read.
+    ("search", &["assistant.search.context|search.messages"]),
+    ("react", &["reactions.add"]),
+    ("unreact", &["reactions.remove"]),
+    ("reactions", &["reactions.get"]),
+    ("emoji", &["emoji.list"]),
+    ("pin", &["pins.add"]),
+    ("unpin", &["pins.remove"]),
+    ("pins", &["pins.list"]),
+    ("bookmark", &["bookmarks.add"]),
+    ("unbookmark", &["bookmarks.remove"]),
+    ("bookmarks", &["bookmarks.list"]),
+    ("remind", &["reminders.add"]),
+    ("remind list", &["reminders.list"]),
+    (
+        "remind complete|delete",
+        &["reminders.complete", "reminders.delete"],
+    ),
+    ("cache refresh", &["users.list", "conversations.list"]),
+    ("cache refresh members", &["conversations.members"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CheckStatus {
+    Ok,
+    /// A token is available but its scopes were not recorded at login
+    /// (static tokens, bot tokens), so usability cannot be decided offline.
+    Unknown,
+    MissingScope,
+    MissingToken,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CommandCheck {
+    pub command: &'static str,
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_scopes: Vec<&'static str>,
+    /// Only `OPTIONAL_USER_SCOPES` are missing: the command is unusable, but
+    /// the profile is not broken.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DoctorReport {
+    pub profile: String,
+    pub method: &'static str,
+    pub scopes_known: bool,
+    pub commands: Vec<CommandCheck>,
+    pub missing_scopes: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+pub(crate) fn diagnose(name: &str, profile: &Profile, overrides: &TokenOverrides) -> DoctorReport {
+    let commands: Vec<CommandCheck> = COMMAND_METHODS
+        .iter()
+        .map(|(command, methods)| check_command(command, methods, profile, overrides))
+        .collect();
+
+    let missing_scopes: Vec<&'static str> = commands
+        .iter()
+        .flat_map(|c| c.missing_scopes.iter().copied())
+        .filter(|s| !OPTIONAL_USER_SCOPES.contains(s))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let needs_fix = commands.iter().any(|c| {
+        !c.optional
+            && matches!(
+                c.status,
+                CheckStatus::MissingScope | CheckStatus::MissingToken
+            )
+    });
+
+    DoctorReport {
+        profile: name.to_string(),
+        method: profile.method.as_str(),
+        scopes_known: !profile.scopes.is_empty(),
+        commands,
+        fix: needs_fix.then(|| fix_command(name, profile, &missing_scopes)),
+        missing_scopes,
+    }
+}
+
+fn check_command(
+    command: &'static str,
+    methods: &[&str],
+    profile: &Profile,
+    overrides: &TokenOverrides,
+) -> CommandCheck {
+    let mut status = CheckStatus::Ok;
+    let mut missing_scopes = Vec::new();
+
+    for requirement in methods {
+        // The best alternative decides; ties report the first one's scope.
+        let (method_status, missing) = requirement
+            .split('|')
+            .filter_map(|method| check_method(method, profile, overrides))
+            .min_by_key(|(status, _)| *status)
+            .unwrap_or((CheckStatus::Ok, None));
+        missing_scopes.extend(missing);
+        status = status.max(method_status);
+    }
+
+    let optional = status == CheckStatus::MissingScope
+        && missing_scopes
+            .iter()
+            .all(|s| OPTIONAL_USER_SCOPES.contains(s));
+    CommandCheck {
+        command,
+        status,
+        missing_scopes,
+        optional,
+    }
+}
+
+/// `None` for a method without an `ApiConfig`; otherwise its status and,
+/// when a scope is missing, the canonical scope to request.
+fn check_method(
+    method: &str,
+    profile: &Profile,
+    overrides: &TokenOverrides,
+) -> Option<(CheckStatus, Option<&'static str>)> {
+    let config = get_api_config(method)?;
+    let policy = overrides.policy_for(method, config.token_policy);
+    let has_token = policy
+        .pick(profile.tokens.user.clone(), profile.tokens.bot.clone())
+        .is_some();
+
+    Some(if !has_token {
+        (CheckStatus::MissingToken, None)
+    } else {
+        match profile_user_scopes(profile, policy) {
+            Some(granted) if config.is_authorized_by(&granted) => (CheckStatus::Ok, None),
+            // The first entry is the canonical scope to request.
+            Some(_) => (CheckStatus::MissingScope, config.scopes.first().copied()),
+            None if config.scopes.is_empty() => (CheckStatus::Ok, None),
+            None => (CheckStatus::Unknown, None),
+        }
+    })
+}
+
+fn fix_command(name: &str, profile: &Profile, missing: &[&str]) -> String {
+    let pkce_covers = missing.iter().all(|s| REQUIRED_USER_SCOPES.contains(s));
+    match profile.method {
+        AuthMethod::Pkce if pkce_covers => {
+            format!("slack-cli --profile {name} auth login --method pkce")
+        }
+        _ => format!(
+            "add the missing scopes to your Slack app, reinstall it, then run: \
+             slack-cli --profile {name} auth login --method static --user-token xoxp-..."
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::profile::{TokenSet, WorkspaceInfo};
+    use crate::auth::secret;
+
+    fn profile(method: AuthMethod, user: bool, bot: bool, scopes: &[&str]) -> Profile {
+        Profile {
+            method,
+            workspace: WorkspaceInfo {
+                team_id: "T1".into(),
+                team_name: "Acme".into(),
+                user_id: None,
+                enterprise: None,
+                is_enterprise_install: false,
+            },
+            tokens: TokenSet {
+                user: user.then(|| secret::new("xoxp")),
+                bot: bot.then(|| secret::new("xoxb")),
+            },
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            client_id: None,
+            authorized_at: chrono::Utc::now(),
+        }
+    }
+
+    fn status_of<'a>(report: &'a DoctorReport, command: &str) -> &'a CommandCheck {
+        report
+            .commands
+            .iter()
+            .find(|c| c.command == command)
+            .unwrap()
+    }
+
+    #[test]
+    fn every_command_method_has_api_config() {
+        for (command, methods) in COMMAND_METHODS {
+            for method in methods.iter().flat_map(|m| m.split('|')) {
+                assert!(
+                    get_api_config(method).is_some(),
+                    "{command} maps to unknown method {method}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn full_pkce_grant_needs_no_fix() {
+        let mut scopes = REQUIRED_USER_SCOPES.to_vec();
+        scopes.extend_from_slice(OPTIONAL_USER_SCOPES);
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Pkce, true, false, &scopes),
+            &TokenOverrides::default(),
+        );
+        assert!(report.commands.iter().all(|c| c.status == CheckStatus::Ok));
+        assert!(report.missing_scopes.is_empty());
+        assert!(report.fix.is_none());
+    }
+
+    #[test]
+    fn missing_optional_scopes_do_not_need_a_fix() {
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Pkce, true, false, REQUIRED_USER_SCOPES),
+            &TokenOverrides::default(),
+        );
+        let mark = status_of(&report, "inbox mark-read");
+        assert_eq!(mark.status, CheckStatus::MissingScope);
+        assert!(mark.optional);
+        assert!(
+            report
+                .commands
+                .iter()
+                .all(|c| c.status == CheckStatus::Ok || c.optional)
+        );
+        assert!(report.missing_scopes.is_empty());
+        assert!(report.fix.is_none());
+    }
+
+    #[test]
+    fn missing_scope_is_reported_with_relogin_command() {
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Pkce, true, false, &["users:read"]),
+            &TokenOverrides::default(),
+        );
+
+        assert_eq!(status_of(&report, "users").status, CheckStatus::Ok);
+        let send = status_of(&report, "send");
+        assert_eq!(send.status, CheckStatus::MissingScope);
+        assert_eq!(send.missing_scopes, vec!["chat:write"]);
+        assert!(report.missing_scopes.contains(&"chat:write"));
+        assert_eq!(
+            report.fix.as_deref(),
+            Some("slack-cli --profile acme auth login --method pkce")
+        );
+    }
+
+    #[test]
+    fn search_accepts_either_search_api_scope() {
+        for scopes in [&["search:read"][..], &["search:read.public"]] {
+            let report = diagnose(
+                "acme",
+                &profile(AuthMethod::Pkce, true, false, scopes),
+                &TokenOverrides::default(),
+            );
+            assert_eq!(status_of(&report, "search").status, CheckStatus::Ok);
+        }
+
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Pkce, true, false, &["users:read"]),
+            &TokenOverrides::default(),
+        );
+        let search = status_of(&report, "search");
+        assert_eq!(search.status, CheckStatus::MissingScope);
+        assert_eq!(search.missing_scopes, vec!["search:read"]);
+    }
+
+    #[test]
+    fn static_profile_scopes_are_unknown() {
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Static, true, false, &[]),
+            &TokenOverrides::default(),
+        );
+        assert!(!report.scopes_known);
+        assert_eq!(status_of(&report, "send").status, CheckStatus::Unknown);
+        assert_eq!(status_of(&report, "permalink").status, CheckStatus::Ok);
+        assert!(report.fix.is_none());
+    }
+
+    #[test]
+    fn bot_only_profile_cannot_run_user_required_commands() {
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Static, false, true, &[]),
+            &TokenOverrides::default(),
+        );
+        assert_eq!(
+            status_of(&report, "search").status,
+            CheckStatus::MissingToken
+        );
+        assert!(report.fix.unwrap().contains("--user-token"));
+    }
+
+    #[test]
+    fn forced_bot_token_reports_user_only_profile_as_missing_token() {
+        let overrides = TokenOverrides {
+            forced: Some(crate::auth::TokenKind::Bot),
+            ..Default::default()
+        };
+        let report = diagnose(
+            "acme",
+            &profile(AuthMethod::Pkce, true, false, REQUIRED_USER_SCOPES),
+            &overrides,
+        );
+        assert_eq!(status_of(&report, "send").status, CheckStatus::MissingToken);
+    }
+}
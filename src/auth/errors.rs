@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
-use super::policy::TokenPolicy;
+use super::policy::{TokenKind, TokenPolicy};
 
 #[derive(Debug, Error)]
 pub enum AuthError {
@@ -17,6 +17,13 @@ pub enum AuthError {
         policy: TokenPolicy,
     },
 
+    #[error(
+        "profile '{profile}' has no {kind} token. add one with: \
+         slack-cli --profile {profile} auth login --method static --{kind}-token ..., \
+         or pick the other token with --as"
+    )]
+    MissingTokenKind { profile: String, kind: TokenKind },
+
     #[error("failed to read auth store at {path}: {source}")]
     StoreRead {
         path: PathBuf,
@@ -7,7 +7,7 @@ use crate::auth::method::AuthMethod;
 use crate::auth::oauth::callback::LoopbackReceiver;
 use crate::auth::oauth::exchange::TokenExchange;
 use crate::auth::oauth::flow::{PkceRunOptions, run_pkce};
-use crate::auth::oauth::scopes::REQUIRED_USER_SCOPES;
+use crate::auth::oauth::scopes::requested_user_scopes;
 use crate::auth::profile::{Profile, TokenSet, WorkspaceInfo};
 
 pub struct Request {
@@ -15,6 +15,7 @@ pub struct Request {
     pub api_base_url: String,
     pub port: u16,
     pub no_browser: bool,
+    pub write_scopes: bool,
 }
 
 pub async fn run(request: Request) -> Result<Profile> {
@@ -33,6 +34,7 @@ pub async fn run(request: Request) -> Result<Profile> {
         PkceRunOptions {
             no_browser: request.no_browser,
             callback_timeout: Duration::from_secs(300),
+            write_scopes: request.write_scopes,
         },
     )
     .await?;
@@ -42,9 +44,9 @@ pub async fn run(request: Request) -> Result<Profile> {
         .ok_or_else(|| anyhow!("Slack did not return a user token"))?;
 
     let scopes = if response.scopes.is_empty() {
-        REQUIRED_USER_SCOPES
-            .iter()
-            .map(|s| (*s).to_string())
+        requested_user_scopes(request.write_scopes)
+            .into_iter()
+            .map(str::to_string)
             .collect()
     } else {
         response.scopes
@@ -56,6 +58,8 @@ pub async fn run(request: Request) -> Result<Profile> {
             team_id: response.team_id,
             team_name: response.team_name,
             user_id: response.user_id,
+            enterprise: response.enterprise,
+            is_enterprise_install: response.is_enterprise_install,
         },
         tokens: TokenSet {
             user: Some(user_token),
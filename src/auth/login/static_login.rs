@@ -3,7 +3,7 @@ use chrono::Utc;
 use secrecy::ExposeSecret;
 
 use crate::auth::method::AuthMethod;
-use crate::auth::profile::{Profile, TokenSet, WorkspaceInfo};
+use crate::auth::profile::{EnterpriseInfo, Profile, TokenSet, WorkspaceInfo};
 use crate::auth::secret::Secret;
 use crate::slack::SlackClient;
 
@@ -24,6 +24,11 @@ pub async fn run(
             team_id: identity.team_id,
             team_name: identity.team,
             user_id: Some(identity.user_id),
+            enterprise: identity.enterprise_id.map(|id| EnterpriseInfo {
+                name: identity.enterprise_name.unwrap_or_else(|| id.clone()),
+                id,
+            }),
+            is_enterprise_install: identity.is_enterprise_install.unwrap_or(false),
         },
         tokens: TokenSet {
             user: user_token,
@@ -2,6 +2,7 @@ pub mod cli_handler;
 pub mod oauth;
 
 pub(crate) mod authenticator;
+pub(crate) mod doctor;
 pub(crate) mod env;
 pub(crate) mod errors;
 pub(crate) mod login;
@@ -16,7 +17,7 @@ pub use authenticator::{AuthLoadOptions, Authenticator};
 pub use env::EnvOverrides;
 pub use errors::{AuthError, OAuthError};
 pub use method::AuthMethod;
-pub use policy::TokenPolicy;
+pub use policy::{TokenKind, TokenOverrides, TokenPolicy};
 
 use std::path::PathBuf;
 
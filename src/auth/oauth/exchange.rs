@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
 use crate::auth::errors::OAuthError;
+use crate::auth::profile::EnterpriseInfo;
 use crate::auth::secret::{self, Secret};
 
 pub struct TokenExchange {
@@ -15,6 +16,8 @@ pub struct TokenResponse {
     pub team_id: String,
     pub team_name: String,
     pub user_id: Option<String>,
+    pub enterprise: Option<EnterpriseInfo>,
+    pub is_enterprise_install: bool,
     pub scopes: Vec<String>,
 }
 
@@ -68,6 +71,10 @@ struct RawResponse {
     #[serde(default)]
     team: Option<TeamPart>,
     #[serde(default)]
+    enterprise: Option<TeamPart>,
+    #[serde(default)]
+    is_enterprise_install: bool,
+    #[serde(default)]
     authed_user: Option<AuthedUserPart>,
 }
 
@@ -97,7 +104,24 @@ impl RawResponse {
             ));
         }
 
-        let team = self.team.unwrap_or_default();
+        let enterprise = self.enterprise.and_then(|e| {
+            let id = e.id?;
+            Some(EnterpriseInfo {
+                name: e.name.unwrap_or_else(|| id.clone()),
+                id,
+            })
+        });
+
+        // Org-wide installs come back with `team: null`; the enterprise then
+        // stands in as the profile's team.
+        let team = match (self.team, &enterprise) {
+            (Some(team), _) if team.id.is_some() => team,
+            (_, Some(org)) if self.is_enterprise_install => TeamPart {
+                id: Some(org.id.clone()),
+                name: Some(org.name.clone()),
+            },
+            _ => TeamPart::default(),
+        };
         let team_id = team.id.ok_or(OAuthError::MissingField("team.id"))?;
         let team_name = team.name.unwrap_or_else(|| team_id.clone());
 
@@ -121,6 +145,8 @@ impl RawResponse {
             team_id,
             team_name,
             user_id: authed.id,
+            enterprise,
+            is_enterprise_install: self.is_enterprise_install,
             scopes,
         })
     }
@@ -162,6 +188,36 @@ mod tests {
         assert_eq!(resp.scopes, vec!["users:read", "chat:write"]);
     }
 
+    #[test]
+    fn org_wide_install_uses_enterprise_as_team() {
+        let r = raw(r#"{
+                "ok": true,
+                "team": null,
+                "enterprise": {"id": "E1", "name": "Acme Corp"},
+                "is_enterprise_install": true,
+                "authed_user": {"id": "U1", "access_token": "xoxp-org"}
+            }"#);
+        let resp = r.into_token_response().unwrap();
+        assert_eq!(resp.team_id, "E1");
+        assert_eq!(resp.team_name, "Acme Corp");
+        assert!(resp.is_enterprise_install);
+        assert_eq!(resp.enterprise.unwrap().id, "E1");
+    }
+
+    #[test]
+    fn workspace_install_in_org_keeps_team_and_records_enterprise() {
+        let r = raw(r#"{
+                "ok": true,
+                "team": {"id": "T1", "name": "Acme EU"},
+                "enterprise": {"id": "E1", "name": "Acme Corp"},
+                "authed_user": {"id": "U1", "access_token": "xoxp-test"}
+            }"#);
+        let resp = r.into_token_response().unwrap();
+        assert_eq!(resp.team_id, "T1");
+        assert!(!resp.is_enterprise_install);
+        assert_eq!(resp.enterprise.unwrap().name, "Acme Corp");
+    }
+
     #[test]
     fn surfaces_error_field() {
         let r = raw(r#"{"ok": false, "error": "invalid_code"}"#);
@@ -9,13 +9,15 @@ use super::browser::BrowserOpener;
 use super::callback::LoopbackReceiver;
 use super::exchange::{ExchangeRequest, TokenExchange, TokenResponse};
 use super::pkce::PkceVerifier;
-use super::scopes::REQUIRED_USER_SCOPES;
+use super::scopes::requested_user_scopes;
 
 const AUTHORIZE_URL: &str = "https://slack.com/oauth/v2/authorize";
 
 pub struct PkceRunOptions {
     pub no_browser: bool,
     pub callback_timeout: Duration,
+    /// Also request `OPTIONAL_USER_SCOPES`.
+    pub write_scopes: bool,
 }
 
 pub async fn run_pkce(
@@ -44,6 +46,7 @@ pub async fn run_pkce_authorized(
         &redirect_uri,
         challenge.as_str(),
         &expected_state,
+        options.write_scopes,
     );
 
     let browser = if options.no_browser {
@@ -72,11 +75,17 @@ pub async fn run_pkce_authorized(
         .await
 }
 
-fn authorize_url(client_id: &str, redirect_uri: &str, challenge: &str, state: &str) -> Url {
+fn authorize_url(
+    client_id: &str,
+    redirect_uri: &str,
+    challenge: &str,
+    state: &str,
+    write_scopes: bool,
+) -> Url {
     let mut url = Url::parse(AUTHORIZE_URL).expect("static URL");
     url.query_pairs_mut()
         .append_pair("client_id", client_id)
-        .append_pair("user_scope", &REQUIRED_USER_SCOPES.join(","))
+        .append_pair("user_scope", &requested_user_scopes(write_scopes).join(","))
         .append_pair("redirect_uri", redirect_uri)
         .append_pair("code_challenge", challenge)
         .append_pair("code_challenge_method", "S256")
@@ -23,5 +23,22 @@ pub const REQUIRED_USER_SCOPES: &[&str] = &[
     "bookmarks:read",
     "bookmarks:write",
     "emoji:read",
+    "reminders:read",
+    "reminders:write",
     "search:read",
 ];
+
+/// Conversation write scopes, requested only with `auth login --write-scopes`.
+/// `channels:write` also allows archiving, renaming and kicking, so they stay
+/// out of the default grant; today only `inbox mark-read` needs them.
+pub const OPTIONAL_USER_SCOPES: &[&str] =
+    &["channels:write", "groups:write", "mpim:write", "im:write"];
+
+/// The user scopes `auth login --method pkce` asks for.
+pub fn requested_user_scopes(write_scopes: bool) -> Vec<&'static str> {
+    let mut scopes = REQUIRED_USER_SCOPES.to_vec();
+    if write_scopes {
+        scopes.extend_from_slice(OPTIONAL_USER_SCOPES);
+    }
+    scopes
+}
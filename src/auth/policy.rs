@@ -1,5 +1,8 @@
+use std::collections::HashMap;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use super::secret::Secret;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -7,6 +10,7 @@ pub enum TokenPolicy {
     BotPreferred,
     UserPreferred,
     UserRequired,
+    BotRequired,
 }
 
 impl TokenPolicy {
@@ -15,6 +19,7 @@ impl TokenPolicy {
             Self::BotPreferred => "bot_preferred",
             Self::UserPreferred => "user_preferred",
             Self::UserRequired => "user_required",
+            Self::BotRequired => "bot_required",
         }
     }
 
@@ -23,6 +28,7 @@ impl TokenPolicy {
     pub fn pick(&self, user: Option<Secret>, bot: Option<Secret>) -> Option<Secret> {
         match self {
             Self::UserRequired => user,
+            Self::BotRequired => bot,
             Self::UserPreferred => user.or(bot),
             Self::BotPreferred => bot.or(user),
         }
@@ -34,3 +40,100 @@ impl fmt::Display for TokenPolicy {
         f.write_str(self.as_str())
     }
 }
+
+/// Token type a caller asks for explicitly, via `--as` or a per-method entry
+/// in `[auth.method_tokens]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    User,
+    Bot,
+}
+
+impl TokenKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Bot => "bot",
+        }
+    }
+
+    pub fn policy(self) -> TokenPolicy {
+        match self {
+            Self::User => TokenPolicy::UserRequired,
+            Self::Bot => TokenPolicy::BotRequired,
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Replaces the per-method default `TokenPolicy` from `API_CONFIGS`.
+/// `forced` (the global `--as` flag) wins over `per_method` (config.toml).
+#[derive(Debug, Clone, Default)]
+pub struct TokenOverrides {
+    pub forced: Option<TokenKind>,
+    pub per_method: HashMap<String, TokenKind>,
+}
+
+impl TokenOverrides {
+    pub fn policy_for(&self, method: &str, default: TokenPolicy) -> TokenPolicy {
+        self.forced
+            .or_else(|| self.per_method.get(method).copied())
+            .map(TokenKind::policy)
+            .unwrap_or(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(forced: Option<TokenKind>, per_method: &[(&str, TokenKind)]) -> TokenOverrides {
+        TokenOverrides {
+            forced,
+            per_method: per_method
+                .iter()
+                .map(|(m, k)| (m.to_string(), *k))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn default_policy_applies_without_overrides() {
+        let o = TokenOverrides::default();
+        assert_eq!(
+            o.policy_for("chat.postMessage", TokenPolicy::BotPreferred),
+            TokenPolicy::BotPreferred
+        );
+    }
+
+    #[test]
+    fn per_method_override_only_touches_its_method() {
+        let o = overrides(None, &[("chat.postMessage", TokenKind::User)]);
+        assert_eq!(
+            o.policy_for("chat.postMessage", TokenPolicy::BotPreferred),
+            TokenPolicy::UserRequired
+        );
+        assert_eq!(
+            o.policy_for("chat.update", TokenPolicy::BotPreferred),
+            TokenPolicy::BotPreferred
+        );
+    }
+
+    #[test]
+    fn forced_kind_wins_over_per_method() {
+        let o = overrides(
+            Some(TokenKind::Bot),
+            &[("chat.postMessage", TokenKind::User)],
+        );
+        assert_eq!(
+            o.policy_for("chat.postMessage", TokenPolicy::UserPreferred),
+            TokenPolicy::BotRequired
+        );
+    }
+}
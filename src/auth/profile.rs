@@ -39,10 +39,29 @@ pub struct WorkspaceInfo {
     pub team_name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_id: Option<String>,
+    /// Enterprise Grid org the workspace belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<EnterpriseInfo>,
+    /// Org-wide install: the token spans every workspace in `enterprise`,
+    /// and `team_id` holds the enterprise id.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_enterprise_install: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnterpriseInfo {
+    pub id: String,
+    pub name: String,
 }
 
 impl Profile {
     pub fn label(&self) -> String {
-        format!("{} ({})", self.workspace.team_name, self.workspace.team_id)
+        match &self.workspace.enterprise {
+            Some(org) if !self.workspace.is_enterprise_install => format!(
+                "{} ({}) in {} ({})",
+                self.workspace.team_name, self.workspace.team_id, org.name, org.id
+            ),
+            _ => format!("{} ({})", self.workspace.team_name, self.workspace.team_id),
+        }
     }
 }
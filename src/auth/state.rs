@@ -55,6 +55,8 @@ mod tests {
                 team_id: team.into(),
                 team_name: team.into(),
                 user_id: None,
+                enterprise: None,
+                is_enterprise_install: false,
             },
             tokens: TokenSet::default(),
             scopes: vec![],
@@ -156,6 +156,8 @@ mod tests {
                 team_id: "T1".into(),
                 team_name: "Acme".into(),
                 user_id: Some("U1".into()),
+                enterprise: None,
+                is_enterprise_install: false,
             },
             tokens: TokenSet {
                 user: Some(secret::new("xoxp-test-1234")),
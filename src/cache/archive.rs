@@ -0,0 +1,466 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::CacheResult;
+use super::sqlite_cache::SqliteCache;
+use crate::slack::types::SlackMessage;
+use rusqlite::{OptionalExtension, Transaction, params};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Slack's stand-in for a deleted thread parent that still has replies.
+const TOMBSTONE_SUBTYPE: &str = "tombstone";
+
+/// What archiving one page changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ArchiveSummary {
+    pub new: usize,
+    pub edited: usize,
+    pub deleted: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageRevision {
+    pub revision: i64,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Value>,
+    /// Slack's `edited.ts` when this version was seen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edited_ts: Option<String>,
+    pub seen_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageHistory {
+    pub channel_id: String,
+    pub ts: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_ts: Option<String>,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// When a sync first noticed the message was gone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<i64>,
+    /// Oldest first.
+    pub revisions: Vec<MessageRevision>,
+}
+
+enum Stored {
+    New,
+    Edited,
+    Unchanged,
+}
+
+impl SqliteCache {
+    /// Records one page of `conversations.history` (`thread: None`) or of
+    /// `conversations.replies` for `thread`. A changed `text` or `blocks`
+    /// adds a revision. An archived message whose ts falls inside the page
+    /// but is missing from it, or comes back as a tombstone, is marked
+    /// deleted.
+    pub fn archive_messages(
+        &self,
+        channel_id: &str,
+        thread: Option<&str>,
+        messages: &[SlackMessage],
+    ) -> CacheResult<ArchiveSummary> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let conn = self.pool.get()?;
+        let tx = conn.unchecked_transaction()?;
+
+        let mut summary = ArchiveSummary::default();
+        for message in messages {
+            if message.subtype.as_deref() == Some(TOMBSTONE_SUBTYPE) {
+                summary.deleted += mark_deleted(&tx, channel_id, &message.ts, now)?;
+                continue;
+            }
+            match store_message(&tx, channel_id, message, now)? {
+                Stored::New => summary.new += 1,
+                Stored::Edited => summary.edited += 1,
+                Stored::Unchanged => {}
+            }
+        }
+        summary.deleted += mark_missing(&tx, channel_id, thread, messages, now)?;
+
+        tx.commit()?;
+        Ok(summary)
+    }
+
+    pub fn message_history(
+        &self,
+        channel_id: &str,
+        ts: &str,
+    ) -> CacheResult<Option<MessageHistory>> {
+        let conn = self.pool.get()?;
+        let Some((thread_ts, first_seen, last_seen, deleted_at)) = conn
+            .query_row(
+                "SELECT thread_ts, first_seen, last_seen, deleted_at
+                 FROM archived_messages WHERE channel_id = ?1 AND ts = ?2",
+                params![channel_id, ts],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT revision, text, blocks, edited_ts, seen_at FROM message_revisions
+             WHERE channel_id = ?1 AND ts = ?2 ORDER BY revision",
+        )?;
+        let rows = stmt
+            .query_map(params![channel_id, ts], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let revisions = rows
+            .into_iter()
+            .map(|(revision, text, blocks, edited_ts, seen_at)| {
+                Ok(MessageRevision {
+                    revision,
+                    text,
+                    blocks: blocks.as_deref().map(serde_json::from_str).transpose()?,
+                    edited_ts,
+                    seen_at,
+                })
+            })
+            .collect::<CacheResult<_>>()?;
+
+        Ok(Some(MessageHistory {
+            channel_id: channel_id.to_string(),
+            ts: ts.to_string(),
+            thread_ts,
+            first_seen,
+            last_seen,
+            deleted_at,
+            revisions,
+        }))
+    }
+
+    /// Archived messages of `channel_id` from `oldest` (a Slack ts) on,
+    /// deleted ones left out: top-level messages oldest first, with archived
+    /// thread replies nested under their parent's `replies`.
+    pub fn archived_messages(
+        &self,
+        channel_id: &str,
+        oldest: &str,
+    ) -> CacheResult<Vec<SlackMessage>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT message FROM archived_messages
+             WHERE channel_id = ?1 AND ts >= ?2 AND deleted_at IS NULL ORDER BY ts",
+        )?;
+        let rows = stmt
+            .query_map(params![channel_id, oldest], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut top_level: Vec<SlackMessage> = Vec::new();
+        let mut replies: HashMap<String, Vec<SlackMessage>> = HashMap::new();
+        for raw in rows {
+            let message: SlackMessage = serde_json::from_str(&raw)?;
+            match message.thread_ts.clone() {
+                Some(thread_ts) if thread_ts != message.ts => {
+                    replies.entry(thread_ts).or_default().push(message)
+                }
+                _ => top_level.push(message),
+            }
+        }
+        for parent in &mut top_level {
+            parent.replies = replies.remove(&parent.ts);
+        }
+        Ok(top_level)
+    }
+}
+
+fn store_message(
+    tx: &Transaction<'_>,
+    channel_id: &str,
+    message: &SlackMessage,
+    now: i64,
+) -> CacheResult<Stored> {
+    let blocks = message
+        .blocks
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
+    tx.execute(
+        "INSERT INTO archived_messages (channel_id, ts, thread_ts, message, first_seen, last_seen)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+         ON CONFLICT(channel_id, ts) DO UPDATE SET
+             thread_ts = excluded.thread_ts,
+             message = excluded.message,
+             last_seen = excluded.last_seen,
+             deleted_at = NULL",
+        params![
+            channel_id,
+            message.ts,
+            message.thread_ts,
+            serde_json::to_string(message)?,
+            now
+        ],
+    )?;
+
+    let latest: Option<(String, Option<String>)> = tx
+        .query_row(
+            "SELECT text, blocks FROM message_revisions
+             WHERE channel_id = ?1 AND ts = ?2 ORDER BY revision DESC LIMIT 1",
+            params![channel_id, message.ts],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let stored = match latest {
+        None => Stored::New,
+        Some((text, old_blocks)) if text == message.text && old_blocks == blocks => {
+            return Ok(Stored::Unchanged);
+        }
+        Some(_) => Stored::Edited,
+    };
+
+    tx.execute(
+        "INSERT INTO message_revisions (channel_id, ts, revision, text, blocks, edited_ts, seen_at)
+         VALUES (?1, ?2,
+                 (SELECT COALESCE(MAX(revision), 0) + 1 FROM message_revisions
+                  WHERE channel_id = ?1 AND ts = ?2),
+                 ?3, ?4, ?5, ?6)",
+        params![
+            channel_id,
+            message.ts,
+            message.text,
+            blocks,
+            message.edited.as_ref().map(|e| &e.ts),
+            now
+        ],
+    )?;
+    Ok(stored)
+}
+
+fn mark_deleted(tx: &Transaction<'_>, channel_id: &str, ts: &str, now: i64) -> CacheResult<usize> {
+    Ok(tx.execute(
+        "UPDATE archived_messages SET deleted_at = ?3
+         WHERE channel_id = ?1 AND ts = ?2 AND deleted_at IS NULL",
+        params![channel_id, ts, now],
+    )?)
+}
+
+/// A page lists every surviving message between its oldest and newest ts,
+/// so archived ones in that span that it lacks were deleted. History pages
+/// only vouch for top-level messages; reply pages for their own thread.
+fn mark_missing(
+    tx: &Transaction<'_>,
+    channel_id: &str,
+    thread: Option<&str>,
+    messages: &[SlackMessage],
+    now: i64,
+) -> CacheResult<usize> {
+    let seen: HashSet<&str> = messages.iter().map(|m| m.ts.as_str()).collect();
+    let (Some(oldest), Some(newest)) = (seen.iter().min(), seen.iter().max()) else {
+        return Ok(0);
+    };
+
+    let candidates: Vec<String> = match thread {
+        None => {
+            let mut stmt = tx.prepare(
+                "SELECT ts FROM archived_messages
+                 WHERE channel_id = ?1 AND ts BETWEEN ?2 AND ?3 AND deleted_at IS NULL
+                   AND (thread_ts IS NULL OR thread_ts = ts)",
+            )?;
+            stmt.query_map(params![channel_id, oldest, newest], |row| row.get(0))?
+                .collect::<Result<_, _>>()?
+        }
+        Some(thread_ts) => {
+            let mut stmt = tx.prepare(
+                "SELECT ts FROM archived_messages
+                 WHERE channel_id = ?1 AND ts BETWEEN ?2 AND ?3 AND deleted_at IS NULL
+                   AND thread_ts = ?4",
+            )?;
+            stmt.query_map(params![channel_id, oldest, newest, thread_ts], |row| {
+                row.get(0)
+            })?
+            .collect::<Result<_, _>>()?
+        }
+    };
+
+    let mut deleted = 0;
+    for ts in candidates.iter().filter(|ts| !seen.contains(ts.as_str())) {
+        deleted += mark_deleted(tx, channel_id, ts, now)?;
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(ts: &str, text: &str) -> SlackMessage {
+        serde_json::from_value(json!({"ts": ts, "text": text})).unwrap()
+    }
+
+    fn reply(ts: &str, thread_ts: &str, text: &str) -> SlackMessage {
+        serde_json::from_value(json!({"ts": ts, "thread_ts": thread_ts, "text": text})).unwrap()
+    }
+
+    #[test]
+    fn adds_a_revision_only_when_text_or_blocks_change() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        let first = [
+            message("1.000001", "deploy at 3"),
+            message("2.000001", "ok"),
+        ];
+        let summary = cache.archive_messages("C1", None, &first).unwrap();
+        assert_eq!(summary.new, 2);
+
+        let again = cache.archive_messages("C1", None, &first).unwrap();
+        assert_eq!(again, ArchiveSummary::default());
+
+        let mut edited = message("1.000001", "deploy at 4");
+        edited.edited = serde_json::from_value(json!({"user": "U1", "ts": "3.000001"})).unwrap();
+        let mut with_blocks = message("2.000001", "ok");
+        with_blocks.blocks = Some(vec![json!({"type": "divider"})]);
+        let summary = cache
+            .archive_messages("C1", None, &[edited, with_blocks])
+            .unwrap();
+        assert_eq!(summary.edited, 2);
+
+        let history = cache.message_history("C1", "1.000001").unwrap().unwrap();
+        let texts: Vec<_> = history.revisions.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, ["deploy at 3", "deploy at 4"]);
+        assert_eq!(history.revisions[1].edited_ts.as_deref(), Some("3.000001"));
+        assert!(history.deleted_at.is_none());
+        assert!(cache.message_history("C1", "9.000001").unwrap().is_none());
+    }
+
+    #[test]
+    fn archived_messages_nest_replies_and_skip_deleted_ones() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache
+            .archive_messages(
+                "C1",
+                None,
+                &[
+                    message("1.000001", "old"),
+                    message("5.000001", "q"),
+                    message("7.000001", "gone"),
+                    message("9.000001", "last"),
+                ],
+            )
+            .unwrap();
+        cache
+            .archive_messages(
+                "C1",
+                Some("5.000001"),
+                &[
+                    reply("5.000001", "5.000001", "q"),
+                    reply("6.000001", "5.000001", "a"),
+                ],
+            )
+            .unwrap();
+        cache
+            .archive_messages(
+                "C1",
+                None,
+                &[message("5.000001", "q"), message("9.000001", "last")],
+            )
+            .unwrap();
+
+        let messages = cache.archived_messages("C1", "2").unwrap();
+        let ts: Vec<_> = messages.iter().map(|m| m.ts.as_str()).collect();
+        assert_eq!(ts, ["5.000001", "9.000001"]);
+        let replies = messages[0].replies.as_ref().unwrap();
+        assert_eq!(replies[0].text, "a");
+        assert!(messages[1].replies.is_none());
+    }
+
+    #[test]
+    fn marks_messages_missing_inside_the_page_span_as_deleted() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        let page = [
+            message("1.000001", "a"),
+            message("2.000001", "b"),
+            message("3.000001", "c"),
+            message("4.000001", "d"),
+        ];
+        cache.archive_messages("C1", None, &page).unwrap();
+
+        // A newer page that only overlaps 3..4 says nothing about 1 and 2.
+        let summary = cache
+            .archive_messages(
+                "C1",
+                None,
+                &[message("3.000001", "c"), message("5.000001", "e")],
+            )
+            .unwrap();
+        assert_eq!(summary.deleted, 1);
+        let deleted = |ts| {
+            cache
+                .message_history("C1", ts)
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_some()
+        };
+        assert!(deleted("4.000001"));
+        assert!(!deleted("1.000001") && !deleted("2.000001"));
+
+        // Seen again, it was not deleted after all.
+        cache
+            .archive_messages("C1", None, &[message("4.000001", "d")])
+            .unwrap();
+        assert!(!deleted("4.000001"));
+    }
+
+    #[test]
+    fn thread_pages_and_tombstones_only_touch_their_thread() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache
+            .archive_messages("C1", None, &[message("1.000001", "top")])
+            .unwrap();
+        cache
+            .archive_messages(
+                "C1",
+                Some("1.000001"),
+                &[
+                    reply("1.000001", "1.000001", "parent"),
+                    reply("1.500001", "1.000001", "first"),
+                    reply("1.600001", "1.000001", "second"),
+                ],
+            )
+            .unwrap();
+        cache
+            .archive_messages("C1", None, &[message("1.550001", "unrelated")])
+            .unwrap();
+
+        // History pages never mark replies deleted.
+        let reply_history = cache.message_history("C1", "1.500001").unwrap().unwrap();
+        assert!(reply_history.deleted_at.is_none());
+
+        let mut tombstone = reply("1.000001", "1.000001", "This message was deleted.");
+        tombstone.subtype = Some("tombstone".into());
+        let summary = cache
+            .archive_messages(
+                "C1",
+                Some("1.000001"),
+                &[tombstone, reply("1.600001", "1.000001", "second")],
+            )
+            .unwrap();
+        assert_eq!(summary.deleted, 2);
+
+        let parent = cache.message_history("C1", "1.000001").unwrap().unwrap();
+        assert!(parent.deleted_at.is_some());
+        assert_eq!(parent.revisions.last().unwrap().text, "parent");
+        assert!(
+            cache
+                .message_history("C1", "1.550001")
+                .unwrap()
+                .unwrap()
+                .deleted_at
+                .is_none()
+        );
+    }
+}
@@ -1,4 +1,7 @@
+use super::constants::FUZZY_SCAN_LIMIT;
 use super::error::{CacheError, CacheResult};
+use super::hangul;
+use super::helpers::{SyncStats, sync_from_staging};
 use super::sqlite_cache::SqliteCache;
 use crate::slack::types::SlackChannel;
 use chrono::Utc;
@@ -7,7 +10,9 @@ use rusqlite::params;
 use rusqlite::OptionalExtension;
 
 impl SqliteCache {
-    pub async fn save_channels(&self, channels: Vec<SlackChannel>) -> CacheResult<()> {
+    /// Replaces the cached channels with `channels`, writing only the rows that
+    /// were added, changed or removed.
+    pub async fn save_channels(&self, channels: Vec<SlackChannel>) -> CacheResult<SyncStats> {
         if channels.is_empty() {
             return Err(CacheError::InvalidInput("No channels to save".to_string()));
         }
@@ -16,7 +21,10 @@ impl SqliteCache {
             .await
     }
 
-    pub(super) fn save_channels_internal(&self, channels: Vec<SlackChannel>) -> CacheResult<()> {
+    pub(super) fn save_channels_internal(
+        &self,
+        channels: Vec<SlackChannel>,
+    ) -> CacheResult<SyncStats> {
         if channels.is_empty() {
             return Err(CacheError::InvalidInput("No channels to save".to_string()));
         }
@@ -52,11 +60,7 @@ impl SqliteCache {
             ));
         }
 
-        tx.execute("DELETE FROM channels", [])?;
-        tx.execute(
-            "INSERT INTO channels (id, data, updated_at) SELECT id, data, updated_at FROM channels_new",
-            [],
-        )?;
+        let stats = sync_from_staging(&tx, "channels", "channels_new")?;
         tx.execute("DELETE FROM channels_new", [])?;
 
         let now = Utc::now().timestamp();
@@ -66,10 +70,9 @@ impl SqliteCache {
         )?;
 
         tx.commit()?;
-        Ok(())
+        Ok(stats)
     }
 
-    #[cfg(test)]
     pub fn get_channels(&self) -> CacheResult<Vec<SlackChannel>> {
         let conn = self.pool.get()?;
         let mut stmt = conn.prepare_cached(
@@ -163,6 +166,28 @@ impl SqliteCache {
             return Ok(channels);
         }
 
+        if hangul::is_choseong_query(query) {
+            let mut stmt = conn.prepare_cached(
+                "SELECT data FROM channels
+                 WHERE name IS NOT NULL AND (is_archived = 0 OR is_archived IS NULL)
+                 ORDER BY name LIMIT ?1",
+            )?;
+            let channels = stmt
+                .query_map([FUZZY_SCAN_LIMIT], |row| row.get::<_, String>(0))?
+                .map(|json| Ok(serde_json::from_str::<SlackChannel>(&json?)?))
+                // Errors pass the filter so `collect` surfaces them.
+                .filter(|c: &CacheResult<SlackChannel>| {
+                    c.as_ref().map_or(true, |c| {
+                        c.name
+                            .as_deref()
+                            .is_some_and(|name| hangul::choseong_matches(query, name))
+                    })
+                })
+                .take(limit)
+                .collect::<CacheResult<Vec<_>>>()?;
+            return Ok(channels);
+        }
+
         let like_pattern = format!("%{query}%");
         let like_result = conn
             .prepare_cached(
@@ -231,6 +256,7 @@ impl SqliteCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::FtsTokenizer;
     use rstest::rstest;
 
     fn create_test_channel(
@@ -253,6 +279,8 @@ mod tests {
             is_archived,
             is_general: name == "general",
             is_member: true,
+            is_ext_shared: false,
+            is_org_shared: false,
             created: None,
             creator: None,
             topic: None,
@@ -274,6 +302,8 @@ mod tests {
             is_archived: false,
             is_general: false,
             is_member: true,
+            is_ext_shared: false,
+            is_org_shared: false,
             created: None,
             creator: None,
             topic: None,
@@ -355,6 +385,40 @@ mod tests {
         assert!(all_channels.iter().all(|c| c.id != "C456"));
     }
 
+    #[tokio::test]
+    async fn test_save_channels_reports_sync_stats() {
+        let cache = setup_cache().await;
+        let first = cache
+            .save_channels(vec![
+                create_test_channel("C1", "general", false, false, false, false),
+                create_test_channel("C2", "random", false, false, false, false),
+                create_test_channel("C3", "deploys", false, false, false, false),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(first.added, 3);
+
+        let stats = cache
+            .save_channels(vec![
+                create_test_channel("C1", "general", false, false, false, false),
+                create_test_channel("C2", "random", false, true, false, false),
+                create_test_channel("C4", "incidents", false, false, false, false),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            SyncStats {
+                added: 1,
+                changed: 1,
+                removed: 1,
+                unchanged: 1
+            }
+        );
+        assert_eq!(cache.search_channels("deploys", 10).unwrap().len(), 0);
+        assert_eq!(cache.search_channels("incidents", 10).unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_channels_filters_archived() {
         let cache = setup_cache().await;
@@ -681,4 +745,74 @@ mod tests {
 
         assert!(cache.find_dm_by_user("U_anyone").unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_search_channels_by_choseong() {
+        let cache = setup_cache().await;
+        cache
+            .save_channels(vec![
+                create_test_channel("C1", "배포-알림", false, false, false, false),
+                create_test_channel("C2", "release-배포", false, false, false, false),
+                create_test_channel("C3", "general", false, false, false, false),
+            ])
+            .await
+            .unwrap();
+
+        let ids: Vec<String> = cache
+            .search_channels("ㅂㅍ", 10)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, ["C2", "C1"]); // ordered by name
+    }
+
+    #[tokio::test]
+    async fn test_search_channels_by_choseong_surfaces_corrupt_rows() {
+        let cache = setup_cache().await;
+        cache
+            .save_channels(vec![create_test_channel(
+                "C1",
+                "배포-알림",
+                false,
+                false,
+                false,
+                false,
+            )])
+            .await
+            .unwrap();
+        cache
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                r#"UPDATE channels SET data = '{"id": 1, "name": "배포-알림"}'"#,
+                [],
+            )
+            .unwrap();
+
+        assert!(cache.search_channels("ㅂㅍ", 10).is_err());
+    }
+
+    #[rstest]
+    #[case::unicode61(FtsTokenizer::Unicode61, 0)]
+    #[case::trigram(FtsTokenizer::Trigram, 1)]
+    #[tokio::test]
+    async fn test_topic_substring_depends_on_tokenizer(
+        #[case] tokenizer: FtsTokenizer,
+        #[case] expected: usize,
+    ) {
+        let cache = SqliteCache::open(":memory:", tokenizer).await.unwrap();
+        let mut channel = create_test_channel("C1", "ops", false, false, false, false);
+        channel.topic = Some(crate::slack::types::ChannelTopic {
+            value: "프로덕션배포 일정 and deploys".to_string(),
+            creator: "U1".to_string(),
+            last_set: 0,
+        });
+        cache.save_channels(vec![channel]).await.unwrap();
+
+        assert_eq!(cache.search_channels("덕션배", 10).unwrap().len(), expected);
+        // Latin words keep matching under either tokenizer.
+        assert_eq!(cache.search_channels("deploys", 10).unwrap().len(), 1);
+    }
 }
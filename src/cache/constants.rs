@@ -1,2 +1,15 @@
 pub const LOCK_TIMEOUT_SECS: i64 = 300;
 pub const STALE_LOCK_THRESHOLD_SECS: i64 = 600;
+/// Held by the detached `cache refresh --background` process so concurrent
+/// commands spawn at most one refresh.
+pub const BACKGROUND_REFRESH_LOCK: &str = "background_refresh";
+/// Lowest similarity (0..1) a fuzzy user/channel match must reach.
+pub const FUZZY_MIN_SCORE: f64 = 0.6;
+/// Rows the trigram index hands to fuzzy scoring, best FTS rank first.
+pub const FUZZY_CANDIDATES: i64 = 200;
+/// Rows scanned when the query is too short for the trigram index or is a
+/// choseong query (fuzzy scoring and choseong search alike), so even those
+/// stay bounded on large workspaces.
+pub const FUZZY_SCAN_LIMIT: i64 = 5000;
+/// Rows kept in `search_history`; older entries are pruned on insert.
+pub const SEARCH_HISTORY_RETENTION: i64 = 500;
@@ -27,6 +27,10 @@ pub enum CacheError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// Snapshot file is not importable into this cache
+    #[error("Cannot import snapshot: {0}")]
+    SnapshotMismatch(String),
+
     /// IO error during cache operations
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
@@ -0,0 +1,425 @@
+//! Typo-tolerant ranking for user and channel lookups. The `users_fuzzy`
+//! and `channels_fuzzy` trigram indexes pick rows sharing any trigram with
+//! the query; those candidates are scored in Rust with a mix of edit
+//! distance (optimal string alignment, so `jonh` is one edit from `john`)
+//! and trigram overlap, which catches romanization variants that differ in
+//! several scattered places. Queries too short to share a trigram with a
+//! typo'd name score a bounded scan instead.
+
+use std::collections::HashSet;
+
+use rusqlite::params;
+use serde::Serialize;
+
+use super::constants::{FUZZY_CANDIDATES, FUZZY_MIN_SCORE, FUZZY_SCAN_LIMIT};
+use super::error::CacheResult;
+use super::hangul;
+use super::sqlite_cache::SqliteCache;
+use crate::slack::types::{SlackChannel, SlackUser};
+
+/// A lookup result with its similarity to the query, in `0.0..=1.0`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ranked<T> {
+    #[serde(flatten)]
+    pub item: T,
+    pub score: f64,
+}
+
+impl SqliteCache {
+    /// `search_users`, scored; misspellings that match nothing there fall
+    /// back to `fuzzy_users`.
+    pub fn rank_users(
+        &self,
+        query: &str,
+        limit: usize,
+        include_bots: bool,
+    ) -> CacheResult<Vec<Ranked<SlackUser>>> {
+        let users = self.search_users(query, limit, include_bots)?;
+        if users.is_empty() && !query.trim().is_empty() {
+            return self.fuzzy_users(query, limit, include_bots);
+        }
+        Ok(users
+            .into_iter()
+            .map(|user| Ranked {
+                score: user_score(query, &user),
+                item: user,
+            })
+            .collect())
+    }
+
+    pub fn rank_channels(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> CacheResult<Vec<Ranked<SlackChannel>>> {
+        let channels = self.search_channels(query, limit)?;
+        if channels.is_empty() && !query.trim().is_empty() {
+            return self.fuzzy_channels(query, limit);
+        }
+        Ok(channels
+            .into_iter()
+            .map(|channel| Ranked {
+                score: similarity(query, channel.name.as_deref().unwrap_or_default()),
+                item: channel,
+            })
+            .collect())
+    }
+
+    /// Best matches scoring at least `FUZZY_MIN_SCORE` across name, display
+    /// name and real name, highest first.
+    pub fn fuzzy_users(
+        &self,
+        query: &str,
+        limit: usize,
+        include_bots: bool,
+    ) -> CacheResult<Vec<Ranked<SlackUser>>> {
+        let conn = self.pool.get()?;
+        let rows = match trigram_match(query) {
+            Some(pattern) => conn
+                .prepare_cached(
+                    "SELECT u.data FROM users_fuzzy f JOIN users u ON u.rowid = f.rowid
+                     WHERE users_fuzzy MATCH ?1 AND (?2 OR u.is_bot = 0 OR u.is_bot IS NULL)
+                     ORDER BY f.rank LIMIT ?3",
+                )?
+                .query_map(params![pattern, include_bots, FUZZY_CANDIDATES], |row| {
+                    row.get::<_, String>(0)
+                })?
+                .collect::<Result<Vec<_>, _>>()?,
+            None => conn
+                .prepare_cached(
+                    "SELECT data FROM users WHERE ?1 OR is_bot = 0 OR is_bot IS NULL
+                     ORDER BY name LIMIT ?2",
+                )?
+                .query_map(params![include_bots, FUZZY_SCAN_LIMIT], |row| {
+                    row.get::<_, String>(0)
+                })?
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let users = rows
+            .iter()
+            .map(|json| serde_json::from_str::<SlackUser>(json))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(top_ranked(
+            users.into_iter().map(|user| Ranked {
+                score: user_score(query, &user),
+                item: user,
+            }),
+            limit,
+        ))
+    }
+
+    /// Unarchived channels whose name scores at least `FUZZY_MIN_SCORE`,
+    /// highest first. Also feeds "did you mean" hints.
+    pub fn fuzzy_channels(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> CacheResult<Vec<Ranked<SlackChannel>>> {
+        let conn = self.pool.get()?;
+        let rows = match trigram_match(query) {
+            Some(pattern) => conn
+                .prepare_cached(
+                    "SELECT c.data FROM channels_fuzzy f JOIN channels c ON c.rowid = f.rowid
+                     WHERE channels_fuzzy MATCH ?1
+                       AND (c.is_archived = 0 OR c.is_archived IS NULL)
+                     ORDER BY f.rank LIMIT ?2",
+                )?
+                .query_map(params![pattern, FUZZY_CANDIDATES], |row| {
+                    row.get::<_, String>(0)
+                })?
+                .collect::<Result<Vec<_>, _>>()?,
+            None => conn
+                .prepare_cached(
+                    "SELECT data FROM channels
+                     WHERE name IS NOT NULL AND (is_archived = 0 OR is_archived IS NULL)
+                     ORDER BY name LIMIT ?1",
+                )?
+                .query_map(params![FUZZY_SCAN_LIMIT], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let channels = rows
+            .iter()
+            .map(|json| serde_json::from_str::<SlackChannel>(json))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(top_ranked(
+            channels.into_iter().map(|channel| Ranked {
+                score: similarity(query, channel.name.as_deref().unwrap_or_default()),
+                item: channel,
+            }),
+            limit,
+        ))
+    }
+}
+
+/// Trigrams a query needs before the index can stand in for a scan: a
+/// four-letter typo like `jhon` shares no trigram with `john`.
+const MIN_QUERY_TRIGRAMS: usize = 3;
+
+/// An FTS5 query OR-ing the query's trigrams, or `None` when the query is
+/// too short or is a choseong query (consonant trigrams never occur in the
+/// indexed names).
+fn trigram_match(query: &str) -> Option<String> {
+    if hangul::is_choseong_query(query) {
+        return None;
+    }
+    let grams = trigrams(&normalize(query));
+    if grams.len() < MIN_QUERY_TRIGRAMS {
+        return None;
+    }
+    let terms: Vec<String> = grams
+        .iter()
+        .map(|gram| {
+            format!(
+                "\"{}\"",
+                gram.iter().collect::<String>().replace('"', "\"\"")
+            )
+        })
+        .collect();
+    Some(terms.join(" OR "))
+}
+
+fn top_ranked<T>(candidates: impl Iterator<Item = Ranked<T>>, limit: usize) -> Vec<Ranked<T>> {
+    let mut ranked: Vec<Ranked<T>> = candidates.filter(|r| r.score >= FUZZY_MIN_SCORE).collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(limit);
+    ranked
+}
+
+fn user_score(query: &str, user: &SlackUser) -> f64 {
+    let profile = user.profile.as_ref();
+    [
+        Some(user.name.as_str()),
+        profile.and_then(|p| p.display_name.as_deref()),
+        profile.and_then(|p| p.real_name.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|field| similarity(query, field))
+    .fold(0.0, f64::max)
+}
+
+/// Case-insensitive similarity in `0.0..=1.0`. Compares the query with the
+/// whole candidate and with each of its words (`john.smith` → `john`,
+/// `smith`), so a misspelled first name still finds the full handle. A
+/// query with bare Hangul consonants is compared in choseong form.
+pub(crate) fn similarity(query: &str, candidate: &str) -> f64 {
+    let (query, candidate) = if hangul::is_choseong_query(query) {
+        (
+            hangul::to_choseong(&normalize(query)),
+            hangul::to_choseong(&normalize(candidate)),
+        )
+    } else {
+        (normalize(query), normalize(candidate))
+    };
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    if query == candidate {
+        return 1.0;
+    }
+
+    let containment = if candidate.contains(&query) {
+        0.8 + 0.2 * query.chars().count() as f64 / candidate.chars().count() as f64
+    } else {
+        0.0
+    };
+    let words = candidate
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|word| edit_ratio(&query, word))
+        .fold(0.0, f64::max);
+
+    [
+        containment,
+        words,
+        edit_ratio(&query, &candidate),
+        trigram_overlap(&query, &candidate),
+    ]
+    .into_iter()
+    .fold(0.0, f64::max)
+    .min(1.0)
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().trim_start_matches(['#', '@']).to_lowercase()
+}
+
+fn edit_ratio(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+    1.0 - osa_distance(a, b) as f64 / longest as f64
+}
+
+/// Levenshtein distance that also counts an adjacent transposition as a
+/// single edit (optimal string alignment).
+fn osa_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                curr[j] = curr[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Dice coefficient over character trigrams; 0 when either side is too
+/// short to have one.
+fn trigram_overlap(a: &str, b: &str) -> f64 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+fn trigrams(value: &str) -> HashSet<[char; 3]> {
+    let chars: Vec<char> = value.chars().collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::types::SlackUserProfile;
+
+    fn user(id: &str, name: &str, real_name: &str) -> SlackUser {
+        SlackUser {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_bot: false,
+            is_admin: false,
+            deleted: false,
+            profile: Some(SlackUserProfile {
+                real_name: Some(real_name.to_string()),
+                display_name: None,
+                email: None,
+                status_text: None,
+                status_emoji: None,
+                title: None,
+                avatar: None,
+                timezone: None,
+            }),
+        }
+    }
+
+    fn channel(id: &str, name: &str) -> SlackChannel {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name, "is_channel": true}))
+            .unwrap()
+    }
+
+    #[test]
+    fn transposition_is_one_edit() {
+        assert_eq!(osa_distance("jonh", "john"), 1);
+        assert_eq!(osa_distance("kitten", "sitting"), 3);
+        assert_eq!(osa_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn similarity_prefers_closer_candidates() {
+        assert_eq!(similarity("Alice", "alice"), 1.0);
+        assert!(similarity("jonh", "john.smith") >= FUZZY_MIN_SCORE);
+        assert!(similarity("junyoung", "junyeong") > similarity("junyoung", "jiyoon"));
+        assert!(similarity("#deplyos", "deploys") >= FUZZY_MIN_SCORE);
+        assert!(similarity("jonh", "marketing") < FUZZY_MIN_SCORE);
+    }
+
+    #[tokio::test]
+    async fn misspelled_user_is_found_with_score() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_users(vec![
+                user("U1", "john.smith", "John Smith"),
+                user("U2", "jane.doe", "Jane Doe"),
+            ])
+            .await
+            .unwrap();
+
+        assert!(cache.search_users("jonh", 10, false).unwrap().is_empty());
+        let ranked = cache.rank_users("jonh", 10, false).unwrap();
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].item.id, "U1");
+        assert!(ranked[0].score < 1.0);
+    }
+
+    #[tokio::test]
+    async fn exact_matches_keep_full_score() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_users(vec![user("U1", "alice", "Alice Kim")])
+            .await
+            .unwrap();
+
+        let ranked = cache.rank_users("alice", 10, false).unwrap();
+        assert_eq!(ranked[0].score, 1.0);
+    }
+
+    #[tokio::test]
+    async fn fuzzy_channels_rank_best_first() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_channels(vec![
+                channel("C1", "deploys"),
+                channel("C2", "deploy-staging"),
+                channel("C3", "random"),
+            ])
+            .await
+            .unwrap();
+
+        let ranked = cache.fuzzy_channels("deplyos", 5).unwrap();
+        assert_eq!(ranked[0].item.id, "C1");
+        assert!(ranked.iter().all(|r| r.item.id != "C3"));
+    }
+
+    #[test]
+    fn only_long_enough_queries_use_the_trigram_index() {
+        assert!(trigram_match("jhon").is_none());
+        assert!(trigram_match("ㄱㅁㅅ").is_none());
+        let pattern = trigram_match("#Dep\"loy").unwrap();
+        assert_eq!(pattern.matches(" OR ").count(), 4);
+        assert!(pattern.contains(r#""p""l""#));
+    }
+
+    #[tokio::test]
+    async fn trigram_index_follows_renames() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_channels(vec![channel("C1", "deploys")])
+            .await
+            .unwrap();
+        cache
+            .save_channels(vec![channel("C1", "incidents")])
+            .await
+            .unwrap();
+
+        assert!(cache.fuzzy_channels("deplyos", 5).unwrap().is_empty());
+        let ranked = cache.fuzzy_channels("incidnets", 5).unwrap();
+        assert_eq!(ranked[0].item.id, "C1");
+    }
+
+    #[test]
+    fn ranked_serializes_flat_with_score() {
+        let ranked = Ranked {
+            item: channel("C1", "deploys"),
+            score: 0.75,
+        };
+        let value = serde_json::to_value(&ranked).unwrap();
+        assert_eq!(value["id"], "C1");
+        assert_eq!(value["score"], 0.75);
+    }
+}
@@ -0,0 +1,96 @@
+//! Hangul initial-consonant (choseong) matching, so `ㄱㅁㅅ` finds 김민수
+//! the way Korean users expect from contact search.
+
+const SYLLABLE_FIRST: u32 = 0xAC00;
+const SYLLABLE_LAST: u32 = 0xD7A3;
+/// Syllables per initial consonant: 21 vowels × 28 finals.
+const SYLLABLES_PER_CHOSEONG: u32 = 21 * 28;
+
+/// Initial consonants in syllable order, as compatibility jamo (what a
+/// keyboard produces when typing a bare consonant).
+const CHOSEONG: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+    'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+fn is_choseong(c: char) -> bool {
+    CHOSEONG.contains(&c)
+}
+
+/// True when the query contains a bare consonant, i.e. it can only match
+/// through choseong expansion (`ㄱㅁㅅ`, `김ㅁㅅ`).
+pub(super) fn is_choseong_query(query: &str) -> bool {
+    query.chars().any(is_choseong)
+}
+
+/// The initial consonant of a precomposed syllable.
+fn choseong_of(c: char) -> Option<char> {
+    let code = c as u32;
+    (SYLLABLE_FIRST..=SYLLABLE_LAST)
+        .contains(&code)
+        .then(|| CHOSEONG[((code - SYLLABLE_FIRST) / SYLLABLES_PER_CHOSEONG) as usize])
+}
+
+/// Drops whitespace and lowercases: `김민수 Kim` → `김민수kim`.
+fn normalize(value: &str) -> Vec<char> {
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Replaces every precomposed syllable with its initial consonant, drops
+/// whitespace and lowercases the rest: `김민수 Kim` → `ㄱㅁㅅkim`.
+pub(super) fn to_choseong(value: &str) -> String {
+    normalize(value)
+        .into_iter()
+        .map(|c| choseong_of(c).unwrap_or(c))
+        .collect()
+}
+
+/// Whether `candidate` contains `query`, where a bare consonant in the
+/// query stands for any syllable starting with it and everything else,
+/// complete syllables included, must match literally: `김ㅁ` finds 김민수
+/// but not 곽민수.
+pub(super) fn choseong_matches(query: &str, candidate: &str) -> bool {
+    let query = normalize(query);
+    let candidate = normalize(candidate);
+    let matches = |q: char, c: char| q == c || (is_choseong(q) && choseong_of(c) == Some(q));
+    !query.is_empty()
+        && candidate
+            .windows(query.len())
+            .any(|window| window.iter().zip(&query).all(|(&c, &q)| matches(q, c)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syllables_reduce_to_initial_consonants() {
+        assert_eq!(to_choseong("김민수"), "ㄱㅁㅅ");
+        assert_eq!(to_choseong("까치 Park"), "ㄲㅊpark");
+        assert_eq!(to_choseong("힣"), "ㅎ");
+        assert_eq!(to_choseong("가"), "ㄱ");
+    }
+
+    #[test]
+    fn detects_bare_consonants_only() {
+        assert!(is_choseong_query("ㄱㅁㅅ"));
+        assert!(is_choseong_query("김ㅁ"));
+        assert!(!is_choseong_query("김민수"));
+        assert!(!is_choseong_query("minsu"));
+    }
+
+    #[test]
+    fn matches_mixed_script_names() {
+        assert!(choseong_matches("ㄱㅁㅅ", "김민수 (Minsu Kim)"));
+        assert!(choseong_matches("김ㅁ", "김민수"));
+        assert!(!choseong_matches("김ㅁ", "곽민수"));
+        assert!(choseong_matches("ㄱ민", "곽민수"));
+        assert!(choseong_matches("ㅈㅎ", "Park 지훈"));
+        assert!(!choseong_matches("ㅂㅈㅎ", "Park 지훈"));
+        assert!(!choseong_matches("", "김민수"));
+    }
+}
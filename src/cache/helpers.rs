@@ -1,6 +1,7 @@
 use super::error::{CacheError, CacheResult};
 use super::sqlite_cache::SqliteCache;
-use rusqlite::OptionalExtension;
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,6 +11,56 @@ pub enum CacheStatus {
     NeedsRefresh,
 }
 
+/// Row-level outcome of syncing a cached table with a fresh listing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SyncStats {
+    pub added: usize,
+    pub changed: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Brings `table` in line with `staging` (both `id`/`data`/`updated_at`)
+/// touching only rows that differ: departed ids are deleted, rows whose
+/// serialized data changed are updated, new ids are inserted. Unchanged rows
+/// keep their `updated_at` and skip the FTS triggers entirely.
+pub(super) fn sync_from_staging(
+    conn: &Connection,
+    table: &str,
+    staging: &str,
+) -> CacheResult<SyncStats> {
+    let removed = conn.execute(
+        &format!("DELETE FROM {table} WHERE id NOT IN (SELECT id FROM {staging})"),
+        [],
+    )?;
+    let changed = conn.execute(
+        &format!(
+            "UPDATE {table} SET data = s.data, updated_at = s.updated_at
+             FROM {staging} AS s
+             WHERE {table}.id = s.id AND {table}.data IS NOT s.data"
+        ),
+        [],
+    )?;
+    let added = conn.execute(
+        &format!(
+            "INSERT INTO {table} (id, data, updated_at)
+             SELECT id, data, updated_at FROM {staging}
+             WHERE id NOT IN (SELECT id FROM {table})"
+        ),
+        [],
+    )?;
+    let staged: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {staging}"), [], |row| {
+        row.get(0)
+    })?;
+
+    Ok(SyncStats {
+        added,
+        changed,
+        removed,
+        unchanged: (staged as usize).saturating_sub(added + changed),
+    })
+}
+
 impl SqliteCache {
     pub(super) fn process_fts_query(&self, query: &str) -> String {
         let trimmed = query.trim();
@@ -76,7 +127,7 @@ impl SqliteCache {
         }
     }
 
-    fn get_cache_entry_status(
+    pub(super) fn get_cache_entry_status(
         &self,
         metadata_key: &str,
         ttl_hours: u64,
@@ -66,7 +66,61 @@ impl SqliteCache {
         })
     }
 
-    pub(super) async fn release_lock(&self, key: &str) -> CacheResult<()> {
+    /// Single non-blocking attempt at `key`; `false` when another live
+    /// instance holds it. Expired locks are cleared first.
+    pub fn try_acquire_lock(&self, key: &str) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        conn.execute(
+            "DELETE FROM locks WHERE expires_at < ? OR acquired_at < ?",
+            params![now, now - STALE_LOCK_THRESHOLD_SECS],
+        )?;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO locks (key, instance_id, acquired_at, expires_at)
+             VALUES (?, ?, ?, ?)",
+            params![key, &self.instance_id, now, now + LOCK_TIMEOUT_SECS],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Whether an unexpired lock on `key` exists, held by anyone.
+    pub fn is_locked(&self, key: &str) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM locks WHERE key = ? AND expires_at >= ?)",
+            params![key, now],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Pushes this instance's hold on `key` another `LOCK_TIMEOUT_SECS`
+    /// out; `false` when the lock was lost (expired and taken, or released).
+    pub fn renew_lock(&self, key: &str) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let updated = conn.execute(
+            "UPDATE locks SET acquired_at = ?, expires_at = ? WHERE key = ? AND instance_id = ?",
+            params![now, now + LOCK_TIMEOUT_SECS, key, &self.instance_id],
+        )?;
+        Ok(updated == 1)
+    }
+
+    /// Renews `key` every third of `LOCK_TIMEOUT_SECS` until dropped, so a
+    /// job that outlasts the timeout keeps its lock. Race it against the job.
+    pub async fn keep_lock(&self, key: &str) {
+        let period = Duration::from_secs((LOCK_TIMEOUT_SECS / 3) as u64);
+        loop {
+            tokio::time::sleep(period).await;
+            match self.renew_lock(key) {
+                Ok(true) => {}
+                Ok(false) => warn!("Lock '{}' was lost before the job finished", key),
+                Err(e) => warn!("Failed to renew lock '{}': {}", key, e),
+            }
+        }
+    }
+
+    pub async fn release_lock(&self, key: &str) -> CacheResult<()> {
         let conn = self.pool.get()?;
         conn.execute(
             "DELETE FROM locks WHERE key = ? AND instance_id = ?",
@@ -365,4 +419,44 @@ mod tests {
         let final_success = success_count.load(std::sync::atomic::Ordering::SeqCst);
         assert!(final_success >= 1);
     }
+
+    #[tokio::test]
+    async fn test_try_acquire_lock_is_exclusive_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.db");
+        let path = path.to_str().unwrap();
+        let first = SqliteCache::new(path).await.unwrap();
+        let second = SqliteCache::new(path).await.unwrap();
+
+        assert!(!second.is_locked("refresh").unwrap());
+        assert!(first.try_acquire_lock("refresh").unwrap());
+        assert!(!second.try_acquire_lock("refresh").unwrap());
+        assert!(second.is_locked("refresh").unwrap());
+
+        first.release_lock("refresh").await.unwrap();
+        assert!(second.try_acquire_lock("refresh").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_renew_lock_only_extends_own_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.db");
+        let path = path.to_str().unwrap();
+        let first = SqliteCache::new(path).await.unwrap();
+        let second = SqliteCache::new(path).await.unwrap();
+
+        assert!(first.try_acquire_lock("refresh").unwrap());
+        first
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                "UPDATE locks SET acquired_at = 0, expires_at = 1 WHERE key = 'refresh'",
+                [],
+            )
+            .unwrap();
+        assert!(!second.renew_lock("refresh").unwrap());
+        assert!(first.renew_lock("refresh").unwrap());
+        assert!(!second.try_acquire_lock("refresh").unwrap());
+    }
 }
@@ -0,0 +1,325 @@
+//! `cache check`/`optimize`/`vacuum`/`clear`/`locks`: upkeep for a cache
+//! file that outlives many CLI versions and is shared between processes.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use super::error::CacheResult;
+use super::sqlite_cache::SqliteCache;
+
+/// FTS tables and the content tables they index, the trigram candidate
+/// indexes included.
+const FTS_TABLES: [(&str, &str); 4] = [
+    ("users_fts", "users"),
+    ("channels_fts", "channels"),
+    ("users_fuzzy", "users"),
+    ("channels_fuzzy", "channels"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// `PRAGMA integrity_check` output; `["ok"]` when the file is sound.
+    pub sqlite: Vec<String>,
+    pub fts: Vec<FtsCheck>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FtsCheck {
+    pub table: &'static str,
+    pub rows: i64,
+    pub indexed: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FtsCheck {
+    pub fn ok(&self) -> bool {
+        self.error.is_none() && self.rows == self.indexed
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockEntry {
+    pub key: String,
+    pub instance_id: String,
+    pub acquired_at: i64,
+    pub expires_at: i64,
+    pub expired: bool,
+}
+
+impl SqliteCache {
+    /// SQLite page-level check plus, per FTS table, the FTS5
+    /// `integrity-check` against its content table and a row/index count
+    /// comparison.
+    pub fn check_integrity(&self) -> CacheResult<IntegrityReport> {
+        let conn = self.pool.get()?;
+        let sqlite = conn
+            .prepare("PRAGMA integrity_check")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let fts = FTS_TABLES
+            .into_iter()
+            .map(|(fts, content)| check_fts(&conn, fts, content))
+            .collect::<CacheResult<Vec<_>>>()?;
+
+        Ok(IntegrityReport {
+            ok: sqlite == ["ok"] && fts.iter().all(FtsCheck::ok),
+            sqlite,
+            fts,
+        })
+    }
+
+    /// Rebuilds every FTS index from its content table.
+    pub fn rebuild_fts(&self) -> CacheResult<()> {
+        let conn = self.pool.get()?;
+        for (fts, _) in FTS_TABLES {
+            conn.execute(&format!("INSERT INTO {fts}({fts}) VALUES('rebuild')"), [])?;
+        }
+        Ok(())
+    }
+
+    /// Merges FTS index segments and refreshes planner statistics.
+    pub fn optimize(&self) -> CacheResult<()> {
+        let conn = self.pool.get()?;
+        for (fts, _) in FTS_TABLES {
+            conn.execute(&format!("INSERT INTO {fts}({fts}) VALUES('optimize')"), [])?;
+        }
+        conn.execute_batch("ANALYZE; PRAGMA optimize;")?;
+        Ok(())
+    }
+
+    /// Rewrites the database file to drop free pages. Returns the size in
+    /// bytes before and after.
+    pub fn vacuum(&self) -> CacheResult<(i64, i64)> {
+        let conn = self.pool.get()?;
+        let before = database_size(&conn)?;
+        conn.execute_batch("VACUUM;")?;
+        Ok((before, database_size(&conn)?))
+    }
+
+    /// Drops every cached user; the next lookup refetches them.
+    pub async fn clear_users(&self) -> CacheResult<usize> {
+        self.with_lock("users_update", || {
+            let conn = self.pool.get()?;
+            let tx = conn.unchecked_transaction()?;
+            let removed = tx.execute("DELETE FROM users", [])?;
+            tx.execute("DELETE FROM metadata WHERE key = 'last_user_sync'", [])?;
+            tx.commit()?;
+            Ok(removed)
+        })
+        .await
+    }
+
+    /// Drops every cached channel together with its membership.
+    pub async fn clear_channels(&self) -> CacheResult<usize> {
+        self.with_lock("channels_update", || {
+            let conn = self.pool.get()?;
+            let tx = conn.unchecked_transaction()?;
+            let removed = tx.execute("DELETE FROM channels", [])?;
+            tx.execute("DELETE FROM channel_members", [])?;
+            tx.execute(
+                "DELETE FROM metadata WHERE key IN ('last_channel_sync', 'last_member_sync')",
+                [],
+            )?;
+            tx.commit()?;
+            Ok(removed)
+        })
+        .await
+    }
+
+    /// Drops every cached membership; channels stay.
+    pub async fn clear_members(&self) -> CacheResult<usize> {
+        self.with_lock("channel_members_update", || {
+            let conn = self.pool.get()?;
+            let tx = conn.unchecked_transaction()?;
+            let removed = tx.execute("DELETE FROM channel_members", [])?;
+            tx.execute("DELETE FROM metadata WHERE key = 'last_member_sync'", [])?;
+            tx.commit()?;
+            Ok(removed)
+        })
+        .await
+    }
+
+    pub fn list_locks(&self) -> CacheResult<Vec<LockEntry>> {
+        let conn = self.pool.get()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut stmt = conn.prepare(
+            "SELECT key, instance_id, acquired_at, expires_at FROM locks ORDER BY acquired_at",
+        )?;
+        let locks = stmt
+            .query_map([], |row| {
+                let expires_at: i64 = row.get(3)?;
+                Ok(LockEntry {
+                    key: row.get(0)?,
+                    instance_id: row.get(1)?,
+                    acquired_at: row.get(2)?,
+                    expires_at,
+                    expired: expires_at < now,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(locks)
+    }
+
+    /// Removes the lock on `key` whoever holds it; `false` if there was none.
+    pub fn break_lock(&self, key: &str) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        Ok(conn.execute("DELETE FROM locks WHERE key = ?", params![key])? > 0)
+    }
+
+    pub fn break_all_locks(&self) -> CacheResult<usize> {
+        let conn = self.pool.get()?;
+        Ok(conn.execute("DELETE FROM locks", [])?)
+    }
+}
+
+fn check_fts(conn: &Connection, fts: &'static str, content: &str) -> CacheResult<FtsCheck> {
+    // rank = 1 also verifies the index against the external content table.
+    let error = conn
+        .execute(
+            &format!("INSERT INTO {fts}({fts}, rank) VALUES('integrity-check', 1)"),
+            [],
+        )
+        .err()
+        .map(|err| err.to_string());
+    let count = |table: &str| -> CacheResult<i64> {
+        Ok(
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })?,
+        )
+    };
+
+    Ok(FtsCheck {
+        table: fts,
+        rows: count(content)?,
+        indexed: count(&format!("{fts}_docsize"))?,
+        error,
+    })
+}
+
+fn database_size(conn: &Connection) -> CacheResult<i64> {
+    Ok(conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::types::SlackChannel;
+
+    fn channel(id: &str, name: &str) -> SlackChannel {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name, "is_channel": true}))
+            .unwrap()
+    }
+
+    async fn setup_cache() -> SqliteCache {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_channels(vec![channel("C1", "general"), channel("C2", "deploys")])
+            .await
+            .unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn healthy_cache_passes_check() {
+        let cache = setup_cache().await;
+        let report = cache.check_integrity().unwrap();
+
+        assert!(report.ok, "{report:?}");
+        assert_eq!(report.sqlite, ["ok"]);
+        let channels = report
+            .fts
+            .iter()
+            .find(|f| f.table == "channels_fts")
+            .unwrap();
+        assert_eq!((channels.rows, channels.indexed), (2, 2));
+        assert_eq!(report.fts.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn drifted_fts_index_fails_check_until_rebuilt() {
+        let cache = setup_cache().await;
+        {
+            let conn = cache.pool.get().unwrap();
+            conn.execute_batch(
+                "DROP TRIGGER channels_ad;
+                 DELETE FROM channels WHERE id = 'C2';",
+            )
+            .unwrap();
+        }
+
+        let report = cache.check_integrity().unwrap();
+        assert!(!report.ok);
+
+        cache.rebuild_fts().unwrap();
+        assert!(cache.check_integrity().unwrap().ok);
+    }
+
+    #[tokio::test]
+    async fn drifted_fuzzy_index_fails_check_until_rebuilt() {
+        let cache = setup_cache().await;
+        {
+            let conn = cache.pool.get().unwrap();
+            conn.execute(
+                "INSERT INTO channels_fuzzy(channels_fuzzy, rowid, name)
+                 SELECT 'delete', rowid, name FROM channels WHERE id = 'C2'",
+                [],
+            )
+            .unwrap();
+        }
+
+        let report = cache.check_integrity().unwrap();
+        assert!(!report.ok);
+        let fuzzy = report
+            .fts
+            .iter()
+            .find(|f| f.table == "channels_fuzzy")
+            .unwrap();
+        assert!(!fuzzy.ok(), "{fuzzy:?}");
+        assert!(
+            report
+                .fts
+                .iter()
+                .filter(|f| f.table != "channels_fuzzy")
+                .all(FtsCheck::ok)
+        );
+
+        cache.rebuild_fts().unwrap();
+        assert!(cache.check_integrity().unwrap().ok);
+    }
+
+    #[tokio::test]
+    async fn optimize_and_clear_keep_the_cache_usable() {
+        let cache = setup_cache().await;
+        cache.optimize().unwrap();
+
+        assert_eq!(cache.clear_channels().await.unwrap(), 2);
+        assert_eq!(cache.get_counts().unwrap().1, 0);
+        assert!(cache.check_integrity().unwrap().ok);
+    }
+
+    #[tokio::test]
+    async fn locks_can_be_listed_and_broken() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        assert!(cache.try_acquire_lock("users_update").unwrap());
+        assert!(cache.try_acquire_lock("channels_update").unwrap());
+
+        let locks = cache.list_locks().unwrap();
+        assert_eq!(locks.len(), 2);
+        assert!(locks.iter().all(|l| !l.expired));
+
+        assert!(cache.break_lock("users_update").unwrap());
+        assert!(!cache.break_lock("users_update").unwrap());
+        assert_eq!(cache.break_all_locks().unwrap(), 1);
+        assert!(cache.list_locks().unwrap().is_empty());
+    }
+}
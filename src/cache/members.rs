@@ -0,0 +1,256 @@
+//! Channel membership (`channel_members`), filled by `cache refresh members`
+//! and live `members` calls so "where is @y" is answered without calling Slack.
+
+use super::error::CacheResult;
+use super::helpers::CacheStatus;
+use super::sqlite_cache::SqliteCache;
+use crate::slack::types::{SlackChannel, SlackUser};
+use chrono::Utc;
+use rusqlite::{Connection, params};
+
+impl SqliteCache {
+    /// Replaces every cached membership with `memberships`
+    /// (`(channel_id, member_ids)`). Returns the number of rows written.
+    pub async fn save_channel_members(
+        &self,
+        memberships: Vec<(String, Vec<String>)>,
+    ) -> CacheResult<usize> {
+        self.with_lock("channel_members_update", || {
+            let conn = self.pool.get()?;
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM channel_members", [])?;
+            let mut written = 0;
+            for (channel_id, members) in &memberships {
+                written += insert_members(&tx, channel_id, members)?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO metadata (key, value) VALUES ('last_member_sync', ?)",
+                params![Utc::now().timestamp().to_string()],
+            )?;
+            tx.commit()?;
+            Ok(written)
+        })
+        .await
+    }
+
+    /// Replaces the cached members of one channel, e.g. after a live
+    /// `conversations.members` call for a channel the refresh skipped.
+    pub async fn save_members_of(&self, channel_id: &str, members: &[String]) -> CacheResult<()> {
+        self.with_lock("channel_members_update", || {
+            let conn = self.pool.get()?;
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM channel_members WHERE channel_id = ?",
+                params![channel_id],
+            )?;
+            insert_members(&tx, channel_id, members)?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Cached member ids of `channel_id`, or `None` when its membership has
+    /// never been cached.
+    pub fn get_member_ids(&self, channel_id: &str) -> CacheResult<Option<Vec<String>>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT user_id FROM channel_members WHERE channel_id = ? ORDER BY user_id",
+        )?;
+        let ids = stmt
+            .query_map([channel_id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok((!ids.is_empty()).then_some(ids))
+    }
+
+    /// Cached users in `channel_id` whose handle, display name, real name or
+    /// email contains `query` (ASCII case-insensitive), sorted by handle.
+    pub fn search_members(&self, channel_id: &str, query: &str) -> CacheResult<Vec<SlackUser>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT u.data FROM channel_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.channel_id = ?1
+               AND (?2 = ''
+                    OR instr(lower(u.name), lower(?2))
+                    OR instr(lower(coalesce(u.display_name, '')), lower(?2))
+                    OR instr(lower(coalesce(u.real_name, '')), lower(?2))
+                    OR instr(lower(coalesce(u.email, '')), lower(?2)))
+             ORDER BY u.name",
+        )?;
+        let users = stmt
+            .query_map(params![channel_id, query.trim()], |row| {
+                row.get::<_, String>(0)
+            })?
+            .map(|json| Ok(serde_json::from_str(&json?)?))
+            .collect::<CacheResult<Vec<_>>>()?;
+        Ok(users)
+    }
+
+    /// Cached channels `user_id` belongs to, sorted by name; archived
+    /// channels only with `include_archived`.
+    pub fn get_user_channels(
+        &self,
+        user_id: &str,
+        include_archived: bool,
+    ) -> CacheResult<Vec<SlackChannel>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT c.data FROM channel_members m
+             JOIN channels c ON c.id = m.channel_id
+             WHERE m.user_id = ?1 AND (?2 OR c.is_archived = 0 OR c.is_archived IS NULL)
+             ORDER BY c.name",
+        )?;
+        let channels = stmt
+            .query_map(params![user_id, include_archived], |row| {
+                row.get::<_, String>(0)
+            })?
+            .map(|json| Ok(serde_json::from_str(&json?)?))
+            .collect::<CacheResult<Vec<_>>>()?;
+        Ok(channels)
+    }
+
+    /// Whether a full membership sync has ever completed.
+    pub fn has_member_sync(&self) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM metadata WHERE key = 'last_member_sync')",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Whether the last full membership sync is younger than
+    /// `threshold_percent` of `ttl_hours`; `false` if none has completed.
+    pub fn is_member_sync_fresh(
+        &self,
+        ttl_hours: u64,
+        threshold_percent: u64,
+    ) -> CacheResult<bool> {
+        Ok(
+            self.get_cache_entry_status("last_member_sync", ttl_hours, threshold_percent)?
+                == CacheStatus::Fresh,
+        )
+    }
+}
+
+fn insert_members(conn: &Connection, channel_id: &str, members: &[String]) -> CacheResult<usize> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR IGNORE INTO channel_members (channel_id, user_id) VALUES (?, ?)",
+    )?;
+    let mut written = 0;
+    for user_id in members {
+        written += stmt.execute(params![channel_id, user_id])?;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, name: &str, real_name: &str) -> SlackUser {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": name,
+            "profile": {"real_name": real_name, "email": format!("{name}@example.com")}
+        }))
+        .unwrap()
+    }
+
+    fn channel(id: &str, name: &str, archived: bool) -> SlackChannel {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": name, "is_channel": true, "is_archived": archived
+        }))
+        .unwrap()
+    }
+
+    async fn setup_cache() -> SqliteCache {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_users(vec![
+                user("U1", "alice", "Alice Kim"),
+                user("U2", "bob", "Bob Lee"),
+                user("U3", "carol", "Carol Park"),
+            ])
+            .await
+            .unwrap();
+        cache
+            .save_channels(vec![
+                channel("C1", "general", false),
+                channel("C2", "deploys", false),
+                channel("C3", "old-project", true),
+            ])
+            .await
+            .unwrap();
+        cache
+            .save_channel_members(vec![
+                ("C1".into(), vec!["U1".into(), "U2".into(), "U3".into()]),
+                ("C2".into(), vec!["U1".into(), "U3".into()]),
+                ("C3".into(), vec!["U1".into()]),
+            ])
+            .await
+            .unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn search_members_filters_by_any_name_field() {
+        let cache = setup_cache().await;
+
+        let ids = |users: Vec<SlackUser>| users.into_iter().map(|u| u.id).collect::<Vec<_>>();
+        assert_eq!(ids(cache.search_members("C2", "").unwrap()), ["U1", "U3"]);
+        assert_eq!(ids(cache.search_members("C2", "PARK").unwrap()), ["U3"]);
+        assert_eq!(ids(cache.search_members("C1", "bob@").unwrap()), ["U2"]);
+        assert!(cache.search_members("C2", "bob").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn user_channels_skip_archived_unless_asked() {
+        let cache = setup_cache().await;
+
+        let names = |channels: Vec<SlackChannel>| {
+            channels
+                .into_iter()
+                .filter_map(|c| c.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(cache.get_user_channels("U1", false).unwrap()),
+            ["deploys", "general"]
+        );
+        assert_eq!(
+            names(cache.get_user_channels("U1", true).unwrap()),
+            ["deploys", "general", "old-project"]
+        );
+        assert_eq!(
+            names(cache.get_user_channels("U2", false).unwrap()),
+            ["general"]
+        );
+    }
+
+    #[tokio::test]
+    async fn per_channel_save_replaces_only_that_channel() {
+        let cache = setup_cache().await;
+        assert!(cache.has_member_sync().unwrap());
+        assert!(cache.get_member_ids("C9").unwrap().is_none());
+
+        cache
+            .save_members_of("C2", &["U2".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get_member_ids("C2").unwrap().unwrap(), ["U2"]);
+        assert_eq!(cache.get_member_ids("C1").unwrap().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn member_sync_freshness_follows_the_ttl() {
+        let empty = SqliteCache::new(":memory:").await.unwrap();
+        assert!(!empty.is_member_sync_fresh(24, 75).unwrap());
+
+        let cache = setup_cache().await;
+        assert!(cache.is_member_sync_fresh(24, 75).unwrap());
+        assert!(!cache.is_member_sync_fresh(0, 75).unwrap());
+    }
+}
@@ -1,11 +1,26 @@
+mod archive;
 mod channels;
 pub mod constants;
 mod error;
+mod fuzzy;
+mod hangul;
 mod helpers;
 mod locks;
+mod maintenance;
+mod members;
 mod schema;
+mod searches;
+mod snapshot;
 pub mod sqlite_cache;
+mod stats;
 mod users;
 
-pub use helpers::CacheStatus;
+pub use archive::{ArchiveSummary, MessageHistory, MessageRevision};
+pub use fuzzy::Ranked;
+pub use helpers::{CacheStatus, SyncStats};
+pub use maintenance::{FtsCheck, IntegrityReport, LockEntry};
+pub use schema::FtsTokenizer;
+pub use searches::{SavedSearch, SearchHistoryEntry};
+pub use snapshot::SnapshotHeader;
 pub use sqlite_cache::SqliteCache;
+pub use stats::{CacheStats, ChannelCounts, Freshness, UserCounts};
@@ -1,10 +1,45 @@
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{Connection, OptionalExtension, TransactionBehavior, params, types::Value};
+use serde::{Deserialize, Serialize};
 
 use super::error::CacheResult;
 
-pub const SCHEMA_VERSION: i32 = 2;
+pub const SCHEMA_VERSION: i32 = 5;
+
+/// FTS5 tokenizer for `users_fts`/`channels_fts` (`cache.tokenizer`).
+/// `unicode61` splits on word boundaries with English stemming; `trigram`
+/// matches any 3+ character substring, which suits Korean, Japanese and
+/// Chinese names that have no spaces to split on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FtsTokenizer {
+    #[default]
+    Unicode61,
+    Trigram,
+}
+
+impl FtsTokenizer {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unicode61 => "unicode61",
+            Self::Trigram => "trigram",
+        }
+    }
+
+    const fn fts_arg(self) -> &'static str {
+        match self {
+            Self::Unicode61 => "porter unicode61",
+            Self::Trigram => "trigram",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [Self::Unicode61, Self::Trigram]
+            .into_iter()
+            .find(|t| t.as_str() == value)
+    }
+}
 
 /// Every object the cache owns. `apply_schema` drops and recreates all of it
 /// when the stored version differs from `SCHEMA_VERSION` — cache contents are
@@ -46,57 +81,83 @@ const SCHEMA_DDL: &str = "
     CREATE INDEX IF NOT EXISTS idx_channels_type ON channels(is_channel, is_group, is_im, is_mpim);
     CREATE INDEX IF NOT EXISTS idx_channels_archived ON channels(is_archived);
 
-    -- FTS5 tables for fuzzy search
-    CREATE VIRTUAL TABLE IF NOT EXISTS users_fts USING fts5(
-        id UNINDEXED,
+    -- Channel membership, synced by `cache refresh members`
+    CREATE TABLE IF NOT EXISTS channel_members (
+        channel_id TEXT NOT NULL,
+        user_id TEXT NOT NULL,
+        PRIMARY KEY (channel_id, user_id)
+    ) WITHOUT ROWID;
+
+    CREATE INDEX IF NOT EXISTS idx_channel_members_user ON channel_members(user_id);
+
+    -- Trigram candidate indexes for typo-tolerant lookups (src/cache/fuzzy.rs),
+    -- always trigram whatever `cache.tokenizer` says: a misspelled name still
+    -- shares most of its trigrams with the real one.
+    CREATE VIRTUAL TABLE IF NOT EXISTS users_fuzzy USING fts5(
         name,
         display_name,
         real_name,
-        email,
         content=users,
         content_rowid=rowid,
-        tokenize='porter unicode61'
+        tokenize='trigram'
     );
 
-    CREATE VIRTUAL TABLE IF NOT EXISTS channels_fts USING fts5(
-        id UNINDEXED,
+    CREATE VIRTUAL TABLE IF NOT EXISTS channels_fuzzy USING fts5(
         name,
-        topic,
-        purpose,
         content=channels,
         content_rowid=rowid,
-        tokenize='porter unicode61'
+        tokenize='trigram'
     );
 
-    -- Triggers to keep FTS in sync
+    -- Triggers to keep FTS in sync. External-content tables must be told the
+    -- old values through the 'delete' command; a plain DELETE would look them
+    -- up in a content row that is already gone and leave stale tokens behind.
     CREATE TRIGGER IF NOT EXISTS users_ai AFTER INSERT ON users BEGIN
         INSERT INTO users_fts(rowid, id, name, display_name, real_name, email)
         VALUES (new.rowid, new.id, new.name, new.display_name, new.real_name, new.email);
+        INSERT INTO users_fuzzy(rowid, name, display_name, real_name)
+        VALUES (new.rowid, new.name, new.display_name, new.real_name);
     END;
 
     CREATE TRIGGER IF NOT EXISTS users_ad AFTER DELETE ON users BEGIN
-        DELETE FROM users_fts WHERE rowid = old.rowid;
+        INSERT INTO users_fts(users_fts, rowid, id, name, display_name, real_name, email)
+        VALUES ('delete', old.rowid, old.id, old.name, old.display_name, old.real_name, old.email);
+        INSERT INTO users_fuzzy(users_fuzzy, rowid, name, display_name, real_name)
+        VALUES ('delete', old.rowid, old.name, old.display_name, old.real_name);
     END;
 
     CREATE TRIGGER IF NOT EXISTS users_au AFTER UPDATE ON users BEGIN
-        DELETE FROM users_fts WHERE rowid = old.rowid;
+        INSERT INTO users_fts(users_fts, rowid, id, name, display_name, real_name, email)
+        VALUES ('delete', old.rowid, old.id, old.name, old.display_name, old.real_name, old.email);
         INSERT INTO users_fts(rowid, id, name, display_name, real_name, email)
         VALUES (new.rowid, new.id, new.name, new.display_name, new.real_name, new.email);
+        INSERT INTO users_fuzzy(users_fuzzy, rowid, name, display_name, real_name)
+        VALUES ('delete', old.rowid, old.name, old.display_name, old.real_name);
+        INSERT INTO users_fuzzy(rowid, name, display_name, real_name)
+        VALUES (new.rowid, new.name, new.display_name, new.real_name);
     END;
 
     CREATE TRIGGER IF NOT EXISTS channels_ai AFTER INSERT ON channels BEGIN
         INSERT INTO channels_fts(rowid, id, name, topic, purpose)
         VALUES (new.rowid, new.id, new.name, new.topic, new.purpose);
+        INSERT INTO channels_fuzzy(rowid, name) VALUES (new.rowid, new.name);
     END;
 
     CREATE TRIGGER IF NOT EXISTS channels_ad AFTER DELETE ON channels BEGIN
-        DELETE FROM channels_fts WHERE rowid = old.rowid;
+        INSERT INTO channels_fts(channels_fts, rowid, id, name, topic, purpose)
+        VALUES ('delete', old.rowid, old.id, old.name, old.topic, old.purpose);
+        INSERT INTO channels_fuzzy(channels_fuzzy, rowid, name)
+        VALUES ('delete', old.rowid, old.name);
     END;
 
     CREATE TRIGGER IF NOT EXISTS channels_au AFTER UPDATE ON channels BEGIN
-        DELETE FROM channels_fts WHERE rowid = old.rowid;
+        INSERT INTO channels_fts(channels_fts, rowid, id, name, topic, purpose)
+        VALUES ('delete', old.rowid, old.id, old.name, old.topic, old.purpose);
         INSERT INTO channels_fts(rowid, id, name, topic, purpose)
         VALUES (new.rowid, new.id, new.name, new.topic, new.purpose);
+        INSERT INTO channels_fuzzy(channels_fuzzy, rowid, name)
+        VALUES ('delete', old.rowid, old.name);
+        INSERT INTO channels_fuzzy(rowid, name) VALUES (new.rowid, new.name);
     END;
 
     -- Metadata table
@@ -117,29 +178,111 @@ const SCHEMA_DDL: &str = "
     CREATE INDEX IF NOT EXISTS idx_locks_expires ON locks(expires_at);
 ";
 
+/// External-content FTS tables; `{tokenizer}` is substituted from
+/// `FtsTokenizer`. Dropping and recreating them only costs a `rebuild`
+/// from the content tables, so a tokenizer change keeps cached rows.
+const FTS_DDL: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS users_fts USING fts5(
+        id UNINDEXED,
+        name,
+        display_name,
+        real_name,
+        email,
+        content=users,
+        content_rowid=rowid,
+        tokenize='{tokenizer}'
+    );
+
+    CREATE VIRTUAL TABLE IF NOT EXISTS channels_fts USING fts5(
+        id UNINDEXED,
+        name,
+        topic,
+        purpose,
+        content=channels,
+        content_rowid=rowid,
+        tokenize='{tokenizer}'
+    );
+";
+
+/// User-authored data and the message archive (past revisions are gone from
+/// Slack once edited), neither of which can be refetched. Created on every
+/// open and never part of `SCHEMA_TEARDOWN`, so a cache rebuild keeps it;
+/// changes here must be additive (`ADD COLUMN`, new tables).
+const PERSISTENT_DDL: &str = "
+    CREATE TABLE IF NOT EXISTS saved_searches (
+        name TEXT PRIMARY KEY,
+        query TEXT NOT NULL,
+        options JSON NOT NULL,
+        created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+        updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+    );
+
+    CREATE TABLE IF NOT EXISTS search_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        query TEXT NOT NULL,
+        options JSON NOT NULL,
+        saved_name TEXT,
+        result_count INTEGER NOT NULL,
+        executed_at INTEGER NOT NULL DEFAULT (unixepoch())
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_search_history_executed ON search_history(executed_at);
+
+    CREATE TABLE IF NOT EXISTS archived_messages (
+        channel_id TEXT NOT NULL,
+        ts TEXT NOT NULL,
+        thread_ts TEXT,
+        message JSON NOT NULL,
+        first_seen INTEGER NOT NULL,
+        last_seen INTEGER NOT NULL,
+        deleted_at INTEGER,
+        PRIMARY KEY (channel_id, ts)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_archived_messages_thread
+        ON archived_messages(channel_id, thread_ts);
+
+    CREATE TABLE IF NOT EXISTS message_revisions (
+        channel_id TEXT NOT NULL,
+        ts TEXT NOT NULL,
+        revision INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        blocks JSON,
+        edited_ts TEXT,
+        seen_at INTEGER NOT NULL,
+        PRIMARY KEY (channel_id, ts, revision)
+    );
+";
+
 /// FTS virtual tables first (they shadow the content tables), then the
 /// content tables (their triggers and indexes drop with them).
 const SCHEMA_TEARDOWN: &str = "
     DROP TABLE IF EXISTS users_fts;
     DROP TABLE IF EXISTS channels_fts;
+    DROP TABLE IF EXISTS users_fuzzy;
+    DROP TABLE IF EXISTS channels_fuzzy;
     DROP TABLE IF EXISTS users;
     DROP TABLE IF EXISTS channels;
+    DROP TABLE IF EXISTS channel_members;
     DROP TABLE IF EXISTS metadata;
     DROP TABLE IF EXISTS locks;
 ";
 
-pub async fn initialize_schema(pool: &Pool<SqliteConnectionManager>) -> CacheResult<()> {
+pub async fn initialize_schema(
+    pool: &Pool<SqliteConnectionManager>,
+    tokenizer: FtsTokenizer,
+) -> CacheResult<()> {
     let mut conn = pool.get()?;
-    apply_schema(&mut conn)
+    apply_schema(&mut conn, tokenizer)
 }
 
 #[cfg(test)]
 pub fn initialize_schema_sync(pool: &Pool<SqliteConnectionManager>) -> CacheResult<()> {
     let mut conn = pool.get()?;
-    apply_schema(&mut conn)
+    apply_schema(&mut conn, FtsTokenizer::default())
 }
 
-fn apply_schema(conn: &mut Connection) -> CacheResult<()> {
+fn apply_schema(conn: &mut Connection, tokenizer: FtsTokenizer) -> CacheResult<()> {
     // The whole check-teardown-rebuild sequence runs in one IMMEDIATE
     // transaction: concurrent openers serialize on the write lock (via the
     // busy handler), and the version is re-read under that lock so the losers
@@ -149,19 +292,55 @@ fn apply_schema(conn: &mut Connection) -> CacheResult<()> {
     conn.execute_batch("PRAGMA busy_timeout = 5000;")?;
     let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
 
+    let fts_ddl = FTS_DDL.replace("{tokenizer}", tokenizer.fts_arg());
     if stored_schema_version(&tx)? != Some(SCHEMA_VERSION) {
         tx.execute_batch(SCHEMA_TEARDOWN)?;
         tx.execute_batch(SCHEMA_DDL)?;
+        tx.execute_batch(&fts_ddl)?;
         tx.execute(
             "INSERT OR REPLACE INTO metadata (key, value) VALUES ('schema_version', json(?))",
             params![SCHEMA_VERSION],
         )?;
+        store_tokenizer(&tx, tokenizer)?;
+    } else if stored_tokenizer(&tx)? != tokenizer {
+        tx.execute_batch("DROP TABLE IF EXISTS users_fts; DROP TABLE IF EXISTS channels_fts;")?;
+        tx.execute_batch(&fts_ddl)?;
+        tx.execute_batch(
+            "INSERT INTO users_fts(users_fts) VALUES ('rebuild');
+             INSERT INTO channels_fts(channels_fts) VALUES ('rebuild');",
+        )?;
+        store_tokenizer(&tx, tokenizer)?;
     }
+    tx.execute_batch(PERSISTENT_DDL)?;
 
     tx.commit()?;
     Ok(())
 }
 
+fn store_tokenizer(conn: &Connection, tokenizer: FtsTokenizer) -> CacheResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO metadata (key, value) VALUES ('fts_tokenizer', json_quote(?))",
+        params![tokenizer.as_str()],
+    )?;
+    Ok(())
+}
+
+/// Caches built before the tokenizer became configurable carry no entry and
+/// were built with the default.
+fn stored_tokenizer(conn: &Connection) -> CacheResult<FtsTokenizer> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT json_extract(value, '$') FROM metadata WHERE key = 'fts_tokenizer'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(stored
+        .as_deref()
+        .and_then(FtsTokenizer::parse)
+        .unwrap_or_default())
+}
+
 fn stored_schema_version(conn: &Connection) -> CacheResult<Option<i32>> {
     let metadata_exists: bool = conn.query_row(
         "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'metadata')",
@@ -199,7 +378,7 @@ mod tests {
     #[test]
     fn fresh_database_gets_current_version() {
         let mut conn = open_connection();
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
 
         assert_eq!(stored_schema_version(&conn).unwrap(), Some(SCHEMA_VERSION));
     }
@@ -213,14 +392,14 @@ mod tests {
     #[test]
     fn matching_version_preserves_cached_data() {
         let mut conn = open_connection();
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
         conn.execute(
             "INSERT INTO users (id, data) VALUES ('U1', json('{\"name\":\"alice\"}'))",
             [],
         )
         .unwrap();
 
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
 
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
@@ -231,7 +410,7 @@ mod tests {
     #[test]
     fn outdated_version_rebuilds_from_scratch() {
         let mut conn = open_connection();
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
         conn.execute(
             "INSERT INTO users (id, data) VALUES ('U1', json('{\"name\":\"alice\"}'))",
             [],
@@ -243,7 +422,7 @@ mod tests {
         )
         .unwrap();
 
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
 
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
@@ -252,10 +431,71 @@ mod tests {
         assert_eq!(stored_schema_version(&conn).unwrap(), Some(SCHEMA_VERSION));
     }
 
+    #[test]
+    fn rebuild_keeps_persistent_tables() {
+        let mut conn = open_connection();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
+        conn.execute(
+            "INSERT INTO saved_searches (name, query, options) VALUES ('deploys', 'deploy', json('{}'))",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES ('schema_version', json(?))",
+            params![SCHEMA_VERSION - 1],
+        )
+        .unwrap();
+
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM saved_searches", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn tokenizer_change_rebuilds_fts_and_keeps_rows() {
+        let mut conn = open_connection();
+        apply_schema(&mut conn, FtsTokenizer::Unicode61).unwrap();
+        conn.execute(
+            "INSERT INTO channels (id, data) VALUES ('C1', json('{\"name\":\"프로덕션배포\"}'))",
+            [],
+        )
+        .unwrap();
+        let matches = |conn: &Connection| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM channels_fts WHERE channels_fts MATCH '\"덕션배\"'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(matches(&conn), 0);
+
+        apply_schema(&mut conn, FtsTokenizer::Trigram).unwrap();
+
+        assert_eq!(stored_tokenizer(&conn).unwrap(), FtsTokenizer::Trigram);
+        assert_eq!(matches(&conn), 1);
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM channels", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn missing_tokenizer_entry_reads_as_default() {
+        let mut conn = open_connection();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
+        conn.execute("DELETE FROM metadata WHERE key = 'fts_tokenizer'", [])
+            .unwrap();
+        assert_eq!(stored_tokenizer(&conn).unwrap(), FtsTokenizer::Unicode61);
+    }
+
     #[test]
     fn non_integer_version_reads_as_no_version_and_rebuilds() {
         let mut conn = open_connection();
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
         conn.execute(
             "INSERT OR REPLACE INTO metadata (key, value) VALUES ('schema_version', json('\"corrupt\"'))",
             [],
@@ -264,7 +504,7 @@ mod tests {
 
         assert_eq!(stored_schema_version(&conn).unwrap(), None);
 
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
         assert_eq!(stored_schema_version(&conn).unwrap(), Some(SCHEMA_VERSION));
     }
 
@@ -278,7 +518,7 @@ mod tests {
         )
         .unwrap();
 
-        apply_schema(&mut conn).unwrap();
+        apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
 
         let generated_columns: i64 = conn
             .query_row(
@@ -297,7 +537,7 @@ mod tests {
         let path = dir.path().join("cache.db");
         {
             let mut conn = Connection::open(&path).unwrap();
-            apply_schema(&mut conn).unwrap();
+            apply_schema(&mut conn, FtsTokenizer::default()).unwrap();
             conn.execute(
                 "INSERT INTO users (id, data) VALUES ('U1', json('{\"name\":\"alice\"}'))",
                 [],
@@ -315,7 +555,7 @@ mod tests {
                 let path = path.clone();
                 std::thread::spawn(move || {
                     let mut conn = Connection::open(&path)?;
-                    apply_schema(&mut conn)
+                    apply_schema(&mut conn, FtsTokenizer::default())
                 })
             })
             .collect();
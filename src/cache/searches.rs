@@ -0,0 +1,279 @@
+use super::constants::SEARCH_HISTORY_RETENTION;
+use super::error::{CacheError, CacheResult};
+use super::sqlite_cache::SqliteCache;
+use crate::slack::SearchOptions;
+use rusqlite::{OptionalExtension, Row, params};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    pub options: SearchOptions,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub options: SearchOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved_name: Option<String>,
+    pub result_count: usize,
+    pub executed_at: i64,
+}
+
+impl SqliteCache {
+    /// Stores `query` + `options` under `name`, replacing any previous
+    /// definition while keeping its original `created_at`.
+    pub fn save_search(&self, name: &str, query: &str, options: &SearchOptions) -> CacheResult<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CacheError::InvalidInput(
+                "saved search name must not be blank".to_string(),
+            ));
+        }
+
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO saved_searches (name, query, options) VALUES (?1, ?2, json(?3))
+             ON CONFLICT(name) DO UPDATE SET
+                 query = excluded.query,
+                 options = excluded.options,
+                 updated_at = unixepoch()",
+            params![name, query, serde_json::to_string(options)?],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_saved_search(&self, name: &str) -> CacheResult<Option<SavedSearch>> {
+        let conn = self.pool.get()?;
+        let row = conn
+            .query_row(
+                "SELECT name, query, options, created_at, updated_at
+                 FROM saved_searches WHERE name = ?",
+                params![name.trim()],
+                read_saved_row,
+            )
+            .optional()?;
+        row.map(into_saved_search).transpose()
+    }
+
+    pub fn list_saved_searches(&self) -> CacheResult<Vec<SavedSearch>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT name, query, options, created_at, updated_at
+             FROM saved_searches ORDER BY name",
+        )?;
+        let rows = stmt
+            .query_map([], read_saved_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.into_iter().map(into_saved_search).collect()
+    }
+
+    pub fn delete_saved_search(&self, name: &str) -> CacheResult<bool> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute(
+            "DELETE FROM saved_searches WHERE name = ?",
+            params![name.trim()],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    pub fn record_search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        saved_name: Option<&str>,
+        result_count: usize,
+    ) -> CacheResult<()> {
+        let conn = self.pool.get()?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO search_history (query, options, saved_name, result_count)
+             VALUES (?1, json(?2), ?3, ?4)",
+            params![
+                query,
+                serde_json::to_string(options)?,
+                saved_name,
+                result_count as i64
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM search_history WHERE id NOT IN (
+                 SELECT id FROM search_history ORDER BY id DESC LIMIT ?
+             )",
+            params![SEARCH_HISTORY_RETENTION],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Most recent first.
+    pub fn recent_searches(&self, limit: usize) -> CacheResult<Vec<SearchHistoryEntry>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT query, options, saved_name, result_count, executed_at
+             FROM search_history ORDER BY id DESC LIMIT ?",
+        )?;
+        let rows = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(query, options, saved_name, result_count, executed_at)| {
+                Ok(SearchHistoryEntry {
+                    query,
+                    options: serde_json::from_str(&options)?,
+                    saved_name,
+                    result_count: result_count.max(0) as usize,
+                    executed_at,
+                })
+            })
+            .collect()
+    }
+}
+
+type SavedRow = (String, String, String, i64, i64);
+
+fn read_saved_row(row: &Row<'_>) -> rusqlite::Result<SavedRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+fn into_saved_search(
+    (name, query, options, created_at, updated_at): SavedRow,
+) -> CacheResult<SavedSearch> {
+    Ok(SavedSearch {
+        name,
+        query,
+        options: serde_json::from_str(&options)?,
+        created_at,
+        updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::{SearchChannelType, SearchContentType, SearchSort, SearchSortDirection};
+
+    fn options(limit: usize) -> SearchOptions {
+        SearchOptions {
+            limit,
+            channel_types: vec![SearchChannelType::PublicChannel],
+            content_types: vec![SearchContentType::Messages],
+            context_channel_id: Some("C1".into()),
+            include_archived_channels: false,
+            before: None,
+            after: Some(1_700_000_000),
+            include_bots: false,
+            disable_semantic_search: true,
+            sort: SearchSort::Timestamp,
+            sort_dir: SearchSortDirection::Desc,
+            include_context_messages: false,
+            from_user: None,
+            has: Vec::new(),
+            is: Vec::new(),
+            include_message_blocks: false,
+            highlight: false,
+        }
+    }
+
+    #[test]
+    fn saved_search_round_trips_options() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache
+            .save_search("deploys", "deploy failed", &options(25))
+            .unwrap();
+
+        let saved = cache.get_saved_search("deploys").unwrap().unwrap();
+        assert_eq!(saved.query, "deploy failed");
+        assert_eq!(saved.options.limit, 25);
+        assert_eq!(saved.options.context_channel_id.as_deref(), Some("C1"));
+        assert_eq!(saved.options.after, Some(1_700_000_000));
+        assert!(saved.options.disable_semantic_search);
+    }
+
+    #[test]
+    fn saving_same_name_replaces_definition() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache
+            .save_search("deploys", "deploy", &options(10))
+            .unwrap();
+        cache
+            .save_search("deploys", "rollback", &options(50))
+            .unwrap();
+
+        let all = cache.list_saved_searches().unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].query, "rollback");
+        assert_eq!(all[0].options.limit, 50);
+    }
+
+    #[test]
+    fn blank_name_is_rejected() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        assert!(matches!(
+            cache.save_search("  ", "q", &options(10)),
+            Err(CacheError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn delete_reports_whether_search_existed() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache
+            .save_search("deploys", "deploy", &options(10))
+            .unwrap();
+        assert!(cache.delete_saved_search("deploys").unwrap());
+        assert!(!cache.delete_saved_search("deploys").unwrap());
+        assert!(cache.get_saved_search("deploys").unwrap().is_none());
+    }
+
+    #[test]
+    fn history_is_newest_first_with_counts() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        cache.record_search("first", &options(10), None, 3).unwrap();
+        cache
+            .record_search("second", &options(10), Some("deploys"), 7)
+            .unwrap();
+
+        let history = cache.recent_searches(10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].query, "second");
+        assert_eq!(history[0].result_count, 7);
+        assert_eq!(history[0].saved_name.as_deref(), Some("deploys"));
+        assert_eq!(history[1].query, "first");
+    }
+
+    #[test]
+    fn history_is_pruned_to_retention() {
+        let cache = SqliteCache::new_sync(":memory:").unwrap();
+        for i in 0..(SEARCH_HISTORY_RETENTION + 5) {
+            cache
+                .record_search(&format!("q{i}"), &options(10), None, 0)
+                .unwrap();
+        }
+        let history = cache.recent_searches(1000).unwrap();
+        assert_eq!(history.len() as i64, SEARCH_HISTORY_RETENTION);
+        assert_eq!(
+            history[0].query,
+            format!("q{}", SEARCH_HISTORY_RETENTION + 4)
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Portable cache snapshots for `cache export`/`cache import`: gzip'd JSON
+//! lines, a header first and then one record per user, channel, membership
+//! and sync timestamp. Rows go through the normal save paths on import, so
+//! a snapshot stays valid across storage changes that keep `SCHEMA_VERSION`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use chrono::Utc;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::error::{CacheError, CacheResult};
+use super::schema::SCHEMA_VERSION;
+use super::sqlite_cache::SqliteCache;
+use crate::slack::types::{SlackChannel, SlackUser};
+
+const FORMAT: &str = "slack-cli-cache";
+/// Metadata carried over so an imported cache ages from its original sync,
+/// not from the import.
+const SYNC_KEYS: [&str; 3] = ["last_user_sync", "last_channel_sync", "last_member_sync"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub format: String,
+    pub schema_version: i32,
+    pub team_id: Option<String>,
+    pub created_at: i64,
+    pub users: usize,
+    pub channels: usize,
+    pub memberships: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Record {
+    User { data: SlackUser },
+    Channel { data: SlackChannel },
+    Member { channel_id: String, user_id: String },
+    Sync { key: String, value: String },
+}
+
+impl SqliteCache {
+    /// Writes every cached user, channel and membership to `writer`,
+    /// tagged with `team_id`.
+    pub fn export_snapshot(
+        &self,
+        writer: impl Write,
+        team_id: Option<&str>,
+    ) -> CacheResult<SnapshotHeader> {
+        let conn = self.pool.get()?;
+        // One read transaction so the header counts match the records.
+        let tx = conn.unchecked_transaction()?;
+        let count = |table: &str| -> CacheResult<usize> {
+            let count: i64 = tx.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })?;
+            Ok(count as usize)
+        };
+        let header = SnapshotHeader {
+            format: FORMAT.to_string(),
+            schema_version: SCHEMA_VERSION,
+            team_id: team_id.map(str::to_string),
+            created_at: Utc::now().timestamp(),
+            users: count("users")?,
+            channels: count("channels")?,
+            memberships: count("channel_members")?,
+        };
+
+        let mut out = GzEncoder::new(writer, Compression::default());
+        write_line(&mut out, &header)?;
+
+        let mut stmt = tx.prepare("SELECT data FROM users ORDER BY id")?;
+        for json in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let data = serde_json::from_str(&json?)?;
+            write_line(&mut out, &Record::User { data })?;
+        }
+        let mut stmt = tx.prepare("SELECT data FROM channels ORDER BY id")?;
+        for json in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let data = serde_json::from_str(&json?)?;
+            write_line(&mut out, &Record::Channel { data })?;
+        }
+        let mut stmt = tx.prepare("SELECT channel_id, user_id FROM channel_members")?;
+        for row in stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (channel_id, user_id) = row?;
+            write_line(
+                &mut out,
+                &Record::Member {
+                    channel_id,
+                    user_id,
+                },
+            )?;
+        }
+        let mut stmt =
+            tx.prepare("SELECT key, CAST(value AS TEXT) FROM metadata WHERE key IN (?1, ?2, ?3)")?;
+        for row in stmt.query_map(params![SYNC_KEYS[0], SYNC_KEYS[1], SYNC_KEYS[2]], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })? {
+            let (key, value) = row?;
+            write_line(&mut out, &Record::Sync { key, value })?;
+        }
+
+        out.finish()?.flush()?;
+        Ok(header)
+    }
+
+    /// Replaces the cached users, channels and memberships with the contents
+    /// of a snapshot; a table the snapshot has no rows for is left as is.
+    /// Refuses snapshots from another schema version, and from another
+    /// workspace unless `force` is set.
+    pub async fn import_snapshot(
+        &self,
+        reader: impl Read,
+        team_id: Option<&str>,
+        force: bool,
+    ) -> CacheResult<SnapshotHeader> {
+        let mut lines = BufReader::new(GzDecoder::new(reader)).lines();
+        let not_a_snapshot = || {
+            CacheError::SnapshotMismatch(
+                "not a slack-cli snapshot (expected a file written by `cache export`)".into(),
+            )
+        };
+        let header: SnapshotHeader = lines
+            .next()
+            .and_then(|line| serde_json::from_str(&line.ok()?).ok())
+            .ok_or_else(not_a_snapshot)?;
+        check_header(&header, team_id, force)?;
+
+        let (mut users, mut channels, mut memberships) = (Vec::new(), Vec::new(), Vec::new());
+        let mut sync = Vec::new();
+        for line in lines {
+            match serde_json::from_str(&line?)? {
+                Record::User { data } => users.push(data),
+                Record::Channel { data } => channels.push(data),
+                Record::Member {
+                    channel_id,
+                    user_id,
+                } => memberships.push((channel_id, user_id)),
+                Record::Sync { key, value } if SYNC_KEYS.contains(&key.as_str()) => {
+                    sync.push((key, value))
+                }
+                Record::Sync { .. } => {}
+            }
+        }
+
+        if !users.is_empty() {
+            self.save_users(users).await?;
+        }
+        if !channels.is_empty() {
+            self.save_channels(channels).await?;
+        }
+        if !memberships.is_empty() {
+            let mut by_channel: Vec<(String, Vec<String>)> = Vec::new();
+            memberships.sort();
+            for (channel_id, user_id) in memberships {
+                match by_channel.last_mut() {
+                    Some((last, members)) if *last == channel_id => members.push(user_id),
+                    _ => by_channel.push((channel_id, vec![user_id])),
+                }
+            }
+            self.save_channel_members(by_channel).await?;
+        }
+
+        let conn = self.pool.get()?;
+        for (key, value) in sync {
+            conn.execute(
+                "INSERT OR REPLACE INTO metadata (key, value) VALUES (?, ?)",
+                params![key, value],
+            )?;
+        }
+        Ok(header)
+    }
+}
+
+fn check_header(header: &SnapshotHeader, team_id: Option<&str>, force: bool) -> CacheResult<()> {
+    if header.format != FORMAT {
+        return Err(CacheError::SnapshotMismatch(format!(
+            "unknown snapshot format `{}`",
+            header.format
+        )));
+    }
+    if header.schema_version != SCHEMA_VERSION {
+        return Err(CacheError::SnapshotMismatch(format!(
+            "it was written with cache schema {} but this version uses {}; export it again with a matching slack-cli",
+            header.schema_version, SCHEMA_VERSION
+        )));
+    }
+    if let (Some(theirs), Some(ours)) = (header.team_id.as_deref(), team_id)
+        && theirs != ours
+        && !force
+    {
+        return Err(CacheError::SnapshotMismatch(format!(
+            "it belongs to workspace {theirs}, not {ours} (pass --force to import anyway)"
+        )));
+    }
+    Ok(())
+}
+
+fn write_line(out: &mut impl Write, value: &impl Serialize) -> CacheResult<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, name: &str) -> SlackUser {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name})).unwrap()
+    }
+
+    fn channel(id: &str, name: &str) -> SlackChannel {
+        serde_json::from_value(serde_json::json!({"id": id, "name": name, "is_channel": true}))
+            .unwrap()
+    }
+
+    async fn populated_cache() -> SqliteCache {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_users(vec![user("U1", "alice"), user("U2", "bob")])
+            .await
+            .unwrap();
+        cache
+            .save_channels(vec![channel("C1", "general")])
+            .await
+            .unwrap();
+        cache
+            .save_channel_members(vec![("C1".into(), vec!["U1".into(), "U2".into()])])
+            .await
+            .unwrap();
+        cache
+    }
+
+    #[tokio::test]
+    async fn snapshot_round_trips_into_an_empty_cache() {
+        let source = populated_cache().await;
+        let mut buf = Vec::new();
+        let header = source.export_snapshot(&mut buf, Some("T1")).unwrap();
+        assert_eq!(
+            (header.users, header.channels, header.memberships),
+            (2, 1, 2)
+        );
+
+        let target = SqliteCache::new(":memory:").await.unwrap();
+        let imported = target
+            .import_snapshot(buf.as_slice(), Some("T1"), false)
+            .await
+            .unwrap();
+        assert_eq!(imported.team_id.as_deref(), Some("T1"));
+        assert_eq!(target.get_counts().unwrap(), (2, 1));
+        assert_eq!(target.get_member_ids("C1").unwrap().unwrap(), ["U1", "U2"]);
+        assert_eq!(target.search_users("alice", 10, false).unwrap().len(), 1);
+        assert!(target.has_member_sync().unwrap());
+    }
+
+    #[tokio::test]
+    async fn foreign_workspace_needs_force() {
+        let source = populated_cache().await;
+        let mut buf = Vec::new();
+        source.export_snapshot(&mut buf, Some("T1")).unwrap();
+
+        let target = SqliteCache::new(":memory:").await.unwrap();
+        let err = target
+            .import_snapshot(buf.as_slice(), Some("T2"), false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("workspace T1, not T2"));
+        assert_eq!(target.get_counts().unwrap(), (0, 0));
+
+        target
+            .import_snapshot(buf.as_slice(), Some("T2"), true)
+            .await
+            .unwrap();
+        assert_eq!(target.get_counts().unwrap(), (2, 1));
+    }
+
+    #[test]
+    fn rejects_other_schema_versions_and_formats() {
+        let mut header = SnapshotHeader {
+            format: FORMAT.into(),
+            schema_version: SCHEMA_VERSION - 1,
+            team_id: None,
+            created_at: 0,
+            users: 0,
+            channels: 0,
+            memberships: 0,
+        };
+        assert!(check_header(&header, None, true).is_err());
+
+        header.schema_version = SCHEMA_VERSION;
+        assert!(check_header(&header, None, false).is_ok());
+
+        header.format = "other".into();
+        assert!(check_header(&header, None, false).is_err());
+    }
+}
@@ -5,7 +5,7 @@ use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 
 use super::error::CacheResult;
-use super::schema;
+use super::schema::{self, FtsTokenizer};
 
 /// Retry budget for the one-time WAL switch: SQLite returns SQLITE_BUSY
 /// without consulting the busy handler on journal-mode transitions, so a
@@ -31,6 +31,12 @@ fn is_busy(err: &rusqlite::Error) -> bool {
 
 impl SqliteCache {
     pub async fn new(path: impl AsRef<Path>) -> CacheResult<Self> {
+        Self::open(path, FtsTokenizer::default()).await
+    }
+
+    /// Opens the cache with `tokenizer` for the FTS tables; an existing cache
+    /// built with another tokenizer has its FTS index rebuilt in place.
+    pub async fn open(path: impl AsRef<Path>, tokenizer: FtsTokenizer) -> CacheResult<Self> {
         let path = path.as_ref();
 
         // Ensure parent directory exists (skip for in-memory databases)
@@ -85,7 +91,7 @@ impl SqliteCache {
             }
         }
 
-        schema::initialize_schema(&cache.pool).await?;
+        schema::initialize_schema(&cache.pool, tokenizer).await?;
         Ok(cache)
     }
 
@@ -0,0 +1,261 @@
+//! `cache stats`: row breakdowns, per-table freshness against the configured
+//! TTLs, and on-disk sizes.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::error::CacheResult;
+use super::sqlite_cache::SqliteCache;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStats {
+    pub users: UserCounts,
+    pub channels: ChannelCounts,
+    pub memberships: i64,
+    pub freshness: Vec<Freshness>,
+    /// True when any table is past its refresh threshold or never synced.
+    pub stale: bool,
+    pub db_bytes: i64,
+    pub fts_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserCounts {
+    pub total: i64,
+    pub humans: i64,
+    pub bots: i64,
+    pub deleted: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelCounts {
+    pub total: i64,
+    pub public: i64,
+    pub private: i64,
+    pub mpim: i64,
+    pub im: i64,
+    pub archived: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Freshness {
+    pub table: &'static str,
+    /// Unix seconds of the last completed sync.
+    pub last_sync: Option<i64>,
+    pub age_hours: Option<f64>,
+    pub ttl_hours: u64,
+    /// Age at which the table counts as stale (`refresh_threshold_percent`
+    /// of the TTL).
+    pub refresh_after_hours: f64,
+    /// Hours until the table turns stale; negative once it has.
+    pub remaining_hours: Option<f64>,
+    pub stale: bool,
+}
+
+impl SqliteCache {
+    pub fn stats(
+        &self,
+        users_ttl_hours: u64,
+        channels_ttl_hours: u64,
+        threshold_percent: u64,
+    ) -> CacheResult<CacheStats> {
+        let conn = self.pool.get()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        let users = conn.query_row(
+            "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE coalesce(is_bot, 0) = 0 AND json_extract(data, '$.deleted') IS NOT 1),
+                    COUNT(*) FILTER (WHERE is_bot = 1),
+                    COUNT(*) FILTER (WHERE json_extract(data, '$.deleted') = 1)
+             FROM users",
+            [],
+            |row| {
+                Ok(UserCounts {
+                    total: row.get(0)?,
+                    humans: row.get(1)?,
+                    bots: row.get(2)?,
+                    deleted: row.get(3)?,
+                })
+            },
+        )?;
+        let channels = conn.query_row(
+            "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE is_channel = 1 AND coalesce(is_private, 0) = 0),
+                    COUNT(*) FILTER (WHERE (is_group = 1 OR is_private = 1) AND coalesce(is_mpim, 0) = 0 AND coalesce(is_im, 0) = 0),
+                    COUNT(*) FILTER (WHERE is_mpim = 1),
+                    COUNT(*) FILTER (WHERE is_im = 1),
+                    COUNT(*) FILTER (WHERE is_archived = 1)
+             FROM channels",
+            [],
+            |row| {
+                Ok(ChannelCounts {
+                    total: row.get(0)?,
+                    public: row.get(1)?,
+                    private: row.get(2)?,
+                    mpim: row.get(3)?,
+                    im: row.get(4)?,
+                    archived: row.get(5)?,
+                })
+            },
+        )?;
+        let memberships =
+            conn.query_row("SELECT COUNT(*) FROM channel_members", [], |row| row.get(0))?;
+
+        let freshness = [
+            ("users", "last_user_sync", users_ttl_hours),
+            ("channels", "last_channel_sync", channels_ttl_hours),
+            ("channel_members", "last_member_sync", channels_ttl_hours),
+        ]
+        .into_iter()
+        .map(|(table, key, ttl_hours)| {
+            let last_sync = last_sync(&conn, key)?;
+            Ok(freshness(
+                table,
+                last_sync,
+                ttl_hours,
+                threshold_percent,
+                now,
+            ))
+        })
+        .collect::<CacheResult<Vec<_>>>()?;
+
+        Ok(CacheStats {
+            // Memberships are opt-in: never having synced them is not stale.
+            stale: freshness
+                .iter()
+                .any(|f| f.stale && (f.table != "channel_members" || f.last_sync.is_some())),
+            users,
+            channels,
+            memberships,
+            freshness,
+            db_bytes: conn.query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )?,
+            fts_bytes: conn.query_row(
+                "SELECT coalesce(sum(pgsize), 0) FROM dbstat
+                 WHERE name LIKE 'users_fts%' OR name LIKE 'channels_fts%'
+                    OR name LIKE 'users_fuzzy%' OR name LIKE 'channels_fuzzy%'",
+                [],
+                |row| row.get(0),
+            )?,
+        })
+    }
+}
+
+fn last_sync(conn: &Connection, key: &str) -> CacheResult<Option<i64>> {
+    Ok(conn
+        .query_row("SELECT value FROM metadata WHERE key = ?", [key], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+fn freshness(
+    table: &'static str,
+    last_sync: Option<i64>,
+    ttl_hours: u64,
+    threshold_percent: u64,
+    now: i64,
+) -> Freshness {
+    // Same integer arithmetic as `get_cache_status`, so both agree on when
+    // a table turns stale.
+    let refresh_after_hours = (ttl_hours * threshold_percent / 100) as f64;
+    let age_hours = last_sync.map(|ts| (now - ts) as f64 / 3600.0);
+    let remaining_hours = age_hours.map(|age| refresh_after_hours - age);
+
+    Freshness {
+        table,
+        last_sync,
+        age_hours,
+        ttl_hours,
+        refresh_after_hours,
+        remaining_hours,
+        stale: remaining_hours.is_none_or(|r| r <= 0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::types::{SlackChannel, SlackUser};
+
+    fn user(id: &str, is_bot: bool, deleted: bool) -> SlackUser {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "is_bot": is_bot, "deleted": deleted
+        }))
+        .unwrap()
+    }
+
+    fn channel(id: &str, flags: serde_json::Value) -> SlackChannel {
+        let mut value = serde_json::json!({"id": id, "name": id});
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(flags.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn breaks_down_users_and_channels() {
+        let cache = SqliteCache::new(":memory:").await.unwrap();
+        cache
+            .save_users(vec![
+                user("U1", false, false),
+                user("U2", false, true),
+                user("B1", true, false),
+            ])
+            .await
+            .unwrap();
+        cache
+            .save_channels(vec![
+                channel("C1", serde_json::json!({"is_channel": true})),
+                channel(
+                    "C2",
+                    serde_json::json!({"is_channel": true, "is_archived": true}),
+                ),
+                channel(
+                    "G1",
+                    serde_json::json!({"is_group": true, "is_private": true}),
+                ),
+                channel("D1", serde_json::json!({"is_im": true, "is_private": true})),
+            ])
+            .await
+            .unwrap();
+
+        let stats = cache.stats(168, 168, 10).unwrap();
+        let u = &stats.users;
+        assert_eq!((u.total, u.humans, u.bots, u.deleted), (3, 1, 1, 1));
+        let c = &stats.channels;
+        assert_eq!(
+            (c.total, c.public, c.private, c.im, c.archived),
+            (4, 2, 1, 1, 1)
+        );
+        assert!(stats.db_bytes > 0);
+        assert!(stats.fts_bytes > 0);
+
+        // Users and channels were just synced; memberships never were, which
+        // only counts against the cache once they have been synced.
+        assert!(!stats.freshness[0].stale);
+        assert!(stats.freshness[2].stale);
+        assert!(!stats.stale);
+    }
+
+    #[test]
+    fn remaining_time_counts_down_to_the_threshold() {
+        let now = 1_000_000;
+        let fresh = freshness("users", Some(now - 3600), 100, 10, now);
+        assert_eq!(fresh.refresh_after_hours, 10.0);
+        assert_eq!(fresh.remaining_hours, Some(9.0));
+        assert!(!fresh.stale);
+
+        let stale = freshness("users", Some(now - 11 * 3600), 100, 10, now);
+        assert_eq!(stale.remaining_hours, Some(-1.0));
+        assert!(stale.stale);
+
+        assert!(freshness("users", None, 100, 10, now).stale);
+    }
+}
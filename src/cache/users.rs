@@ -1,4 +1,7 @@
+use super::constants::FUZZY_SCAN_LIMIT;
 use super::error::{CacheError, CacheResult};
+use super::hangul;
+use super::helpers::{SyncStats, sync_from_staging};
 use super::sqlite_cache::SqliteCache;
 use crate::slack::types::SlackUser;
 use chrono::Utc;
@@ -8,7 +11,9 @@ use rusqlite::params;
 use rusqlite::OptionalExtension;
 
 impl SqliteCache {
-    pub async fn save_users(&self, users: Vec<SlackUser>) -> CacheResult<()> {
+    /// Replaces the cached users with `users`, writing only the rows that
+    /// were added, changed or removed.
+    pub async fn save_users(&self, users: Vec<SlackUser>) -> CacheResult<SyncStats> {
         if users.is_empty() {
             return Err(CacheError::InvalidInput("No users to save".to_string()));
         }
@@ -17,7 +22,7 @@ impl SqliteCache {
             .await
     }
 
-    pub(super) fn save_users_internal(&self, users: Vec<SlackUser>) -> CacheResult<()> {
+    pub(super) fn save_users_internal(&self, users: Vec<SlackUser>) -> CacheResult<SyncStats> {
         if users.is_empty() {
             return Err(CacheError::InvalidInput("No users to save".to_string()));
         }
@@ -53,11 +58,7 @@ impl SqliteCache {
             ));
         }
 
-        tx.execute("DELETE FROM users", [])?;
-        tx.execute(
-            "INSERT INTO users (id, data, updated_at) SELECT id, data, updated_at FROM users_new",
-            [],
-        )?;
+        let stats = sync_from_staging(&tx, "users", "users_new")?;
         tx.execute("DELETE FROM users_new", [])?;
 
         let now = Utc::now().timestamp();
@@ -67,7 +68,7 @@ impl SqliteCache {
         )?;
 
         tx.commit()?;
-        Ok(())
+        Ok(stats)
     }
 
     pub fn get_users(&self) -> CacheResult<Vec<SlackUser>> {
@@ -177,6 +178,35 @@ impl SqliteCache {
             return Ok(users);
         }
 
+        if hangul::is_choseong_query(query) {
+            let mut stmt = conn.prepare_cached(
+                "SELECT data FROM users WHERE ?1 OR is_bot = 0 OR is_bot IS NULL
+                 ORDER BY name LIMIT ?2",
+            )?;
+            let users = stmt
+                .query_map(params![include_bots, FUZZY_SCAN_LIMIT], |row| {
+                    row.get::<_, String>(0)
+                })?
+                .map(|json| Ok(serde_json::from_str::<SlackUser>(&json?)?))
+                // Errors pass the filter so `collect` surfaces them.
+                .filter(|user: &CacheResult<SlackUser>| {
+                    user.as_ref().map_or(true, |user| {
+                        let profile = user.profile.as_ref();
+                        [
+                            Some(user.name.as_str()),
+                            profile.and_then(|p| p.display_name.as_deref()),
+                            profile.and_then(|p| p.real_name.as_deref()),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .any(|field| hangul::choseong_matches(query, field))
+                    })
+                })
+                .take(limit)
+                .collect::<CacheResult<Vec<_>>>()?;
+            return Ok(users);
+        }
+
         let bot_filter = if include_bots {
             ""
         } else {
@@ -358,6 +388,38 @@ mod tests {
         assert!(bob.is_none());
     }
 
+    #[tokio::test]
+    async fn test_save_users_only_touches_changed_rows() {
+        let cache = setup_cache().await;
+        let users = vec![
+            create_test_user("U1", "alice", Some("alice@example.com"), false),
+            create_test_user("U2", "bob", Some("bob@example.com"), false),
+        ];
+        cache.save_users(users.clone()).await.unwrap();
+
+        let unchanged = cache.save_users(users).await.unwrap();
+        assert_eq!(
+            unchanged,
+            SyncStats {
+                unchanged: 2,
+                ..SyncStats::default()
+            }
+        );
+
+        let stats = cache
+            .save_users(vec![
+                create_test_user("U1", "alice", Some("alice@new.example.com"), false),
+                create_test_user("U3", "carol", None, false),
+            ])
+            .await
+            .unwrap();
+        assert_eq!((stats.added, stats.changed, stats.removed), (1, 1, 1));
+
+        let found = cache.search_users("alice@new", 10, false).unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(cache.search_users("bob", 10, false).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_users_filters_bots() {
         let cache = setup_cache().await;
@@ -618,4 +680,62 @@ mod tests {
 
         assert!(result1.is_ok() || result2.is_ok());
     }
+
+    fn korean_user(id: &str, name: &str, real_name: &str) -> SlackUser {
+        let mut user = create_test_user(id, name, None, false);
+        if let Some(profile) = user.profile.as_mut() {
+            profile.real_name = Some(real_name.to_string());
+        }
+        user
+    }
+
+    #[rstest]
+    #[case::choseong("ㄱㅁㅅ", &["U1"])]
+    #[case::partial_choseong("김ㅁ", &["U1"])]
+    #[case::syllable_stays_literal("곽ㅁ", &["U4"])]
+    #[case::choseong_after_latin("ㅈㅎ", &["U2"])]
+    #[case::hangul_substring("민수", &["U1"])]
+    #[case::latin_in_mixed_name("Jihoon", &["U2"])]
+    #[case::no_match("ㅂㅂㅂ", &[])]
+    #[tokio::test]
+    async fn test_search_users_mixed_script(#[case] query: &str, #[case] expected: &[&str]) {
+        let cache = setup_cache().await;
+        cache
+            .save_users(vec![
+                korean_user("U1", "minsu.kim", "김민수 (Minsu Kim)"),
+                korean_user("U2", "jihoon.park", "Park 지훈 Jihoon"),
+                korean_user("U3", "seoyeon", "이서연"),
+                korean_user("U4", "minho.gwak", "곽민호"),
+            ])
+            .await
+            .unwrap();
+
+        let ids: Vec<String> = cache
+            .search_users(query, 10, false)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.id)
+            .collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn test_search_users_choseong_surfaces_corrupt_rows() {
+        let cache = setup_cache().await;
+        cache
+            .save_users(vec![korean_user("U1", "minsu.kim", "김민수")])
+            .await
+            .unwrap();
+        cache
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                r#"UPDATE users SET data = '{"id": 1, "name": "minsu.kim"}'"#,
+                [],
+            )
+            .unwrap();
+
+        assert!(cache.search_users("ㄱㅁㅅ", 10, false).is_err());
+    }
 }
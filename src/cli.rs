@@ -1,7 +1,8 @@
 use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
-use crate::auth::AuthMethod;
+use crate::auth::{AuthMethod, TokenKind};
+use crate::format::OutputFormat;
 use crate::slack::{
     SearchChannelType, SearchContentType, SearchOptions, SearchSort, SearchSortDirection,
 };
@@ -19,6 +20,14 @@ fn parse_search_limit(value: &str) -> Result<usize, String> {
     }
 }
 
+fn parse_mentions_limit(value: &str) -> Result<usize, String> {
+    let max = SearchOptions::MAX_LIMIT;
+    match value.parse::<usize>() {
+        Ok(limit) if limit <= max => Ok(limit),
+        _ => Err(format!("mentions must be between 0 and {max}")),
+    }
+}
+
 #[derive(Parser)]
 #[command(
     name = "slack-cli",
@@ -50,13 +59,93 @@ pub struct Cli {
     )]
     pub profile: Option<String>,
 
-    #[arg(long, short, global = true, help = "Emit machine-readable JSON output")]
+    #[arg(
+        long,
+        env = "SLACK_TEAM",
+        global = true,
+        hide_env_values = true,
+        value_name = "TEAM_ID",
+        help = "Enterprise Grid workspace to scope list calls and the cache to"
+    )]
+    pub team: Option<String>,
+
+    #[arg(
+        long = "as",
+        value_enum,
+        global = true,
+        value_name = "TOKEN",
+        help = "Call Slack with this token type, overriding per-method defaults"
+    )]
+    pub token_as: Option<TokenKindArg>,
+
+    #[arg(
+        long,
+        short,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Output format for results"
+    )]
+    pub output: Option<OutputFormat>,
+
+    /// Alias for `--output json`. After `normalize_output`, true for every
+    /// machine-readable format, which is what most commands branch on.
+    #[arg(
+        long,
+        short,
+        global = true,
+        conflicts_with = "output",
+        help = "Emit machine-readable JSON output (same as --output json)"
+    )]
     pub json: bool,
 
+    /// Applied to the JSON a command prints; implies `--json`. With
+    /// `--output ndjson` it runs once per line.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILTER",
+        help = "Filter JSON output with a jq expression, e.g. '.[].email'"
+    )]
+    pub jq: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "TEMPLATE",
+        help = "Text-mode line per result for users/channels/messages, e.g. '{{date}} {{user_name}} » {{text}}'"
+    )]
+    pub format_template: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "ZONE",
+        help = "Time zone for printed timestamps and date arguments (IANA name or 'local')"
+    )]
+    pub tz: Option<String>,
+
     #[arg(long, short, global = true, help = "Enable debug logging")]
     pub verbose: bool,
 }
 
+impl Cli {
+    pub fn output_format(&self) -> OutputFormat {
+        match self.output {
+            Some(format) => format,
+            None if self.json || self.jq.is_some() => OutputFormat::Json,
+            None => OutputFormat::Text,
+        }
+    }
+
+    /// Folds `--output` into `json` so the many "is this for a program?"
+    /// checks (progress notes, search highlighting, confirmation messages)
+    /// also cover ndjson/csv/tsv.
+    pub fn normalize_output(&mut self) {
+        self.json = self.output_format().is_machine_readable();
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Command {
     #[command(about = "Search users by name/email or get by IDs")]
@@ -94,7 +183,7 @@ pub enum Command {
             long,
             value_delimiter = ',',
             value_name = "FIELDS",
-            help = "Additional fields to include [id,name,type,members,topic,purpose,created,creator,is_member,is_archived,is_private,user]"
+            help = "Additional fields to include [id,name,type,members,topic,purpose,created,creator,is_member,is_archived,is_private,is_ext_shared,is_org_shared,user]"
         )]
         expand: Option<Vec<String>>,
     },
@@ -136,12 +225,36 @@ pub enum Command {
         limit: usize,
         #[arg(long)]
         cursor: Option<String>,
-        #[arg(long, help = "Start time (Unix timestamp or ISO date: 2025-12-11)")]
+        #[arg(
+            long,
+            help = "Start time (Unix timestamp, 2025-12-11, 2h, 3d, yesterday, last monday)"
+        )]
         oldest: Option<String>,
-        #[arg(long, help = "End time (Unix timestamp or ISO date: 2025-12-11)")]
+        #[arg(long, help = "End time (same forms as --oldest)")]
         latest: Option<String>,
         #[arg(long, help = "Exclude bot messages")]
         exclude_bots: bool,
+        #[arg(
+            long,
+            help = "Follow next_cursor through every page, printing pages as they arrive (ignores --limit)"
+        )]
+        all: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Like --all, but stop after fetching N messages"
+        )]
+        max: Option<usize>,
+        #[arg(
+            long,
+            help = "Fetch replies of threaded messages and show them under their parent"
+        )]
+        with_threads: bool,
+        #[arg(
+            long,
+            help = "Show each message's date in its author's profile time zone"
+        )]
+        author_tz: bool,
         #[arg(
             long,
             value_delimiter = ',',
@@ -160,6 +273,11 @@ pub enum Command {
         limit: usize,
         #[arg(long, help = "Exclude bot-authored replies")]
         exclude_bots: bool,
+        #[arg(
+            long,
+            help = "Show each message's date in its author's profile time zone"
+        )]
+        author_tz: bool,
         #[arg(
             long,
             value_delimiter = ',',
@@ -169,12 +287,97 @@ pub enum Command {
         expand: Option<Vec<String>>,
     },
 
-    #[command(about = "List channel members")]
-    Members { channel: String },
+    #[command(
+        about = "Unread counts per channel and recent mentions of you",
+        args_conflicts_with_subcommands = true
+    )]
+    Inbox {
+        #[command(subcommand)]
+        action: Option<InboxAction>,
+        #[arg(
+            long,
+            default_value = "7d",
+            help = "Oldest mention to list (same forms as messages --oldest)"
+        )]
+        since: String,
+        #[arg(
+            long,
+            default_value = "20",
+            value_parser = parse_mentions_limit,
+            help = "Most mentions to list (0-100; 0 skips the search)"
+        )]
+        mentions: usize,
+    },
+
+    #[command(
+        about = "Channel activity: messages per day/hour, top posters, threads, reactions, bots"
+    )]
+    Stats {
+        channel: String,
+        #[arg(
+            long,
+            default_value = "7d",
+            help = "Start of the window (Unix timestamp, 2025-12-11, 2h, 3d, yesterday, last monday)"
+        )]
+        since: String,
+        #[arg(
+            long,
+            help = "Read the local message archive (cache.archive_messages) instead of Slack"
+        )]
+        archive: bool,
+        #[arg(
+            long,
+            help = "Skip the per-thread conversations.replies calls (no median first reply)"
+        )]
+        no_reply_times: bool,
+    },
+
+    #[command(
+        about = "Show a message's archived revisions and deletion (needs cache.archive_messages)"
+    )]
+    HistoryOf { channel: String, ts: String },
 
-    #[command(about = "Search Slack context with Real-time Search API")]
+    #[command(
+        about = "List channel members (live; the cache is the fallback and `--query` source)"
+    )]
+    Members {
+        channel: String,
+        #[arg(
+            long,
+            help = "Only members whose handle, display name, real name or email contains this"
+        )]
+        query: Option<String>,
+    },
+
+    #[command(
+        about = "List the channels a user belongs to, among the channels you are in (from the cache)"
+    )]
+    UserChannels {
+        user: String,
+        #[arg(long, help = "Include archived channels")]
+        include_archived: bool,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "FIELDS",
+            help = "Additional fields to include (same vocabulary as `channels --expand`)"
+        )]
+        expand: Option<Vec<String>>,
+    },
+
+    #[command(
+        about = "Search Slack context with Real-time Search API",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
     Search {
-        query: String,
+        #[command(subcommand)]
+        action: Option<SearchAction>,
+        #[arg(
+            required = true,
+            help = "Search text (put `--` first to search for run/saved/delete/history)"
+        )]
+        query: Option<String>,
         #[arg(
             long,
             default_value = "10",
@@ -193,9 +396,15 @@ pub enum Command {
         content_types: Vec<SearchContentType>,
         #[arg(long, help = "Restrict the search to one channel (ID or name)")]
         channel: Option<String>,
-        #[arg(long, help = "Only results before this time (Unix ts or YYYY-MM-DD)")]
+        #[arg(
+            long,
+            help = "Only results before this time (Unix ts, YYYY-MM-DD, 2h, yesterday, ...)"
+        )]
         before: Option<String>,
-        #[arg(long, help = "Only results after this time (Unix ts or YYYY-MM-DD)")]
+        #[arg(
+            long,
+            help = "Only results after this time (Unix ts, YYYY-MM-DD, 2h, yesterday, ...)"
+        )]
         after: Option<String>,
         #[arg(
             long = "include-context",
@@ -212,6 +421,23 @@ pub enum Command {
         sort: SearchSort,
         #[arg(long, value_enum, default_value = "desc")]
         sort_dir: SearchSortDirection,
+        #[arg(
+            long,
+            help = "Resume from a previous result's next_cursor (JSON envelope)"
+        )]
+        cursor: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "limit",
+            help = "Stream every result page by page as NDJSON, ignoring --limit"
+        )]
+        all: bool,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Save this query and its options under NAME for `search run`"
+        )]
+        save: Option<String>,
     },
 
     #[command(about = "Add reaction to a message")]
@@ -264,6 +490,29 @@ pub enum Command {
     #[command(about = "List bookmarks")]
     Bookmarks { channel: String },
 
+    #[command(
+        about = "Set a Slack reminder",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
+    Remind {
+        #[command(subcommand)]
+        action: Option<RemindAction>,
+        #[arg(required = true)]
+        text: Option<String>,
+        #[arg(
+            long,
+            required = true,
+            help = "When to remind (15:00, friday 09:00, tomorrow 09:30, in 30m, 2025-12-11 09:00, Unix timestamp); weekdays and bare times mean the next one"
+        )]
+        at: Option<String>,
+        #[arg(
+            long,
+            help = "Remind someone else (ID, @handle or name) instead of you"
+        )]
+        user: Option<String>,
+    },
+
     #[command(about = "Authentication management")]
     Auth {
         #[command(subcommand)]
@@ -331,6 +580,53 @@ pub struct MessageContent {
     pub metadata: Option<String>,
 }
 
+#[derive(Subcommand)]
+pub enum SearchAction {
+    #[command(about = "Replay a saved search")]
+    Run {
+        name: String,
+        #[arg(long, value_parser = parse_search_limit, help = "Override the saved limit (1-100)")]
+        limit: Option<usize>,
+    },
+
+    #[command(about = "List saved searches")]
+    Saved,
+
+    #[command(about = "Delete a saved search")]
+    Delete { name: String },
+
+    #[command(about = "Show recent searches with result counts")]
+    History {
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum InboxAction {
+    #[command(about = "Mark a channel read up to its latest message")]
+    MarkRead {
+        channel: String,
+        #[arg(long, help = "Mark read up to this message ts instead")]
+        ts: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RemindAction {
+    #[command(about = "List your open reminders")]
+    List {
+        #[arg(long, help = "Include completed reminders")]
+        all: bool,
+    },
+
+    #[command(about = "Mark a reminder complete")]
+    Complete { id: String },
+
+    #[command(about = "Delete a reminder")]
+    Delete { id: String },
+}
+
 #[derive(Subcommand)]
 pub enum AuthAction {
     #[command(
@@ -361,6 +657,12 @@ pub enum AuthAction {
 
         #[arg(long, help = "Do not open a browser; print the URL instead")]
         no_browser: bool,
+
+        #[arg(
+            long,
+            help = "Also request the conversation write scopes (channels:write, ...) that `inbox mark-read` needs"
+        )]
+        write_scopes: bool,
     },
 
     #[command(
@@ -386,6 +688,15 @@ pub enum AuthAction {
         verify: bool,
     },
 
+    #[command(
+        about = "Check which commands the stored tokens can run",
+        long_about = "Check which commands the stored tokens can run.\n\
+                      Compares the scopes granted at login with those each command needs\n\
+                      and prints the re-login command that fixes any gaps.\n\
+                      Use the global --profile to inspect a specific profile (default: active)."
+    )]
+    Doctor,
+
     #[command(about = "List stored authentication profiles")]
     Profiles,
 
@@ -408,6 +719,21 @@ impl From<AuthMethodArg> for AuthMethod {
     }
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum TokenKindArg {
+    User,
+    Bot,
+}
+
+impl From<TokenKindArg> for TokenKind {
+    fn from(value: TokenKindArg) -> Self {
+        match value {
+            TokenKindArg::User => TokenKind::User,
+            TokenKindArg::Bot => TokenKind::Bot,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum ConfigAction {
     #[command(about = "Show current configuration")]
@@ -426,6 +752,11 @@ pub enum CacheAction {
     Refresh {
         #[arg(value_enum, default_value = "all")]
         target: RefreshTarget,
+
+        /// Run as the detached stale-cache refresher: skip quietly when
+        /// another refresh holds the lock.
+        #[arg(long, hide = true)]
+        background: bool,
     },
 
     #[command(about = "Show cache statistics")]
@@ -433,11 +764,59 @@ pub enum CacheAction {
 
     #[command(about = "Show cache file path")]
     Path,
+
+    #[command(about = "Check database and search index integrity")]
+    Check {
+        #[arg(long, help = "Rebuild the search indexes when they are out of sync")]
+        repair: bool,
+    },
+
+    #[command(about = "Merge search index segments and refresh query statistics")]
+    Optimize,
+
+    #[command(about = "Reclaim unused space in the cache file")]
+    Vacuum,
+
+    #[command(about = "Remove cached users and/or channels")]
+    Clear {
+        #[arg(value_enum, default_value = "all")]
+        target: RefreshTarget,
+    },
+
+    #[command(about = "Write users, channels and memberships to a gzip'd snapshot file")]
+    Export {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    #[command(about = "Load a snapshot written by `cache export`")]
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        #[arg(long, help = "Import a snapshot taken from another workspace")]
+        force: bool,
+    },
+
+    #[command(about = "List cache locks, or break stuck ones")]
+    Locks {
+        #[arg(
+            long = "break",
+            value_name = "KEY",
+            conflicts_with = "break_all",
+            help = "Remove the lock on KEY, whoever holds it"
+        )]
+        break_key: Option<String>,
+        #[arg(long, help = "Remove every lock")]
+        break_all: bool,
+    },
 }
 
 #[derive(ValueEnum, Clone)]
 pub enum RefreshTarget {
     Users,
     Channels,
+    /// Users and channels
     All,
+    /// Members of every channel you belong to (one `conversations.members` call each)
+    Members,
 }
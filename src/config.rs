@@ -1,8 +1,13 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use crate::auth::TokenKind;
+use crate::cache::FtsTokenizer;
+use crate::format::print_json;
 use crate::slack::ConversationType;
+use crate::slack::api_config::get_api_config;
 
 fn expand_tilde(path: &Path) -> PathBuf {
     if let Some(path_str) = path.to_str() {
@@ -33,6 +38,9 @@ pub struct Config {
 
     #[serde(default)]
     pub connection: ConnectionConfig,
+
+    #[serde(default)]
+    pub auth: AuthConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -52,6 +60,40 @@ pub struct CacheConfig {
 
     #[serde(default = "default_channel_types")]
     pub channel_types: Vec<ConversationType>,
+
+    /// FTS5 tokenizer for user/channel search: `unicode61` (default) or
+    /// `trigram` for substring matches in CJK names.
+    #[serde(default)]
+    pub tokenizer: FtsTokenizer,
+
+    /// What to do when the cache passes its refresh threshold.
+    #[serde(default)]
+    pub stale_refresh: StaleRefresh,
+
+    /// Keep every message read by `messages`/`thread`, with its past
+    /// revisions and deletion time, for `history-of`. Off by default since
+    /// it copies message text to disk.
+    #[serde(default)]
+    pub archive_messages: bool,
+}
+
+/// `warn` prints a hint to run `cache refresh`; `background` answers from
+/// the stale cache and refreshes it in a detached process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleRefresh {
+    #[default]
+    Warn,
+    Background,
+}
+
+impl StaleRefresh {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warn => "warn",
+            Self::Background => "background",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -65,6 +107,22 @@ pub struct OutputConfig {
 
     #[serde(default = "default_messages_fields")]
     pub messages_fields: Vec<String>,
+
+    /// Text-mode line templates (`{{field}}` placeholders), overridden by
+    /// `--format-template`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub users_template: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels_template: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_template: Option<String>,
+
+    /// IANA zone (or `local`) for printed timestamps and date arguments,
+    /// overridden by `--tz`. Defaults to the system zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
 }
 
 fn default_users_fields() -> Vec<String> {
@@ -104,6 +162,10 @@ impl Default for OutputConfig {
             users_fields: default_users_fields(),
             channels_fields: default_channels_fields(),
             messages_fields: default_messages_fields(),
+            users_template: None,
+            channels_template: None,
+            messages_template: None,
+            timezone: None,
         }
     }
 }
@@ -146,6 +208,20 @@ pub struct ConnectionConfig {
 
     #[serde(default)]
     pub app_distribution: SlackAppDistribution,
+
+    /// Enterprise Grid workspace that list calls and the cache are scoped
+    /// to. Overridden by the global `--team` flag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Slack method → token type (`user` | `bot`), replacing that method's
+    /// built-in token preference. The global `--as` flag still wins.
+    #[serde(default)]
+    pub method_tokens: BTreeMap<String, TokenKind>,
 }
 
 fn default_ttl_hours() -> u64 {
@@ -190,6 +266,9 @@ impl Default for CacheConfig {
             refresh_threshold_percent: 10,
             data_path: None,
             channel_types: default_channel_types(),
+            tokenizer: FtsTokenizer::default(),
+            stale_refresh: StaleRefresh::default(),
+            archive_messages: false,
         }
     }
 }
@@ -212,12 +291,17 @@ impl Default for ConnectionConfig {
             timeout_seconds: 30,
             rate_limit_per_minute: 20,
             app_distribution: SlackAppDistribution::default(),
+            team_id: None,
         }
     }
 }
 
 impl Config {
-    pub fn load(config_path: Option<PathBuf>, cli_data_dir: Option<PathBuf>) -> Result<Self> {
+    pub fn load(
+        config_path: Option<PathBuf>,
+        cli_data_dir: Option<PathBuf>,
+        cli_team: Option<String>,
+    ) -> Result<Self> {
         let mut config = Self::default();
 
         let path = config_path.or_else(Self::default_config_path);
@@ -231,6 +315,16 @@ impl Config {
             config.cache.data_path = Some(dir);
         }
 
+        if let Some(team) = cli_team {
+            config.connection.team_id = Some(team);
+        }
+        config.connection.team_id = config
+            .connection
+            .team_id
+            .take()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+
         config.connection.api_base_url = config
             .connection
             .api_base_url
@@ -285,6 +379,21 @@ impl Config {
             anyhow::bail!("connection timeout and rate limit values must be greater than zero");
         }
 
+        if let Some(team) = &self.connection.team_id
+            && !team.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            anyhow::bail!("connection.team_id must be a Slack team id such as T0123ABCD");
+        }
+
+        if let Some(method) = self
+            .auth
+            .method_tokens
+            .keys()
+            .find(|m| get_api_config(m).is_none())
+        {
+            anyhow::bail!("auth.method_tokens: unknown Slack method '{method}'");
+        }
+
         Ok(())
     }
 
@@ -347,13 +456,18 @@ impl Config {
             path = canonical;
         }
 
-        path.push("slack.db");
+        // Workspaces of one Enterprise Grid org share user/channel ids but not
+        // membership or visibility, so each selected team gets its own file.
+        match &self.connection.team_id {
+            Some(team) => path.push(format!("slack-{team}.db")),
+            None => path.push("slack.db"),
+        }
         path
     }
 
     pub fn show(&self, as_json: bool) -> Result<()> {
         if as_json {
-            println!("{}", serde_json::to_string_pretty(self)?);
+            print_json(self);
             return Ok(());
         }
 
@@ -381,6 +495,8 @@ impl Config {
             .map(|t| t.as_api_str())
             .collect();
         println!("  channel_types: {:?}", channel_types);
+        println!("  tokenizer: {}", self.cache.tokenizer.as_str());
+        println!("  stale_refresh: {}", self.cache.stale_refresh.as_str());
         println!("\nOutput:");
         println!("  users_fields: {:?}", self.output.users_fields);
         println!("  channels_fields: {:?}", self.output.channels_fields);
@@ -397,6 +513,10 @@ impl Config {
             "  rate_limit_per_minute: {}",
             self.connection.rate_limit_per_minute
         );
+        println!(
+            "  team_id: {}",
+            self.connection.team_id.as_deref().unwrap_or("-")
+        );
         println!(
             "  app_distribution: {}",
             match self.connection.app_distribution {
@@ -404,6 +524,15 @@ impl Config {
                 SlackAppDistribution::MarketplaceOrInternal => "marketplace_or_internal",
             }
         );
+        println!("\nAuth:");
+        if self.auth.method_tokens.is_empty() {
+            println!("  method_tokens: (none)");
+        } else {
+            println!("  method_tokens:");
+            for (method, kind) in &self.auth.method_tokens {
+                println!("    {method}: {kind}");
+            }
+        }
 
         Ok(())
     }
@@ -515,6 +644,28 @@ mod tests {
                     ConversationType::PrivateChannel,
                 ]
             );
+            assert_eq!(config.tokenizer, FtsTokenizer::Unicode61);
+            assert_eq!(config.stale_refresh, StaleRefresh::Warn);
+        }
+
+        #[test]
+        fn load_reads_trigram_tokenizer() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(&path, "[cache]\ntokenizer = \"trigram\"\n").unwrap();
+
+            let config = Config::load(Some(path), None, None).unwrap();
+            assert_eq!(config.cache.tokenizer, FtsTokenizer::Trigram);
+        }
+
+        #[test]
+        fn load_reads_background_stale_refresh() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(&path, "[cache]\nstale_refresh = \"background\"\n").unwrap();
+
+            let config = Config::load(Some(path), None, None).unwrap();
+            assert_eq!(config.cache.stale_refresh, StaleRefresh::Background);
         }
 
         #[test]
@@ -527,7 +678,7 @@ mod tests {
             )
             .unwrap();
 
-            let config = Config::load(Some(path), None).unwrap();
+            let config = Config::load(Some(path), None, None).unwrap();
             assert_eq!(config.connection.api_base_url, "https://slack.com/api");
         }
 
@@ -537,7 +688,7 @@ mod tests {
             let path = dir.path().join("config.toml");
             std::fs::write(&path, "[cache]\nchannel_types = []\n").unwrap();
 
-            let err = Config::load(Some(path), None).unwrap_err();
+            let err = Config::load(Some(path), None, None).unwrap_err();
             assert!(err.to_string().contains("channel_types must not be empty"));
         }
 
@@ -547,7 +698,7 @@ mod tests {
             let path = dir.path().join("config.toml");
             std::fs::write(&path, "[connection]\ntimeout_seconds = 0\n").unwrap();
 
-            let err = Config::load(Some(path), None).unwrap_err();
+            let err = Config::load(Some(path), None, None).unwrap_err();
             assert!(
                 err.to_string()
                     .contains("connection timeout and rate limit values must be greater than zero")
@@ -562,7 +713,7 @@ mod tests {
             let path = dir.path().join("config.toml");
             std::fs::write(&path, "user_token = \"xoxp-stale\"\n").unwrap();
 
-            let err = Config::load(Some(path), None).unwrap_err();
+            let err = Config::load(Some(path), None, None).unwrap_err();
             let chain: String = err
                 .chain()
                 .map(|c| c.to_string())
@@ -577,7 +728,7 @@ mod tests {
             let path = dir.path().join("config.toml");
             std::fs::write(&path, "[retry]\nmax_attempts = 0\n").unwrap();
 
-            let err = Config::load(Some(path), None).unwrap_err();
+            let err = Config::load(Some(path), None, None).unwrap_err();
             assert!(
                 err.to_string()
                     .contains("retry.max_attempts must be greater than zero")
@@ -590,13 +741,66 @@ mod tests {
             let path = dir.path().join("config.toml");
             std::fs::write(&path, "[cache]\nrefresh_threshold_percent = 101\n").unwrap();
 
-            let err = Config::load(Some(path), None).unwrap_err();
+            let err = Config::load(Some(path), None, None).unwrap_err();
             assert!(
                 err.to_string()
                     .contains("refresh_threshold_percent must be between 1 and 100")
             );
         }
 
+        #[test]
+        fn load_parses_method_token_overrides() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(
+                &path,
+                "[auth.method_tokens]\n\"chat.postMessage\" = \"user\"\n",
+            )
+            .unwrap();
+
+            let config = Config::load(Some(path), None, None).unwrap();
+            assert_eq!(
+                config.auth.method_tokens.get("chat.postMessage"),
+                Some(&TokenKind::User)
+            );
+        }
+
+        #[test]
+        fn load_rejects_unknown_method_token_override() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(&path, "[auth.method_tokens]\n\"chat.post\" = \"bot\"\n").unwrap();
+
+            let err = Config::load(Some(path), None, None).unwrap_err();
+            assert!(err.to_string().contains("unknown Slack method 'chat.post'"));
+        }
+
+        #[test]
+        fn cli_team_overrides_config_and_partitions_db_path() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(&path, "[connection]\nteam_id = \"T1\"\n").unwrap();
+
+            let config = Config::load(
+                Some(path),
+                Some(dir.path().to_path_buf()),
+                Some(" T2 ".into()),
+            )
+            .unwrap();
+            assert_eq!(config.connection.team_id.as_deref(), Some("T2"));
+            assert!(config.db_path().ends_with("slack-T2.db"));
+        }
+
+        #[test]
+        fn load_rejects_malformed_team_id() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("config.toml");
+            std::fs::write(&path, "[connection]\nteam_id = \"../T1\"\n").unwrap();
+
+            let err = Config::load(Some(path), None, None).unwrap_err();
+            assert!(err.to_string().contains("connection.team_id"));
+        }
+
         #[test]
         fn retry_config_defaults() {
             let config = RetryConfig::default();
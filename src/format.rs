@@ -1,33 +1,362 @@
-use crate::cache::SqliteCache;
+use crate::activity::ChannelActivity;
+use crate::cache::{
+    CacheStats, IntegrityReport, LockEntry, MessageHistory, MessageRevision, Ranked, SavedSearch,
+    SearchHistoryEntry, SqliteCache,
+};
+use crate::inbox::Inbox;
 use crate::slack::types::{SlackChannel, SlackMessage, SlackUser};
-use crate::slack::{Bookmark, CustomEmoji, MessageReactions, PinnedMessage, SearchResults};
-use chrono::DateTime;
+use crate::slack::{
+    Bookmark, CustomEmoji, MessageReactions, PinnedMessage, Reminder, SearchResults,
+};
 use serde_json::{Value, json};
 use std::collections::HashSet;
+use std::io::Write;
+
+/// `--output`: how list results are printed. `Text` is each command's own
+/// human layout; the rest are generic renderings of the same projected
+/// rows, so `--expand` and the configured field lists decide the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Table,
+}
 
-pub fn print_users(users: &[SlackUser], fields: &[String], as_json: bool) {
-    if users.is_empty() {
-        if as_json {
-            println!("[]");
-        } else {
-            println!("No users found");
+impl OutputFormat {
+    /// Formats meant for other programs: progress notes stay off stdout
+    /// and search highlighting is disabled.
+    pub fn is_machine_readable(self) -> bool {
+        matches!(self, Self::Json | Self::Ndjson | Self::Csv | Self::Tsv)
+    }
+}
+
+/// Prints projected rows in any format but `Text`. `columns` orders the
+/// csv/tsv/table columns (with a header even when there are no rows);
+/// JSON keeps the objects as they are.
+fn print_rows(rows: &[Value], columns: &[String], format: OutputFormat) {
+    match format {
+        // Nothing to print and no header to derive: an empty result.
+        OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Table if columns.is_empty() => {}
+        OutputFormat::Json => print_json(rows),
+        OutputFormat::Ndjson => {
+            for row in rows {
+                print_json_line(row);
+            }
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            println!(
+                "{}",
+                delimited_line(columns.iter().map(String::as_str), format)
+            );
+            for row in rows {
+                println!("{}", delimited_row(row, columns, format));
+            }
+        }
+        OutputFormat::Table | OutputFormat::Text => print_table(rows, columns),
+    }
+}
+
+/// Like `print_rows` for types printed as they serialize. Columns are the
+/// union of the rows' keys, in `serde_json`'s sorted map order.
+fn print_serialized<T: serde::Serialize>(items: &[T], format: OutputFormat) {
+    let rows: Vec<Value> = items
+        .iter()
+        .filter_map(|item| serde_json::to_value(item).ok())
+        .collect();
+    print_rows(&rows, &columns_of(&rows), format);
+}
+
+/// Prints `value` as indented JSON, through the `--jq` filter when one is
+/// set.
+pub fn print_json<T: serde::Serialize + ?Sized>(value: &T) {
+    emit_json(value, true);
+}
+
+/// Like `print_json` on a single line, for ndjson and one-line results.
+pub fn print_json_line<T: serde::Serialize + ?Sized>(value: &T) {
+    emit_json(value, false);
+}
+
+fn emit_json<T: serde::Serialize + ?Sized>(value: &T, pretty: bool) {
+    match serde_json::to_value(value) {
+        // Like `println!`, minus the panic when stdout is a closed pipe.
+        Ok(value) => {
+            let _ = write_json(&mut std::io::stdout().lock(), value, pretty);
+        }
+        Err(e) => eprintln!("Error serializing output: {}", e),
+    }
+}
+
+/// Writes each result of the `--jq` filter (or `value` itself) on its own
+/// line. Filtered strings are written raw, as `jq -r` would, so a query
+/// like `.[].email` pipes straight into other tools. A failing filter is
+/// reported on stderr and turns the exit status non-zero.
+fn write_json(out: &mut impl Write, value: Value, pretty: bool) -> std::io::Result<()> {
+    let values = match crate::jq::apply(value) {
+        Ok(values) => values,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            crate::jq::mark_failed();
+            return Ok(());
+        }
+    };
+    for value in values {
+        match value {
+            Value::String(s) if crate::jq::is_active() => writeln!(out, "{}", s)?,
+            value if pretty => writeln!(out, "{:#}", value)?,
+            value => writeln!(out, "{}", value)?,
         }
-        return;
     }
+    Ok(())
+}
+
+fn columns_of(rows: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for key in rows
+        .iter()
+        .filter_map(Value::as_object)
+        .flat_map(|o| o.keys())
+    {
+        if !columns.contains(key) {
+            columns.push(key.clone());
+        }
+    }
+    columns
+}
+
+/// Scalar cells print bare, nested values as compact JSON, null as empty.
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn delimited_row(row: &Value, columns: &[String], format: OutputFormat) -> String {
+    let cells: Vec<String> = columns.iter().map(|c| cell(row.get(c))).collect();
+    delimited_line(cells.iter().map(String::as_str), format)
+}
+
+/// CSV quotes per RFC 4180; TSV has no quoting, so tabs and line breaks
+/// inside a cell are escaped as `\t`/`\n`.
+fn delimited_line<'a>(cells: impl Iterator<Item = &'a str>, format: OutputFormat) -> String {
+    let cells: Vec<String> = cells
+        .map(|cell| match format {
+            OutputFormat::Tsv => cell
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r"),
+            _ if cell.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            }
+            _ => cell.to_string(),
+        })
+        .collect();
+    let sep = if format == OutputFormat::Tsv {
+        "\t"
+    } else {
+        ","
+    };
+    cells.join(sep)
+}
 
-    if as_json {
-        let filtered: Vec<Value> = users
+fn print_table(rows: &[Value], columns: &[String]) {
+    for line in table_lines(rows, columns) {
+        println!("{}", line);
+    }
+}
+
+fn table_lines(rows: &[Value], columns: &[String]) -> Vec<String> {
+    let grid: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|c| cell(row.get(c)).replace(['\n', '\r', '\t'], " "))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            grid.iter()
+                .map(|r| r[i].chars().count())
+                .chain([c.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    [line(columns), line(&rule)]
+        .into_iter()
+        .chain(grid.iter().map(|row| line(row)))
+        .collect()
+}
+
+/// Field vocabularies for `--expand` and text templates.
+pub const USER_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "real_name",
+    "display_name",
+    "email",
+    "status",
+    "status_emoji",
+    "avatar",
+    "title",
+    "timezone",
+    "is_admin",
+    "is_bot",
+    "deleted",
+];
+
+pub const CHANNEL_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "type",
+    "members",
+    "topic",
+    "purpose",
+    "created",
+    "creator",
+    "is_member",
+    "is_archived",
+    "is_private",
+    "is_ext_shared",
+    "is_org_shared",
+    "user",
+];
+
+pub const MESSAGE_FIELDS: &[&str] = &[
+    "ts",
+    "user",
+    "bot_id",
+    "username",
+    "text",
+    "thread_ts",
+    "reply_count",
+    "subtype",
+    "metadata",
+    "blocks",
+    "attachments",
+    "reactions",
+    "edited",
+    "parent_user_id",
+    "reply_users",
+    "reply_users_count",
+    "latest_reply",
+    "channel",
+    "permalink",
+    "date",
+    "user_name",
+];
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TemplateError {
+    #[error("unclosed placeholder in template near `{0}`")]
+    Unclosed(String),
+
+    #[error("unknown template field `{field}` (available: {available})")]
+    UnknownField { field: String, available: String },
+}
+
+/// `--format-template` / `output.*_template`: the text-mode line printed
+/// per result, with `{{field}}` placeholders. `\t` and `\n` are unescaped
+/// so tab-separated layouts can be typed on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Field(String),
+}
+
+impl Template {
+    /// Parses `source`, rejecting placeholders outside `vocabulary`.
+    pub fn parse(source: &str, vocabulary: &[&str]) -> Result<Self, TemplateError> {
+        let source = source.replace("\\t", "\t").replace("\\n", "\n");
+        let mut parts = Vec::new();
+        let mut rest = source.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start..].find("}}") else {
+                return Err(TemplateError::Unclosed(
+                    rest[start..].chars().take(20).collect(),
+                ));
+            };
+            let field = rest[start + 2..start + len].trim();
+            if !vocabulary.contains(&field) {
+                return Err(TemplateError::UnknownField {
+                    field: field.to_string(),
+                    available: vocabulary.join(", "),
+                });
+            }
+            if start > 0 {
+                parts.push(TemplatePart::Literal(rest[..start].to_string()));
+            }
+            parts.push(TemplatePart::Field(field.to_string()));
+            rest = &rest[start + len + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    fn render(&self, mut field: impl FnMut(&str) -> String) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.clone(),
+                TemplatePart::Field(name) => field(name),
+            })
+            .collect()
+    }
+}
+
+pub fn print_users(
+    users: &[SlackUser],
+    fields: &[String],
+    format: OutputFormat,
+    template: Option<&Template>,
+) {
+    if format != OutputFormat::Text {
+        let rows: Vec<Value> = users
             .iter()
             .map(|u| filter_user_fields(u, fields))
             .collect();
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&filtered).unwrap_or_default()
-        );
+        print_rows(&rows, fields, format);
+        return;
+    }
+
+    if users.is_empty() {
+        println!("No users found");
         return;
     }
 
     for user in users {
+        if let Some(template) = template {
+            println!("{}", template.render(|field| get_user_field(user, field)));
+            continue;
+        }
+
         let mut parts: Vec<String> = Vec::new();
 
         for field in fields {
@@ -39,6 +368,37 @@ pub fn print_users(users: &[SlackUser], fields: &[String], as_json: bool) {
     }
 }
 
+/// `users --query`: like `print_users`, with each JSON object carrying the
+/// match `score`.
+pub fn print_ranked_users(
+    ranked: &[Ranked<SlackUser>],
+    fields: &[String],
+    format: OutputFormat,
+    template: Option<&Template>,
+) {
+    if format != OutputFormat::Text {
+        let rows: Vec<Value> = ranked
+            .iter()
+            .map(|r| with_score(filter_user_fields(&r.item, fields), r.score))
+            .collect();
+        print_rows(&rows, &with_score_column(fields), format);
+        return;
+    }
+    let users: Vec<SlackUser> = ranked.iter().map(|r| r.item.clone()).collect();
+    print_users(&users, fields, format, template);
+}
+
+fn with_score(mut row: Value, score: f64) -> Value {
+    row["score"] = json!((score * 1000.0).round() / 1000.0);
+    row
+}
+
+fn with_score_column(fields: &[String]) -> Vec<String> {
+    let mut columns = fields.to_vec();
+    columns.push("score".to_string());
+    columns
+}
+
 fn filter_user_fields(user: &SlackUser, fields: &[String]) -> Value {
     let mut obj = serde_json::Map::new();
 
@@ -164,29 +524,32 @@ fn get_user_field(user: &SlackUser, field: &str) -> String {
     }
 }
 
-pub fn print_channels(channels: &[SlackChannel], fields: &[String], as_json: bool) {
-    if channels.is_empty() {
-        if as_json {
-            println!("[]");
-        } else {
-            println!("No channels found");
-        }
-        return;
-    }
-
-    if as_json {
-        let filtered: Vec<Value> = channels
+pub fn print_channels(
+    channels: &[SlackChannel],
+    fields: &[String],
+    format: OutputFormat,
+    template: Option<&Template>,
+) {
+    if format != OutputFormat::Text {
+        let rows: Vec<Value> = channels
             .iter()
             .map(|c| filter_channel_fields(c, fields))
             .collect();
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&filtered).unwrap_or_default()
-        );
+        print_rows(&rows, fields, format);
+        return;
+    }
+
+    if channels.is_empty() {
+        println!("No channels found");
         return;
     }
 
     for ch in channels {
+        if let Some(template) = template {
+            println!("{}", template.render(|field| get_channel_field(ch, field)));
+            continue;
+        }
+
         let mut parts: Vec<String> = Vec::new();
 
         for field in fields {
@@ -198,6 +561,24 @@ pub fn print_channels(channels: &[SlackChannel], fields: &[String], as_json: boo
     }
 }
 
+pub fn print_ranked_channels(
+    ranked: &[Ranked<SlackChannel>],
+    fields: &[String],
+    format: OutputFormat,
+    template: Option<&Template>,
+) {
+    if format != OutputFormat::Text {
+        let rows: Vec<Value> = ranked
+            .iter()
+            .map(|r| with_score(filter_channel_fields(&r.item, fields), r.score))
+            .collect();
+        print_rows(&rows, &with_score_column(fields), format);
+        return;
+    }
+    let channels: Vec<SlackChannel> = ranked.iter().map(|r| r.item.clone()).collect();
+    print_channels(&channels, fields, format, template);
+}
+
 fn filter_channel_fields(ch: &SlackChannel, fields: &[String]) -> Value {
     let mut obj = serde_json::Map::new();
 
@@ -242,6 +623,12 @@ fn filter_channel_fields(ch: &SlackChannel, fields: &[String]) -> Value {
             "is_private" => {
                 obj.insert("is_private".to_string(), json!(ch.is_private));
             }
+            "is_ext_shared" => {
+                obj.insert("is_ext_shared".to_string(), json!(ch.is_ext_shared));
+            }
+            "is_org_shared" => {
+                obj.insert("is_org_shared".to_string(), json!(ch.is_org_shared));
+            }
             _ => {}
         }
     }
@@ -286,8 +673,9 @@ fn get_channel_field(ch: &SlackChannel, field: &str) -> String {
         "created" => ch
             .created
             .map(|ts| {
-                chrono::DateTime::from_timestamp(ts, 0)
-                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                crate::time::display()
+                    .zone
+                    .format_date(ts)
                     .unwrap_or_else(|| ts.to_string())
             })
             .unwrap_or_else(|| "-".to_string()),
@@ -295,62 +683,195 @@ fn get_channel_field(ch: &SlackChannel, field: &str) -> String {
         "is_member" => if ch.is_member { "member" } else { "-" }.to_string(),
         "is_archived" => if ch.is_archived { "archived" } else { "-" }.to_string(),
         "is_private" => if ch.is_private { "private" } else { "public" }.to_string(),
+        "is_ext_shared" => if ch.is_ext_shared { "ext_shared" } else { "-" }.to_string(),
+        "is_org_shared" => if ch.is_org_shared { "org_shared" } else { "-" }.to_string(),
         _ => "-".to_string(),
     }
 }
 
 pub fn print_messages(
     messages: &[SlackMessage],
-    as_json: bool,
+    format: OutputFormat,
     fields: &[String],
     cache: Option<&SqliteCache>,
+    template: Option<&Template>,
 ) {
     let allowed: HashSet<&str> = fields.iter().map(String::as_str).collect();
 
-    if as_json {
-        let projected = project_messages(messages, &allowed, cache);
-        match serde_json::to_string_pretty(&projected) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing messages: {}", e),
-        }
+    if format != OutputFormat::Text {
+        let has_replies = messages.iter().any(|m| m.replies.is_some());
+        print_rows(
+            &project_messages(messages, &allowed, cache),
+            &message_columns(fields, has_replies),
+            format,
+        );
         return;
     }
 
-    render_messages(messages, &allowed, cache);
+    render_messages(messages, &allowed, cache, template);
 }
 
 /// One page of `conversations.history` plus the cursor to the next page.
 /// The JSON shape is an envelope — `{messages, next_cursor}` with a null
 /// cursor on the last page — because following the cursor is the caller's
 /// job for this command. Internally-paginating commands (`thread`) keep the
-/// bare array shape of `print_messages`.
+/// bare array shape of `print_messages`. Row formats have nowhere to put
+/// the cursor, so it goes to stderr as in text mode.
 pub fn print_history(
     messages: &[SlackMessage],
     next_cursor: Option<&str>,
-    as_json: bool,
+    format: OutputFormat,
     fields: &[String],
     cache: Option<&SqliteCache>,
+    template: Option<&Template>,
 ) {
     let allowed: HashSet<&str> = fields.iter().map(String::as_str).collect();
 
-    if as_json {
+    if format == OutputFormat::Json {
         let envelope = json!({
             "messages": project_messages(messages, &allowed, cache),
             "next_cursor": next_cursor,
         });
-        match serde_json::to_string_pretty(&envelope) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing messages: {}", e),
-        }
+        print_json(&envelope);
         return;
     }
 
-    render_messages(messages, &allowed, cache);
+    print_messages(messages, format, fields, cache, template);
     if let Some(cursor) = next_cursor {
         eprintln!("More messages available: rerun with --cursor {}", cursor);
     }
 }
 
+/// Column order for messages in row formats: the selected fields, then
+/// the nested thread when `--with-threads` fetched one.
+fn message_columns(fields: &[String], with_replies: bool) -> Vec<String> {
+    let mut columns = fields.to_vec();
+    if with_replies {
+        columns.push("replies".to_string());
+    }
+    columns
+}
+
+/// `messages --all`/`--max`: prints history pages as they are fetched
+/// instead of collecting the whole window. JSON keeps the `print_history`
+/// envelope, written incrementally with one message per line, so a single
+/// page and a streamed window parse the same way. A table needs every row
+/// to size its columns, and a `--jq` filter the whole envelope, so those
+/// are printed once at the end.
+pub struct HistoryStream<'a> {
+    allowed: HashSet<&'a str>,
+    format: OutputFormat,
+    columns: Vec<String>,
+    cache: Option<&'a SqliteCache>,
+    template: Option<&'a Template>,
+    buffered: Vec<Value>,
+    started: bool,
+    written: usize,
+}
+
+impl<'a> HistoryStream<'a> {
+    pub fn new(
+        format: OutputFormat,
+        fields: &'a [String],
+        with_replies: bool,
+        cache: Option<&'a SqliteCache>,
+        template: Option<&'a Template>,
+    ) -> Self {
+        Self {
+            allowed: fields.iter().map(String::as_str).collect(),
+            format,
+            columns: message_columns(fields, with_replies),
+            cache,
+            template,
+            buffered: Vec::new(),
+            started: false,
+            written: 0,
+        }
+    }
+
+    pub fn page(&mut self, messages: &[SlackMessage]) -> std::io::Result<()> {
+        let mut out = std::io::stdout().lock();
+        match self.format {
+            OutputFormat::Text => {
+                if !messages.is_empty() {
+                    render_messages(messages, &self.allowed, self.cache, self.template);
+                }
+            }
+            _ if self.is_buffered() => {
+                self.buffered
+                    .extend(project_messages(messages, &self.allowed, self.cache))
+            }
+            format => {
+                self.start(&mut out)?;
+                for (i, msg) in messages.iter().enumerate() {
+                    let value = project_message(msg, &self.allowed, self.cache);
+                    match format {
+                        OutputFormat::Json => {
+                            let sep = if self.written + i == 0 { "" } else { "," };
+                            write!(out, "{sep}\n  {}", value)?;
+                        }
+                        OutputFormat::Ndjson => write_json(&mut out, value, false)?,
+                        _ => writeln!(out, "{}", delimited_row(&value, &self.columns, format))?,
+                    }
+                }
+            }
+        }
+        self.written += messages.len();
+        out.flush()
+    }
+
+    pub fn finish(mut self, next_cursor: Option<&str>) -> std::io::Result<()> {
+        let mut out = std::io::stdout().lock();
+        match self.format {
+            OutputFormat::Json if self.is_buffered() => {
+                print_json(&json!({
+                    "messages": self.buffered,
+                    "next_cursor": next_cursor,
+                }));
+                return Ok(());
+            }
+            OutputFormat::Json => {
+                self.start(&mut out)?;
+                let newline = if self.written == 0 { "" } else { "\n" };
+                writeln!(out, "{newline}], \"next_cursor\": {}}}", json!(next_cursor))?;
+                return out.flush();
+            }
+            OutputFormat::Text if self.written == 0 => writeln!(out, "No messages found")?,
+            OutputFormat::Table => print_table(&self.buffered, &self.columns),
+            OutputFormat::Csv | OutputFormat::Tsv => self.start(&mut out)?,
+            _ => {}
+        }
+        if let Some(cursor) = next_cursor {
+            eprintln!("More messages available: rerun with --cursor {}", cursor);
+        }
+        out.flush()
+    }
+
+    fn is_buffered(&self) -> bool {
+        match self.format {
+            OutputFormat::Table => true,
+            OutputFormat::Json => crate::jq::is_active(),
+            _ => false,
+        }
+    }
+
+    /// Writes the JSON envelope opening or the csv/tsv header, once.
+    fn start(&mut self, out: &mut impl Write) -> std::io::Result<()> {
+        if std::mem::replace(&mut self.started, true) {
+            return Ok(());
+        }
+        match self.format {
+            OutputFormat::Json => write!(out, "{{\"messages\": ["),
+            OutputFormat::Csv | OutputFormat::Tsv => writeln!(
+                out,
+                "{}",
+                delimited_line(self.columns.iter().map(String::as_str), self.format)
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
 fn project_messages(
     messages: &[SlackMessage],
     allowed: &HashSet<&str>,
@@ -366,52 +887,96 @@ fn render_messages(
     messages: &[SlackMessage],
     allowed: &HashSet<&str>,
     cache: Option<&SqliteCache>,
+    template: Option<&Template>,
 ) {
     if messages.is_empty() {
         println!("No messages found");
         return;
     }
 
-    let expand_date = allowed.contains("date");
-    let expand_user_name = allowed.contains("user_name");
-
     for msg in messages {
-        // Priority: user > username (bot display name) > bot_id > "system"
-        let author_id = msg
+        match template {
+            Some(template) => render_templated_message(msg, template, cache, ""),
+            None => render_message(msg, allowed, cache, ""),
+        }
+    }
+}
+
+/// A template replaces the whole default layout, attachments and reply
+/// counts included; fetched replies get the same line, indented.
+fn render_templated_message(
+    msg: &SlackMessage,
+    template: &Template,
+    cache: Option<&SqliteCache>,
+    indent: &str,
+) {
+    println!("{}{}", indent, templated_message_line(msg, template, cache));
+    for reply in msg.replies.iter().flatten() {
+        render_templated_message(reply, template, cache, &format!("{indent}    "));
+    }
+}
+
+fn templated_message_line(
+    msg: &SlackMessage,
+    template: &Template,
+    cache: Option<&SqliteCache>,
+) -> String {
+    let value = serde_json::to_value(msg).unwrap_or_else(|_| json!({}));
+    template.render(|field| match field {
+        "date" => message_date(msg, cache).unwrap_or_default(),
+        "user_name" => msg
             .user
             .as_deref()
-            .or(msg.username.as_deref())
-            .or(msg.bot_id.as_deref())
-            .unwrap_or("system");
-
-        let author = if expand_user_name {
-            msg.user
-                .as_ref()
-                .and_then(|id| resolve_user_name(id, cache))
-                .unwrap_or_else(|| author_id.to_string())
-        } else {
-            author_id.to_string()
-        };
+            .and_then(|id| resolve_user_name(id, cache))
+            .unwrap_or_default(),
+        field => cell(value.get(field)),
+    })
+}
 
-        let ts_display = if expand_date {
-            format_timestamp(&msg.ts).unwrap_or_else(|| msg.ts.clone())
-        } else {
-            msg.ts.clone()
-        };
+fn render_message(
+    msg: &SlackMessage,
+    allowed: &HashSet<&str>,
+    cache: Option<&SqliteCache>,
+    indent: &str,
+) {
+    // Priority: user > username (bot display name) > bot_id > "system"
+    let author_id = msg
+        .user
+        .as_deref()
+        .or(msg.username.as_deref())
+        .or(msg.bot_id.as_deref())
+        .unwrap_or("system");
+
+    let author = if allowed.contains("user_name") {
+        msg.user
+            .as_ref()
+            .and_then(|id| resolve_user_name(id, cache))
+            .unwrap_or_else(|| author_id.to_string())
+    } else {
+        author_id.to_string()
+    };
 
-        println!("[{}] {}: {}", ts_display, author, msg.text);
+    let ts_display = if allowed.contains("date") {
+        message_date(msg, cache).unwrap_or_else(|| msg.ts.clone())
+    } else {
+        msg.ts.clone()
+    };
 
-        // Render attachments (wee-slack style)
-        if let Some(attachments) = &msg.attachments {
-            for att in attachments {
-                render_attachment(att);
-            }
-        }
+    println!("{}[{}] {}: {}", indent, ts_display, author, msg.text);
 
-        if let Some(count) = msg.reply_count {
-            println!("  └─ {} replies", count);
+    // Render attachments (wee-slack style)
+    if let Some(attachments) = &msg.attachments {
+        for att in attachments {
+            render_attachment(att, indent);
         }
     }
+
+    if let Some(count) = msg.reply_count {
+        println!("{}  └─ {} replies", indent, count);
+    }
+    for reply in msg.replies.iter().flatten() {
+        render_message(reply, allowed, cache, &format!("{indent}    "));
+    }
 }
 
 fn project_message(
@@ -424,7 +989,7 @@ fn project_message(
         map.retain(|key, _| allowed.contains(key.as_str()));
 
         if allowed.contains("date")
-            && let Some(date_str) = format_timestamp(&msg.ts)
+            && let Some(date_str) = message_date(msg, cache)
         {
             map.insert("date".to_string(), json!(date_str));
         }
@@ -434,13 +999,38 @@ fn project_message(
         {
             map.insert("user_name".to_string(), json!(name));
         }
+        if let Some(replies) = &msg.replies {
+            map.insert(
+                "replies".to_string(),
+                json!(project_messages(replies, allowed, cache)),
+            );
+        }
     }
     value
 }
 
 fn format_timestamp(ts: &str) -> Option<String> {
     let ts_secs: i64 = ts.split('.').next()?.parse().ok()?;
-    DateTime::from_timestamp(ts_secs, 0).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+    crate::time::display().zone.format(ts_secs, false)
+}
+
+/// A message's `date`. With `--author-tz` it is shown in the author's
+/// profile zone, labelled because the zone can change from line to line.
+fn message_date(msg: &SlackMessage, cache: Option<&SqliteCache>) -> Option<String> {
+    let display = crate::time::display();
+    let author_zone = display
+        .author_zones
+        .then(|| author_zone(msg, cache))
+        .flatten();
+    match author_zone {
+        Some(zone) => zone.format(msg.ts.split('.').next()?.parse().ok()?, true),
+        None => format_timestamp(&msg.ts),
+    }
+}
+
+fn author_zone(msg: &SlackMessage, cache: Option<&SqliteCache>) -> Option<crate::time::Zone> {
+    let user = cache?.get_user_by_id(msg.user.as_deref()?).ok().flatten()?;
+    user.profile?.timezone?.parse().ok()
 }
 
 fn resolve_user_name(user_id: &str, cache: Option<&SqliteCache>) -> Option<String> {
@@ -451,13 +1041,13 @@ fn resolve_user_name(user_id: &str, cache: Option<&SqliteCache>) -> Option<Strin
         .and_then(|u| u.profile.and_then(|p| p.real_name))
 }
 
-fn render_attachment(att: &Value) {
+fn render_attachment(att: &Value, indent: &str) {
     let mut rendered = false;
 
     if let Some(pretext) = att.get("pretext").and_then(|v| v.as_str())
         && !pretext.is_empty()
     {
-        println!("  │ {}", pretext);
+        println!("{indent}  │ {}", pretext);
         rendered = true;
     }
 
@@ -465,15 +1055,15 @@ fn render_attachment(att: &Value) {
     let title = att.get("title").and_then(|v| v.as_str());
     match (author, title) {
         (Some(a), Some(t)) => {
-            println!("  │ {}: {}", a, t);
+            println!("{indent}  │ {}: {}", a, t);
             rendered = true;
         }
         (Some(a), None) => {
-            println!("  │ {}", a);
+            println!("{indent}  │ {}", a);
             rendered = true;
         }
         (None, Some(t)) => {
-            println!("  │ {}", t);
+            println!("{indent}  │ {}", t);
             rendered = true;
         }
         _ => {}
@@ -485,7 +1075,7 @@ fn render_attachment(att: &Value) {
         for line in text.lines() {
             let trimmed = line.trim();
             if !trimmed.is_empty() {
-                println!("  │ {}", trimmed);
+                println!("{indent}  │ {}", trimmed);
             }
         }
         rendered = true;
@@ -498,12 +1088,12 @@ fn render_attachment(att: &Value) {
             match (field_title, field_value) {
                 (Some(t), Some(v)) => {
                     let first_line = v.lines().next().unwrap_or(v);
-                    println!("  │ {}: {}", t, first_line);
+                    println!("{indent}  │ {}: {}", t, first_line);
                     rendered = true;
                 }
                 (None, Some(v)) => {
                     let first_line = v.lines().next().unwrap_or(v);
-                    println!("  │ {}", first_line);
+                    println!("{indent}  │ {}", first_line);
                     rendered = true;
                 }
                 _ => {}
@@ -514,7 +1104,7 @@ fn render_attachment(att: &Value) {
     if let Some(footer) = att.get("footer").and_then(|v| v.as_str())
         && !footer.is_empty()
     {
-        println!("  │ {}", footer);
+        println!("{indent}  │ {}", footer);
         rendered = true;
     }
 
@@ -522,41 +1112,60 @@ fn render_attachment(att: &Value) {
         && let Some(fallback) = att.get("fallback").and_then(|v| v.as_str())
         && !fallback.is_empty()
     {
-        println!("  │ {}", fallback);
+        println!("{indent}  │ {}", fallback);
     }
 }
 
-pub fn print_members(member_ids: &[String], cache: &crate::cache::SqliteCache, as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(member_ids) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing members: {}", e),
-        }
+/// JSON stays a bare array of ids; the other row formats carry the cached
+/// handle next to each id.
+pub fn print_members(
+    member_ids: &[String],
+    cache: &crate::cache::SqliteCache,
+    format: OutputFormat,
+) {
+    if format == OutputFormat::Json {
+        print_json(member_ids);
         return;
     }
 
-    if member_ids.is_empty() {
+    if format == OutputFormat::Text && member_ids.is_empty() {
         println!("No members found");
         return;
     }
 
-    let users = cache.get_users().unwrap_or_default();
+    let names: std::collections::HashMap<String, String> = cache
+        .get_users_by_ids(member_ids)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| (u.id, u.name))
+        .collect();
+
+    if format != OutputFormat::Text {
+        let rows: Vec<Value> = member_ids
+            .iter()
+            .map(|id| json!({"id": id, "name": names.get(id)}))
+            .collect();
+        print_rows(&rows, &["id".to_string(), "name".to_string()], format);
+        return;
+    }
 
     for id in member_ids {
-        if let Some(user) = users.iter().find(|u| &u.id == id) {
-            println!("{:<20} {}", user.name, id);
-        } else {
-            println!("{}", id);
+        match names.get(id) {
+            Some(name) => println!("{:<20} {}", name, id),
+            None => println!("{}", id),
         }
     }
 }
 
-pub fn print_reactions(reactions: &MessageReactions, as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(reactions) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing reactions: {}", e),
-        }
+/// JSON keeps the `{channel, ts, reactions}` object; the other row formats
+/// list one reaction per row.
+pub fn print_reactions(reactions: &MessageReactions, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        print_json(reactions);
+        return;
+    }
+    if format != OutputFormat::Text {
+        print_serialized(&reactions.reactions, format);
         return;
     }
 
@@ -570,12 +1179,9 @@ pub fn print_reactions(reactions: &MessageReactions, as_json: bool) {
     }
 }
 
-pub fn print_emoji(emoji: &[CustomEmoji], as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(emoji) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing emoji: {}", e),
-        }
+pub fn print_emoji(emoji: &[CustomEmoji], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(emoji, format);
         return;
     }
 
@@ -597,12 +1203,9 @@ pub fn print_emoji(emoji: &[CustomEmoji], as_json: bool) {
     }
 }
 
-pub fn print_pins(pins: &[PinnedMessage], as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(pins) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing pins: {}", e),
-        }
+pub fn print_pins(pins: &[PinnedMessage], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(pins, format);
         return;
     }
 
@@ -622,12 +1225,9 @@ pub fn print_pins(pins: &[PinnedMessage], as_json: bool) {
     }
 }
 
-pub fn print_bookmarks(bookmarks: &[Bookmark], as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(bookmarks) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing bookmarks: {}", e),
-        }
+pub fn print_bookmarks(bookmarks: &[Bookmark], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(bookmarks, format);
         return;
     }
 
@@ -642,15 +1242,95 @@ pub fn print_bookmarks(bookmarks: &[Bookmark], as_json: bool) {
     }
 }
 
-pub fn print_search_results(results: &SearchResults, as_json: bool) {
-    if as_json {
-        match serde_json::to_string_pretty(results) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("Error serializing search results: {}", e),
-        }
+/// Recurring reminders have no single time and show as `recurring`;
+/// reminders for someone else name that person.
+pub fn print_reminders(reminders: &[Reminder], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(reminders, format);
         return;
     }
 
+    if reminders.is_empty() {
+        println!("No reminders");
+        return;
+    }
+
+    for r in reminders {
+        let when = match r.time {
+            Some(secs) => format_timestamp(&secs.to_string()).unwrap_or_else(|| secs.to_string()),
+            None if r.recurring => "recurring".to_string(),
+            None => "-".to_string(),
+        };
+        let done = if r.is_complete() { " (done)" } else { "" };
+        let whom = match (&r.user, &r.creator) {
+            (Some(user), Some(creator)) if user != creator => format!(" → <@{user}>"),
+            _ => String::new(),
+        };
+        println!("{}  {}  {}{}{}", r.id, when, r.text, whom, done);
+    }
+}
+
+/// JSON is the grouped `{messages, files, channels, users, next_cursor}`
+/// envelope; row formats flatten every result into one row tagged with its
+/// `type` and send the cursor hint to stderr.
+pub fn print_search_results(
+    results: &SearchResults,
+    next_cursor: Option<&str>,
+    format: OutputFormat,
+) {
+    match format {
+        OutputFormat::Json => {
+            let mut envelope = serde_json::to_value(results).unwrap_or_else(|_| json!({}));
+            envelope["next_cursor"] = json!(next_cursor);
+            print_json(&envelope);
+            return;
+        }
+        OutputFormat::Text => render_search_results(results),
+        format => {
+            let rows = search_rows(results);
+            print_rows(&rows, &columns_of(&rows), format);
+        }
+    }
+    if let Some(cursor) = next_cursor {
+        eprintln!("More results available: rerun with --cursor {}", cursor);
+    }
+}
+
+/// One compact JSON object per result, tagged with its `type`, for
+/// `search --all` streaming.
+pub fn print_search_ndjson(results: &SearchResults) {
+    for row in search_rows(results) {
+        print_json_line(&row);
+    }
+}
+
+/// Every result of a search page as one object tagged with its `type`.
+pub fn search_rows(results: &SearchResults) -> Vec<Value> {
+    fn tagged<T: serde::Serialize>(kind: &str, items: &[T]) -> Vec<Value> {
+        items
+            .iter()
+            .filter_map(|item| {
+                let mut value = serde_json::to_value(item).ok()?;
+                value.as_object_mut()?.insert("type".into(), json!(kind));
+                Some(value)
+            })
+            .collect()
+    }
+
+    let mut rows = tagged("message", &results.messages);
+    rows.extend(tagged("file", &results.files));
+    rows.extend(tagged("channel", &results.channels));
+    rows.extend(tagged("user", &results.users));
+    rows
+}
+
+/// `search --all` in csv/tsv/table: the columns depend on which result
+/// types show up, so the rows are collected and printed once at the end.
+pub fn print_search_rows(rows: &[Value], format: OutputFormat) {
+    print_rows(rows, &columns_of(rows), format);
+}
+
+fn render_search_results(results: &SearchResults) {
     if results.messages.is_empty()
         && results.files.is_empty()
         && results.channels.is_empty()
@@ -709,6 +1389,562 @@ pub fn print_search_results(results: &SearchResults, as_json: bool) {
     }
 }
 
+pub fn print_saved_searches(searches: &[SavedSearch], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(searches, format);
+        return;
+    }
+
+    if searches.is_empty() {
+        println!("No saved searches. Save one with: slack-cli search <query> --save <name>");
+        return;
+    }
+
+    for s in searches {
+        let updated = format_timestamp(&s.updated_at.to_string()).unwrap_or_default();
+        println!(
+            "{}\t{}\t(limit {}, updated {})",
+            s.name, s.query, s.options.limit, updated
+        );
+    }
+}
+
+pub fn print_search_history(entries: &[SearchHistoryEntry], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(entries, format);
+        return;
+    }
+
+    if entries.is_empty() {
+        println!("No search history");
+        return;
+    }
+
+    for e in entries {
+        let when = format_timestamp(&e.executed_at.to_string()).unwrap_or_default();
+        let saved = e
+            .saved_name
+            .as_deref()
+            .map(|name| format!(" [{name}]"))
+            .unwrap_or_default();
+        println!(
+            "[{}] {} results\t{}{}",
+            when, e.result_count, e.query, saved
+        );
+    }
+}
+
+pub fn print_inbox(inbox: &Inbox, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => return print_json(inbox),
+        OutputFormat::Text => {}
+        _ => {
+            let columns = ["kind", "channel", "unread", "ts", "user", "text"].map(String::from);
+            return print_rows(&inbox_rows(inbox), &columns, format);
+        }
+    }
+
+    if inbox.unread.is_empty() {
+        println!("Nothing unread");
+    } else {
+        println!("Unread");
+        for channel in &inbox.unread {
+            println!("  {:<32} {}", channel.name, channel.unread);
+        }
+    }
+
+    println!();
+    if inbox.mentions.is_empty() {
+        println!("No recent mentions");
+        return;
+    }
+    println!("Mentions");
+    for msg in &inbox.mentions {
+        let author = msg
+            .author_name
+            .as_deref()
+            .or(msg.author_user_id.as_deref())
+            .unwrap_or("unknown");
+        let channel = msg.channel_name.as_deref().unwrap_or("-");
+        let date = format_timestamp(&msg.ts).unwrap_or_default();
+        println!("  {date}  #{channel} {author}: {}", msg.text);
+        if let Some(permalink) = &msg.permalink {
+            println!("    {permalink}");
+        }
+    }
+}
+
+/// Unread channels then mentions, as one `kind`-tagged table.
+fn inbox_rows(inbox: &Inbox) -> Vec<Value> {
+    let unread = inbox.unread.iter().map(
+        |c| json!({"kind": "unread", "channel": c.name, "unread": c.unread, "ts": c.last_read}),
+    );
+    let mentions = inbox.mentions.iter().map(|m| {
+        json!({
+            "kind": "mention",
+            "channel": m.channel_name.as_deref().or(m.channel_id.as_deref()),
+            "ts": m.ts,
+            "user": m.author_name.as_deref().or(m.author_user_id.as_deref()),
+            "text": m.text,
+        })
+    });
+    unread.chain(mentions).collect()
+}
+
+pub fn print_channel_activity(stats: &ChannelActivity, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => return print_json(stats),
+        OutputFormat::Text => {}
+        _ => {
+            let columns = ["metric", "key", "value"].map(String::from);
+            return print_rows(&activity_rows(stats), &columns, format);
+        }
+    }
+
+    let since = format_timestamp(&stats.since.to_string()).unwrap_or_default();
+    println!("{} since {}", stats.channel_id, since);
+    println!(
+        "Messages:    {} ({} human, {} bot; {:.0}% bot)",
+        stats.messages,
+        stats.human_messages,
+        stats.bot_messages,
+        stats.bot_share * 100.0
+    );
+    println!(
+        "Threads:     {} ({:.0}% of messages, {} replies)",
+        stats.threads,
+        stats.thread_ratio * 100.0,
+        stats.replies
+    );
+    println!(
+        "First reply: {}",
+        stats
+            .median_first_reply_secs
+            .map(|secs| format!("median {}", human_duration(secs)))
+            .unwrap_or_else(|| "no replies seen".to_string())
+    );
+    println!("Reactions:   {}", stats.reactions);
+
+    if !stats.top_posters.is_empty() {
+        println!();
+        println!("Top posters");
+        for poster in &stats.top_posters {
+            let who = match &poster.name {
+                Some(name) => format!("{name} ({})", poster.user),
+                None => poster.user.clone(),
+            };
+            println!("  {:<32} {}", who, poster.messages);
+        }
+    }
+    if !stats.top_reactions.is_empty() {
+        println!();
+        println!("Top reactions");
+        for reaction in &stats.top_reactions {
+            println!(
+                "  {:<32} {}",
+                format!(":{}:", reaction.name),
+                reaction.count
+            );
+        }
+    }
+
+    let busiest = stats.per_day.iter().map(|d| d.messages).max().unwrap_or(0);
+    if busiest > 0 {
+        println!();
+        println!("Per day");
+        for day in &stats.per_day {
+            println!(
+                "  {}  {:>5}{}",
+                day.date,
+                day.messages,
+                bar(day.messages, busiest)
+            );
+        }
+        let busiest = stats.per_hour.iter().map(|h| h.messages).max().unwrap_or(0);
+        println!();
+        println!("Per hour");
+        for hour in &stats.per_hour {
+            println!(
+                "  {:02}  {:>5}{}",
+                hour.hour,
+                hour.messages,
+                bar(hour.messages, busiest)
+            );
+        }
+    }
+}
+
+/// One row per revision, oldest first; `deleted_at` repeats on each.
+fn message_history_rows(history: &MessageHistory) -> Vec<Value> {
+    history
+        .revisions
+        .iter()
+        .map(|rev| {
+            json!({
+                "channel_id": history.channel_id,
+                "ts": history.ts,
+                "revision": rev.revision,
+                "seen_at": rev.seen_at,
+                "edited_ts": rev.edited_ts,
+                "text": rev.text,
+                "deleted_at": history.deleted_at,
+            })
+        })
+        .collect()
+}
+
+/// `stats` as `metric,key,value` rows for csv/tsv/table/ndjson.
+fn activity_rows(stats: &ChannelActivity) -> Vec<Value> {
+    let row = |metric: &str, key: &str, value: Value| json!({"metric": metric, "key": key, "value": value});
+    let mut rows = vec![
+        row("summary", "since", json!(stats.since)),
+        row("summary", "messages", json!(stats.messages)),
+        row("summary", "human_messages", json!(stats.human_messages)),
+        row("summary", "bot_messages", json!(stats.bot_messages)),
+        row("summary", "bot_share", json!(stats.bot_share)),
+        row("summary", "threads", json!(stats.threads)),
+        row("summary", "thread_ratio", json!(stats.thread_ratio)),
+        row("summary", "replies", json!(stats.replies)),
+        row(
+            "summary",
+            "median_first_reply_secs",
+            json!(stats.median_first_reply_secs),
+        ),
+        row("summary", "reactions", json!(stats.reactions)),
+    ];
+    rows.extend(
+        stats
+            .per_day
+            .iter()
+            .map(|d| row("day", &d.date, json!(d.messages))),
+    );
+    rows.extend(
+        stats
+            .per_hour
+            .iter()
+            .map(|h| row("hour", &format!("{:02}", h.hour), json!(h.messages))),
+    );
+    rows.extend(stats.top_posters.iter().map(|p| {
+        let key = p.name.as_deref().unwrap_or(&p.user);
+        row("poster", key, json!(p.messages))
+    }));
+    rows.extend(
+        stats
+            .top_reactions
+            .iter()
+            .map(|r| row("reaction", &r.name, json!(r.count))),
+    );
+    rows
+}
+
+/// A bar up to 30 cells wide, `count` relative to `max`, after a two-space
+/// gap (nothing at all for zero).
+fn bar(count: usize, max: usize) -> String {
+    match (count * 30).div_ceil(max.max(1)) {
+        0 => String::new(),
+        cells => format!("  {}", "█".repeat(cells)),
+    }
+}
+
+/// `252` → `4m 12s`; the two largest units only.
+pub fn human_duration(secs: i64) -> String {
+    let units = [(86_400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let mut rest = secs.max(0);
+    let mut parts = Vec::new();
+    for (size, unit) in units {
+        if rest >= size || (parts.is_empty() && size == 1) {
+            parts.push(format!("{}{unit}", rest / size));
+            rest %= size;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+    parts.join(" ")
+}
+
+pub fn print_message_history(history: &MessageHistory, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => return print_json(history),
+        OutputFormat::Text => {}
+        _ => {
+            let columns = [
+                "channel_id",
+                "ts",
+                "revision",
+                "seen_at",
+                "edited_ts",
+                "text",
+                "deleted_at",
+            ]
+            .map(String::from);
+            return print_rows(&message_history_rows(history), &columns, format);
+        }
+    }
+
+    let at = |secs: i64| format_timestamp(&secs.to_string()).unwrap_or_else(|| secs.to_string());
+    match history.thread_ts.as_deref().filter(|t| *t != history.ts) {
+        Some(thread_ts) => println!(
+            "{} {} (reply in {})",
+            history.channel_id, history.ts, thread_ts
+        ),
+        None => println!("{} {}", history.channel_id, history.ts),
+    }
+    println!(
+        "First seen {}, last seen {}",
+        at(history.first_seen),
+        at(history.last_seen)
+    );
+
+    let mut previous: Option<&MessageRevision> = None;
+    for rev in &history.revisions {
+        let edited = rev
+            .edited_ts
+            .as_deref()
+            .and_then(format_timestamp)
+            .map(|t| format!(", edited {t}"))
+            .unwrap_or_default();
+        println!();
+        println!(
+            "Revision {} (seen {}{})",
+            rev.revision,
+            at(rev.seen_at),
+            edited
+        );
+        match previous {
+            None => rev.text.lines().for_each(|line| println!("  {line}")),
+            Some(prev) => {
+                for (mark, line) in line_diff(&prev.text, &rev.text) {
+                    println!("{mark} {line}");
+                }
+                if prev.text == rev.text {
+                    println!("  (blocks changed)");
+                }
+            }
+        }
+        previous = Some(rev);
+    }
+
+    if let Some(deleted_at) = history.deleted_at {
+        println!();
+        println!("Deleted (noticed {})", at(deleted_at));
+    }
+}
+
+/// `old` → `new` as ` `/`-`/`+` lines along their longest common
+/// subsequence of lines.
+fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<(char, &'a str)> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(('-', a[i]));
+            i += 1;
+        } else {
+            out.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|line| ('-', *line)));
+    out.extend(b[j..].iter().map(|line| ('+', *line)));
+    out
+}
+
+pub fn print_integrity_report(report: &IntegrityReport, repaired: bool, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            let mut value = json!(report);
+            value["repaired"] = json!(repaired);
+            return print_json_line(&value);
+        }
+        OutputFormat::Text => {}
+        _ => {
+            let columns = ["check", "ok", "rows", "indexed", "error", "repaired"].map(String::from);
+            return print_rows(&integrity_rows(report, repaired), &columns, format);
+        }
+    }
+
+    let mark = |ok: bool| if ok { "✓" } else { "✗" };
+    println!(
+        "{} SQLite integrity: {}",
+        mark(report.sqlite == ["ok"]),
+        report.sqlite.join("; ")
+    );
+    for fts in &report.fts {
+        println!(
+            "{} {}: {} rows, {} indexed{}",
+            mark(fts.ok()),
+            fts.table,
+            fts.rows,
+            fts.indexed,
+            fts.error
+                .as_deref()
+                .map(|e| format!(" ({e})"))
+                .unwrap_or_default()
+        );
+    }
+    if repaired {
+        println!("Search indexes rebuilt");
+    }
+}
+
+/// One row for SQLite, then one per FTS table; `repaired` repeats on each.
+fn integrity_rows(report: &IntegrityReport, repaired: bool) -> Vec<Value> {
+    let sqlite = json!({
+        "check": "sqlite",
+        "ok": report.sqlite == ["ok"],
+        "rows": null,
+        "indexed": null,
+        "error": (report.sqlite != ["ok"]).then(|| report.sqlite.join("; ")),
+        "repaired": repaired,
+    });
+    std::iter::once(sqlite)
+        .chain(report.fts.iter().map(|fts| {
+            json!({
+                "check": fts.table,
+                "ok": fts.ok(),
+                "rows": fts.rows,
+                "indexed": fts.indexed,
+                "error": fts.error,
+                "repaired": repaired,
+            })
+        }))
+        .collect()
+}
+
+pub fn print_locks(locks: &[LockEntry], format: OutputFormat) {
+    if format != OutputFormat::Text {
+        print_serialized(locks, format);
+        return;
+    }
+
+    if locks.is_empty() {
+        println!("No locks held");
+        return;
+    }
+
+    for lock in locks {
+        let acquired = format_timestamp(&lock.acquired_at.to_string()).unwrap_or_default();
+        let state = if lock.expired { " (expired)" } else { "" };
+        println!(
+            "{:<20} {}  acquired {}{}",
+            lock.key, lock.instance_id, acquired, state
+        );
+    }
+}
+
+pub fn print_cache_stats(stats: &CacheStats, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => return print_json(stats),
+        OutputFormat::Text => {}
+        _ => {
+            let columns = ["metric", "key", "value"].map(String::from);
+            return print_rows(&cache_stats_rows(stats), &columns, format);
+        }
+    }
+
+    let u = &stats.users;
+    let c = &stats.channels;
+    println!(
+        "Users:       {} ({} humans, {} bots, {} deleted)",
+        u.total, u.humans, u.bots, u.deleted
+    );
+    println!(
+        "Channels:    {} ({} public, {} private, {} group DMs, {} DMs; {} archived)",
+        c.total, c.public, c.private, c.mpim, c.im, c.archived
+    );
+    println!("Memberships: {}", stats.memberships);
+    println!(
+        "Size:        {} ({} FTS index)",
+        human_bytes(stats.db_bytes),
+        human_bytes(stats.fts_bytes)
+    );
+    println!();
+
+    for f in &stats.freshness {
+        let synced = f
+            .last_sync
+            .and_then(|ts| format_timestamp(&ts.to_string()))
+            .unwrap_or_else(|| "never".to_string());
+        let state = match f.remaining_hours {
+            None => "stale (never synced)".to_string(),
+            Some(r) if f.stale => format!("stale for {:.1}h", -r),
+            Some(r) => format!("stale in {:.1}h", r),
+        };
+        println!(
+            "{:<16} synced {:<19}  {}  (ttl {}h, refresh after {}h)",
+            f.table, synced, state, f.ttl_hours, f.refresh_after_hours
+        );
+    }
+}
+
+/// `cache stats` as `metric,key,value` rows; freshness rows are keyed by
+/// table.
+fn cache_stats_rows(stats: &CacheStats) -> Vec<Value> {
+    let row = |metric: &str, key: &str, value: Value| json!({"metric": metric, "key": key, "value": value});
+    let (u, c) = (&stats.users, &stats.channels);
+    let mut rows = vec![
+        row("users", "total", json!(u.total)),
+        row("users", "humans", json!(u.humans)),
+        row("users", "bots", json!(u.bots)),
+        row("users", "deleted", json!(u.deleted)),
+        row("channels", "total", json!(c.total)),
+        row("channels", "public", json!(c.public)),
+        row("channels", "private", json!(c.private)),
+        row("channels", "mpim", json!(c.mpim)),
+        row("channels", "im", json!(c.im)),
+        row("channels", "archived", json!(c.archived)),
+        row("summary", "memberships", json!(stats.memberships)),
+        row("summary", "db_bytes", json!(stats.db_bytes)),
+        row("summary", "fts_bytes", json!(stats.fts_bytes)),
+        row("summary", "stale", json!(stats.stale)),
+    ];
+    for f in &stats.freshness {
+        rows.extend([
+            row("last_sync", f.table, json!(f.last_sync)),
+            row("age_hours", f.table, json!(f.age_hours)),
+            row("ttl_hours", f.table, json!(f.ttl_hours)),
+            row("refresh_after_hours", f.table, json!(f.refresh_after_hours)),
+            row("remaining_hours", f.table, json!(f.remaining_hours)),
+            row("stale", f.table, json!(f.stale)),
+        ]);
+    }
+    rows
+}
+
+/// `1536` → `1.5 KiB`.
+pub fn human_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -738,6 +1974,7 @@ mod tests {
                 event_type: "deploy_done".into(),
                 event_payload: json!({"version": "1.2.3"}),
             }),
+            replies: None,
         }
     }
 
@@ -748,6 +1985,156 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn template_fills_placeholders_and_unescapes_tabs() {
+        let template = Template::parse("{{date}}\\t{{ user }} » {{text}}", MESSAGE_FIELDS).unwrap();
+        let msg = sample_message_with_blocks_and_metadata();
+        assert_eq!(
+            templated_message_line(&msg, &template, None),
+            format!("{}\tU123 » hello", message_date(&msg, None).unwrap())
+        );
+
+        // Unset fields render empty; structured ones as compact JSON.
+        let template = Template::parse("[{{subtype}}] {{metadata}}", MESSAGE_FIELDS).unwrap();
+        assert_eq!(
+            templated_message_line(&msg, &template, None),
+            r#"[] {"event_payload":{"version":"1.2.3"},"event_type":"deploy_done"}"#
+        );
+    }
+
+    #[test]
+    fn template_rejects_unknown_fields_and_unclosed_placeholders() {
+        assert!(matches!(
+            Template::parse("{{name}} {{nickname}}", USER_FIELDS),
+            Err(TemplateError::UnknownField { field, .. }) if field == "nickname"
+        ));
+        assert_eq!(
+            Template::parse("{{name", USER_FIELDS),
+            Err(TemplateError::Unclosed("{{name".to_string()))
+        );
+        // Literal braces outside `{{…}}` are left alone.
+        let template = Template::parse("{id} {{id}}", CHANNEL_FIELDS).unwrap();
+        assert_eq!(template.render(|_| "C1".to_string()), "{id} C1");
+    }
+
+    #[test]
+    fn search_ndjson_tags_each_result_on_its_own_line() {
+        let results: SearchResults = serde_json::from_value(json!({
+            "messages": [
+                {"message_ts": "1.1", "content": "one"},
+                {"message_ts": "2.2", "content": "two"}
+            ],
+            "users": [{"user_id": "U1", "full_name": "Alice"}]
+        }))
+        .unwrap();
+
+        let mut out = Vec::new();
+        for row in search_rows(&results) {
+            write_json(&mut out, row, false).unwrap();
+        }
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let first: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["type"], json!("message"));
+        assert_eq!(first["message_ts"], json!("1.1"));
+        let last: Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(last["type"], json!("user"));
+    }
+
+    #[test]
+    fn message_history_rows_have_one_row_per_revision() {
+        let revision = |revision: i64, text: &str| MessageRevision {
+            revision,
+            text: text.into(),
+            blocks: None,
+            edited_ts: None,
+            seen_at: 100 * revision,
+        };
+        let history = MessageHistory {
+            channel_id: "C1".into(),
+            ts: "1.0".into(),
+            thread_ts: None,
+            first_seen: 100,
+            last_seen: 200,
+            deleted_at: Some(300),
+            revisions: vec![revision(1, "draft"), revision(2, "final")],
+        };
+
+        let rows = message_history_rows(&history);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["revision"], json!(2));
+        assert_eq!(rows[1]["text"], json!("final"));
+        assert_eq!(rows[0]["deleted_at"], json!(300));
+    }
+
+    #[test]
+    fn integrity_rows_list_sqlite_then_each_fts_table() {
+        use crate::cache::FtsCheck;
+
+        let report = IntegrityReport {
+            ok: false,
+            sqlite: vec!["ok".into()],
+            fts: vec![FtsCheck {
+                table: "channels_fuzzy",
+                rows: 2,
+                indexed: 1,
+                error: None,
+            }],
+        };
+
+        let rows = integrity_rows(&report, false);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["check"], json!("sqlite"));
+        assert_eq!(rows[0]["ok"], json!(true));
+        assert_eq!(rows[1]["check"], json!("channels_fuzzy"));
+        assert_eq!(rows[1]["ok"], json!(false));
+        assert_eq!(rows[1]["indexed"], json!(1));
+    }
+
+    #[test]
+    fn delimited_lines_quote_csv_and_escape_tsv() {
+        let cells = ["plain", "a,b", "say \"hi\"", "two\nlines", "tab\there"];
+        assert_eq!(
+            delimited_line(cells.into_iter(), OutputFormat::Csv),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",tab\there"
+        );
+        assert_eq!(
+            delimited_line(cells.into_iter(), OutputFormat::Tsv),
+            "plain\ta,b\tsay \"hi\"\ttwo\\nlines\ttab\\there"
+        );
+    }
+
+    #[test]
+    fn rows_follow_column_order_with_empty_cells_for_gaps() {
+        let columns: Vec<String> = ["name", "id", "members"].map(String::from).into();
+        let row = json!({"id": "C1", "name": "general", "topic": null, "tags": ["a"]});
+        assert_eq!(
+            delimited_row(&row, &columns, OutputFormat::Csv),
+            "general,C1,"
+        );
+        assert_eq!(cell(row.get("tags")), r#"["a"]"#);
+        assert_eq!(columns_of(&[row]), ["id", "name", "tags", "topic"]);
+    }
+
+    #[test]
+    fn table_pads_columns_to_the_widest_cell() {
+        let columns: Vec<String> = ["id", "name"].map(String::from).into();
+        let rows = [
+            json!({"id": "C1", "name": "general"}),
+            json!({"id": "C12345", "name": "multi\nline"}),
+        ];
+        assert_eq!(
+            table_lines(&rows, &columns),
+            [
+                "id      name",
+                "------  ----------",
+                "C1      general",
+                "C12345  multi line",
+            ]
+        );
+    }
+
     #[test]
     fn project_message_drops_fields_outside_allowed_set() {
         let msg = sample_message_with_blocks_and_metadata();
@@ -772,6 +2159,20 @@ mod tests {
         assert_eq!(projected["blocks"][0]["type"], json!("section"));
     }
 
+    #[test]
+    fn project_message_nests_projected_replies() {
+        let mut msg = sample_message_with_blocks_and_metadata();
+        let mut reply = sample_message_with_blocks_and_metadata();
+        reply.ts = "1700000000.000200".into();
+        msg.replies = Some(vec![reply]);
+        let fields = lean_fields();
+        let allowed: HashSet<&str> = fields.iter().map(String::as_str).collect();
+        let projected = project_message(&msg, &allowed, None);
+
+        assert_eq!(projected["replies"][0]["ts"], json!("1700000000.000200"));
+        assert!(projected["replies"][0].get("blocks").is_none());
+    }
+
     #[test]
     fn project_message_adds_computed_date_when_requested() {
         let msg = sample_message_with_blocks_and_metadata();
@@ -782,4 +2183,42 @@ mod tests {
 
         assert!(projected.get("date").is_some());
     }
+
+    #[test]
+    fn human_bytes_picks_binary_units() {
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+        assert_eq!(human_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn human_duration_keeps_the_two_largest_units() {
+        assert_eq!(human_duration(0), "0s");
+        assert_eq!(human_duration(252), "4m 12s");
+        assert_eq!(human_duration(3600), "1h");
+        assert_eq!(human_duration(90_061), "1d 1h");
+    }
+
+    #[test]
+    fn line_diff_keeps_common_lines_and_marks_changes() {
+        let diff = line_diff(
+            "deploy at 3\nin #ops\nthanks",
+            "deploy at 4\nin #ops\nthanks\n-- sre",
+        );
+        assert_eq!(
+            diff,
+            [
+                ('-', "deploy at 3"),
+                ('+', "deploy at 4"),
+                (' ', "in #ops"),
+                (' ', "thanks"),
+                ('+', "-- sre"),
+            ]
+        );
+        assert!(
+            line_diff("same", "same")
+                .iter()
+                .all(|(mark, _)| *mark == ' ')
+        );
+    }
 }
@@ -0,0 +1,104 @@
+//! `inbox`: what needs attention — unread counts per member channel and
+//! recent messages that mention the caller.
+
+use serde::Serialize;
+
+use crate::slack::{ReadState, SearchMessageResult};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnreadChannel {
+    pub channel_id: String,
+    /// `#name`, or `@person` for a DM.
+    pub name: String,
+    pub unread: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_read: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Inbox {
+    pub user_id: String,
+    /// Most unread first.
+    pub unread: Vec<UnreadChannel>,
+    /// Newest first.
+    pub mentions: Vec<SearchMessageResult>,
+}
+
+impl Inbox {
+    /// Keeps the channels with something unread and the mentions written
+    /// by someone other than `user_id`. `name_of` labels channel ids.
+    pub fn new(
+        user_id: &str,
+        states: Vec<ReadState>,
+        mentions: Vec<SearchMessageResult>,
+        name_of: impl Fn(&str) -> String,
+    ) -> Self {
+        let mut unread: Vec<UnreadChannel> = states
+            .into_iter()
+            .filter(|state| state.unread_count > 0)
+            .map(|state| UnreadChannel {
+                name: name_of(&state.channel_id),
+                channel_id: state.channel_id,
+                unread: state.unread_count,
+                last_read: state.last_read,
+            })
+            .collect();
+        unread.sort_by(|a, b| b.unread.cmp(&a.unread).then_with(|| a.name.cmp(&b.name)));
+
+        let mentions = mentions
+            .into_iter()
+            .filter(|m| m.author_user_id.as_deref() != Some(user_id))
+            .collect();
+
+        Self {
+            user_id: user_id.to_string(),
+            unread,
+            mentions,
+        }
+    }
+
+    /// The search query that finds messages mentioning `user_id`.
+    pub fn mention_query(user_id: &str) -> String {
+        format!("<@{user_id}>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn state(channel: &str, unread: i64) -> ReadState {
+        ReadState {
+            channel_id: channel.into(),
+            last_read: Some("1700000000.000100".into()),
+            unread_count: unread,
+        }
+    }
+
+    #[test]
+    fn lists_unread_channels_busiest_first_and_drops_own_mentions() {
+        let mentions: Vec<SearchMessageResult> = serde_json::from_value(json!([
+            {"message_ts": "3.0", "content": "<@U1> ping", "author_user_id": "U2"},
+            {"message_ts": "2.0", "content": "note to self <@U1>", "author_user_id": "U1"},
+        ]))
+        .unwrap();
+        let inbox = Inbox::new(
+            "U1",
+            vec![
+                state("C1", 2),
+                state("C2", 0),
+                state("C3", 7),
+                state("C4", 2),
+            ],
+            mentions,
+            |id| format!("#{}", id.to_lowercase()),
+        );
+
+        let names: Vec<_> = inbox.unread.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["#c3", "#c1", "#c4"]);
+        assert_eq!(inbox.mentions.len(), 1);
+        assert_eq!(inbox.mentions[0].text, "<@U1> ping");
+        assert_eq!(Inbox::mention_query("U1"), "<@U1>");
+    }
+}
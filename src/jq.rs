@@ -0,0 +1,169 @@
+//! `--jq`: a jq filter applied to JSON output before it is printed, for
+//! scripts on machines without `jq`. Backed by `jaq`, so the full jq
+//! language (paths, `select`, `map`, object construction, ...) works.
+
+use std::cell::OnceCell;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use jaq_core::load::{self, Arena, File, Loader};
+use jaq_core::{Ctx, Filter, Native, RcIter};
+use jaq_json::Val;
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JqError {
+    #[error("invalid --jq filter: {0}")]
+    Compile(String),
+
+    #[error("--jq filter failed: {0}")]
+    Run(String),
+}
+
+/// A compiled jq program.
+pub struct Jq(Filter<Native<Val>>);
+
+impl Jq {
+    pub fn compile(expr: &str) -> Result<Self, JqError> {
+        let defs = jaq_std::defs().chain(jaq_json::defs());
+        let arena = Arena::default();
+        let modules = Loader::new(defs)
+            .load(
+                &arena,
+                File {
+                    code: expr,
+                    path: (),
+                },
+            )
+            .map_err(|errs| JqError::Compile(describe_load_errors(errs)))?;
+        let filter = jaq_core::Compiler::default()
+            .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+            .compile(modules)
+            .map_err(|errs| {
+                let undefined: Vec<String> = errs
+                    .into_iter()
+                    .flat_map(|(_, errs)| errs)
+                    .map(|(name, kind)| format!("undefined {} `{}`", kind.as_str(), name))
+                    .collect();
+                JqError::Compile(undefined.join(", "))
+            })?;
+        Ok(Self(filter))
+    }
+
+    /// Every output of the filter for `input`, like `jq` printing one
+    /// value per result.
+    pub fn run(&self, input: Value) -> Result<Vec<Value>, JqError> {
+        let inputs = RcIter::new(core::iter::empty());
+        self.0
+            .run((Ctx::new([], &inputs), Val::from(input)))
+            .map(|out| {
+                out.map(Value::from)
+                    .map_err(|e| JqError::Run(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+fn describe_load_errors(errs: load::Errors<&str, ()>) -> String {
+    let expected = |what: &str, rest: &str| {
+        let near: String = rest.chars().take(20).collect();
+        format!("expected {what} near `{near}`")
+    };
+    let messages: Vec<String> = errs
+        .into_iter()
+        .flat_map(|(_, err)| match err {
+            load::Error::Io(errs) => errs.into_iter().map(|(_, e)| e).collect(),
+            load::Error::Lex(errs) => errs
+                .into_iter()
+                .map(|(expect, rest)| expected(expect.as_str(), rest))
+                .collect(),
+            load::Error::Parse(errs) => errs
+                .into_iter()
+                .map(|(expect, rest)| expected(expect.as_str(), rest))
+                .collect::<Vec<_>>(),
+        })
+        .collect();
+    messages.join(", ")
+}
+
+/// The process-wide filter from `--jq`. Only the source is shared: the
+/// compiled form is not `Sync`, so each printing thread compiles its own.
+static EXPR: OnceLock<String> = OnceLock::new();
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static COMPILED: OnceCell<Jq> = const { OnceCell::new() };
+}
+
+/// Validates `expr` and makes every later JSON print go through it.
+pub fn install(expr: &str) -> Result<(), JqError> {
+    Jq::compile(expr)?;
+    let _ = EXPR.set(expr.to_string());
+    Ok(())
+}
+
+pub fn is_active() -> bool {
+    EXPR.get().is_some()
+}
+
+/// Runs the installed filter, or passes `value` through when there is none.
+pub fn apply(value: Value) -> Result<Vec<Value>, JqError> {
+    let Some(expr) = EXPR.get() else {
+        return Ok(vec![value]);
+    };
+    COMPILED.with(|cell| match cell.get() {
+        Some(jq) => jq.run(value),
+        None => {
+            let jq = Jq::compile(expr)?;
+            let out = jq.run(value);
+            let _ = cell.set(jq);
+            out
+        }
+    })
+}
+
+/// Records that a filter failed at runtime. Printing carries on with the
+/// remaining values (as `jq` does) and the process exits non-zero.
+pub fn mark_failed() {
+    FAILED.store(true, Ordering::Relaxed);
+}
+
+pub fn failed() -> bool {
+    FAILED.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn projects_fields_from_arrays() {
+        let jq = Jq::compile(".[].email").unwrap();
+        let users = json!([{"email": "a@x.io"}, {"email": "b@x.io"}]);
+        assert_eq!(jq.run(users).unwrap(), [json!("a@x.io"), json!("b@x.io")]);
+    }
+
+    #[test]
+    fn supports_select_and_std_functions() {
+        let jq = Jq::compile("[.messages[] | select(.user == \"U1\") | .ts] | length").unwrap();
+        let history = json!({"messages": [
+            {"user": "U1", "ts": "1"}, {"user": "U2", "ts": "2"}, {"user": "U1", "ts": "3"}
+        ]});
+        assert_eq!(jq.run(history).unwrap(), [json!(2)]);
+    }
+
+    #[test]
+    fn reports_syntax_and_undefined_names() {
+        assert!(matches!(Jq::compile(".[] |"), Err(JqError::Compile(_))));
+        let err = Jq::compile("nosuchfn").err().unwrap().to_string();
+        assert!(err.contains("undefined filter `nosuchfn`"), "{err}");
+    }
+
+    #[test]
+    fn runtime_errors_surface_as_run_errors() {
+        let jq = Jq::compile(".foo").unwrap();
+        assert!(matches!(jq.run(json!([1])), Err(JqError::Run(_))));
+    }
+}
@@ -1,8 +1,12 @@
+pub mod activity;
 pub mod auth;
 pub mod cache;
 pub mod cli;
 pub mod config;
 pub mod format;
+pub mod inbox;
+pub mod jq;
 pub mod slack;
+pub mod time;
 
 pub use config::Config;
@@ -1,21 +1,35 @@
 use anyhow::{Context, Result};
-use chrono::{Local, NaiveDate, TimeZone};
-use clap::Parser;
+use chrono::Utc;
+use clap::{Parser, ValueEnum};
 use serde_json::Value;
 use slack_cli::{
-    auth::{self, AuthError, AuthLoadOptions, Authenticator, EnvOverrides},
-    cache::{self, CacheStatus},
-    cli::{CacheAction, Cli, Command, ConfigAction, MessageContent, RefreshTarget},
-    config, format, slack,
-    slack::{MessageMetadata, MessagePayload, SlackApiError},
+    activity,
+    auth::{self, AuthError, AuthLoadOptions, Authenticator, EnvOverrides, TokenOverrides},
+    cache::{self, CacheStatus, constants::BACKGROUND_REFRESH_LOCK},
+    cli::{
+        CacheAction, Cli, Command, ConfigAction, InboxAction, MessageContent, RefreshTarget,
+        RemindAction, SearchAction,
+    },
+    config::{self, StaleRefresh},
+    format,
+    inbox::Inbox,
+    jq::{self, JqError},
+    slack,
+    slack::{
+        MessageMetadata, MessagePayload, SlackApiError,
+        search_query::{self, ModifierKind, QueryError},
+    },
+    time::{self, TimeError},
 };
+use std::ffi::OsString;
 use std::io::Read;
-use std::process::ExitCode;
+use std::process::{ExitCode, Stdio};
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    cli.normalize_output();
 
     let level = if cli.verbose { "debug" } else { "warn" };
     tracing_subscriber::fmt()
@@ -29,6 +43,9 @@ async fn main() -> ExitCode {
 
     let as_json = cli.json;
     match run(cli).await {
+        // A `--jq` filter that failed on some output; the error is already
+        // on stderr next to whatever did print.
+        Ok(()) if jq::failed() => ExitCode::from(5),
         Ok(()) => ExitCode::SUCCESS,
         Err(err) => {
             let (code, exit) = classify_error(&err);
@@ -46,7 +63,37 @@ async fn main() -> ExitCode {
 }
 
 async fn run(cli: Cli) -> Result<()> {
-    let config = config::Config::load(cli.config.clone(), cli.data_dir.clone())?;
+    let output = cli.output_format();
+    if let Some(filter) = &cli.jq {
+        if !matches!(
+            output,
+            format::OutputFormat::Json | format::OutputFormat::Ndjson
+        ) {
+            anyhow::bail!("--jq filters JSON output; use it with --json or --output ndjson");
+        }
+        jq::install(filter)?;
+    }
+    let config = config::Config::load(cli.config.clone(), cli.data_dir.clone(), cli.team.clone())?;
+    let zone = cli
+        .tz
+        .as_deref()
+        .or(config.output.timezone.as_deref())
+        .map(str::parse::<time::Zone>)
+        .transpose()?
+        .unwrap_or_default();
+    time::set_display(time::Display {
+        zone,
+        author_zones: matches!(
+            cli.command,
+            Command::Messages {
+                author_tz: true,
+                ..
+            } | Command::Thread {
+                author_tz: true,
+                ..
+            }
+        ),
+    });
 
     if let Command::Config { action } = &cli.command {
         return handle_config_action(action, cli.json, cli.config.clone(), &config);
@@ -58,6 +105,10 @@ async fn run(cli: Cli) -> Result<()> {
         store_path,
         overrides: EnvOverrides::capture(),
         explicit_profile: cli.profile.clone(),
+        token_overrides: TokenOverrides {
+            forced: cli.token_as.map(Into::into),
+            per_method: config.auth.method_tokens.clone().into_iter().collect(),
+        },
     })?);
 
     if let Command::Auth { action } = cli.command {
@@ -71,7 +122,10 @@ async fn run(cli: Cli) -> Result<()> {
         .await;
     }
 
-    let slack = Arc::new(slack::SlackClient::new(config.clone(), authenticator)?);
+    let slack = Arc::new(slack::SlackClient::new(
+        config.clone(),
+        authenticator.clone(),
+    )?);
 
     let db_path = config.db_path();
     if let Some(parent) = db_path.parent() {
@@ -80,7 +134,7 @@ async fn run(cli: Cli) -> Result<()> {
     let db_path_str = db_path
         .to_str()
         .context("Database path contains invalid UTF-8 characters")?;
-    let cache = Arc::new(cache::SqliteCache::new(db_path_str).await?);
+    let cache = Arc::new(cache::SqliteCache::open(db_path_str, config.cache.tokenizer).await?);
 
     let threshold = config.cache.refresh_threshold_percent;
     let cache_status = cache.get_cache_status(
@@ -88,6 +142,8 @@ async fn run(cli: Cli) -> Result<()> {
         config.cache.ttl_channels_hours,
         threshold,
     )?;
+    let refresh_args = background_refresh_args(&cli);
+    let is_cache_command = matches!(cli.command, Command::Cache { .. });
 
     match cli.command {
         Command::Users {
@@ -97,13 +153,31 @@ async fn run(cli: Cli) -> Result<()> {
             expand,
         } => {
             ensure_users_cache(&slack, &cache, cli.json).await?;
-            let users = if let Some(ids) = id {
-                cache.get_users_by_ids(&ids)?
-            } else {
-                cache.search_users(query.as_deref().unwrap_or(""), limit, false)?
-            };
             let fields = merge_fields(&config.output.users_fields, expand.as_deref());
-            format::print_users(&users, &fields, cli.json);
+            let template = text_template(
+                cli.format_template.as_deref(),
+                config.output.users_template.as_deref(),
+                output,
+                format::USER_FIELDS,
+            )?;
+            let template = template.as_ref();
+            match (id, query.as_deref().filter(|q| !q.trim().is_empty())) {
+                (Some(ids), _) => {
+                    format::print_users(&cache.get_users_by_ids(&ids)?, &fields, output, template)
+                }
+                (None, Some(query)) => format::print_ranked_users(
+                    &cache.rank_users(query, limit, false)?,
+                    &fields,
+                    output,
+                    template,
+                ),
+                (None, None) => format::print_users(
+                    &cache.search_users("", limit, false)?,
+                    &fields,
+                    output,
+                    template,
+                ),
+            }
         }
 
         Command::Channels {
@@ -113,13 +187,34 @@ async fn run(cli: Cli) -> Result<()> {
             expand,
         } => {
             ensure_channels_cache(&slack, &cache, cli.json).await?;
-            let channels = if let Some(ids) = id {
-                cache.get_channels_by_ids(&ids)?
-            } else {
-                cache.search_channels(query.as_deref().unwrap_or(""), limit)?
-            };
             let fields = merge_fields(&config.output.channels_fields, expand.as_deref());
-            format::print_channels(&channels, &fields, cli.json);
+            let template = text_template(
+                cli.format_template.as_deref(),
+                config.output.channels_template.as_deref(),
+                output,
+                format::CHANNEL_FIELDS,
+            )?;
+            let template = template.as_ref();
+            match (id, query.as_deref().filter(|q| !q.trim().is_empty())) {
+                (Some(ids), _) => format::print_channels(
+                    &cache.get_channels_by_ids(&ids)?,
+                    &fields,
+                    output,
+                    template,
+                ),
+                (None, Some(query)) => format::print_ranked_channels(
+                    &cache.rank_channels(query, limit)?,
+                    &fields,
+                    output,
+                    template,
+                ),
+                (None, None) => format::print_channels(
+                    &cache.search_channels("", limit)?,
+                    &fields,
+                    output,
+                    template,
+                ),
+            }
         }
 
         Command::Send {
@@ -132,7 +227,7 @@ async fn run(cli: Cli) -> Result<()> {
             let result = slack.messages.send(&id, payload, thread.as_deref()).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                format::print_json(&result);
             } else {
                 println!("✓ Sent: {}", result.ts);
             }
@@ -148,7 +243,7 @@ async fn run(cli: Cli) -> Result<()> {
             let result = slack.messages.update(&id, &ts, payload).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                format::print_json(&result);
             } else {
                 println!("✓ Updated: {}", result.ts);
             }
@@ -159,7 +254,7 @@ async fn run(cli: Cli) -> Result<()> {
             let result = slack.messages.delete(&id, &ts).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&result)?);
+                format::print_json(&result);
             } else {
                 println!("✓ Deleted: {}", result.ts);
             }
@@ -170,7 +265,7 @@ async fn run(cli: Cli) -> Result<()> {
             let link = slack.messages.permalink(&id, &ts).await?;
 
             if cli.json {
-                println!("{}", serde_json::json!({ "permalink": link }));
+                format::print_json_line(&serde_json::json!({ "permalink": link }));
             } else {
                 println!("{}", link);
             }
@@ -183,36 +278,94 @@ async fn run(cli: Cli) -> Result<()> {
             oldest,
             latest,
             exclude_bots,
+            all,
+            max,
+            with_threads,
+            author_tz,
             expand,
         } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
 
             let oldest_ts = oldest.map(|o| parse_timestamp(&o)).transpose()?;
             let latest_ts = latest.map(|l| parse_timestamp(&l)).transpose()?;
+            let fields =
+                message_fields(&config.output.messages_fields, expand.as_deref(), author_tz);
+            let template = text_template(
+                cli.format_template.as_deref(),
+                config.output.messages_template.as_deref(),
+                output,
+                format::MESSAGE_FIELDS,
+            )?;
+
+            let archive_cache = config.cache.archive_messages.then_some(&*cache);
+            if all || max.is_some() {
+                let mut stream = format::HistoryStream::new(
+                    output,
+                    &fields,
+                    with_threads,
+                    Some(&cache),
+                    template.as_ref(),
+                );
+                let next_cursor = slack
+                    .messages
+                    .history_pages(
+                        &id,
+                        cursor.as_deref(),
+                        oldest_ts.as_deref(),
+                        latest_ts.as_deref(),
+                        max,
+                        async |mut page| {
+                            if config.cache.archive_messages {
+                                archive_messages(&cache, &id, None, &page);
+                            }
+                            filter_history(
+                                &slack,
+                                archive_cache,
+                                &id,
+                                &mut page,
+                                exclude_bots,
+                                with_threads,
+                            )
+                            .await?;
+                            Ok(stream.page(&page)?)
+                        },
+                    )
+                    .await?;
+                stream.finish(next_cursor.as_deref())?;
+            } else {
+                let (mut messages, next_cursor) = slack
+                    .messages
+                    .history(
+                        &id,
+                        limit,
+                        cursor.as_deref(),
+                        oldest_ts.as_deref(),
+                        latest_ts.as_deref(),
+                    )
+                    .await?;
+                if config.cache.archive_messages {
+                    archive_messages(&cache, &id, None, &messages);
+                }
 
-            let (mut messages, next_cursor) = slack
-                .messages
-                .history(
+                filter_history(
+                    &slack,
+                    archive_cache,
                     &id,
-                    limit,
-                    cursor.as_deref(),
-                    oldest_ts.as_deref(),
-                    latest_ts.as_deref(),
+                    &mut messages,
+                    exclude_bots,
+                    with_threads,
                 )
                 .await?;
 
-            if exclude_bots {
-                messages.retain(|m| m.bot_id.is_none());
+                format::print_history(
+                    &messages,
+                    next_cursor.as_deref(),
+                    output,
+                    &fields,
+                    Some(&cache),
+                    template.as_ref(),
+                );
             }
-
-            let fields = merge_fields(&config.output.messages_fields, expand.as_deref());
-            format::print_history(
-                &messages,
-                next_cursor.as_deref(),
-                cli.json,
-                &fields,
-                Some(&cache),
-            );
         }
 
         Command::Thread {
@@ -220,24 +373,312 @@ async fn run(cli: Cli) -> Result<()> {
             ts,
             limit,
             exclude_bots,
+            author_tz,
             expand,
         } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
             let mut messages = slack.messages.replies(&id, &ts, limit).await?;
+            if config.cache.archive_messages {
+                archive_messages(&cache, &id, Some(&ts), &messages);
+            }
             if exclude_bots {
                 messages.retain(|m| m.bot_id.is_none());
             }
-            let fields = merge_fields(&config.output.messages_fields, expand.as_deref());
-            format::print_messages(&messages, cli.json, &fields, Some(&cache));
+            let fields =
+                message_fields(&config.output.messages_fields, expand.as_deref(), author_tz);
+            let template = text_template(
+                cli.format_template.as_deref(),
+                config.output.messages_template.as_deref(),
+                output,
+                format::MESSAGE_FIELDS,
+            )?;
+            format::print_messages(&messages, output, &fields, Some(&cache), template.as_ref());
         }
 
-        Command::Members { channel } => {
+        Command::Inbox {
+            action: Some(InboxAction::MarkRead { channel, ts }),
+            ..
+        } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
-            let members = slack.channels.members(&id).await?;
-            format::print_members(&members, &cache, cli.json);
+            let ts = match ts {
+                Some(ts) => Some(ts),
+                None => {
+                    let (latest, _) = slack.messages.history(&id, 1, None, None, None).await?;
+                    latest.into_iter().next().map(|m| m.ts)
+                }
+            };
+            if let Some(ts) = &ts {
+                slack.channels.mark(&id, ts).await?;
+            }
+            if cli.json {
+                format::print_json_line(&serde_json::json!({"channel": id, "ts": ts}));
+            } else if let Some(ts) = ts {
+                println!("✓ Marked {channel} read up to {ts}");
+            } else {
+                println!("No messages in {channel}");
+            }
+        }
+
+        Command::Inbox {
+            action: None,
+            since,
+            mentions,
+        } => {
+            let after = parse_unix_seconds(&since)?;
+            let me = slack.auth.whoami().await?;
+            ensure_channels_cache(&slack, &cache, cli.json).await?;
+            let channels: Vec<slack::SlackChannel> = cache
+                .get_channels()?
+                .into_iter()
+                .filter(|c| (c.is_member || c.is_im) && !c.is_archived)
+                .collect();
+
+            if !cli.json {
+                eprint!("Checking {} conversations... ", channels.len());
+            }
+            let ids: Vec<String> = channels.iter().map(|c| c.id.clone()).collect();
+            let states = slack.channels.read_states(&ids).await?;
+            if !cli.json {
+                eprintln!("✓");
+            }
+
+            let mentions = if mentions == 0 {
+                Vec::new()
+            } else {
+                let options = slack::SearchOptions {
+                    limit: mentions,
+                    channel_types: vec![
+                        slack::SearchChannelType::PublicChannel,
+                        slack::SearchChannelType::PrivateChannel,
+                        slack::SearchChannelType::Mpim,
+                        slack::SearchChannelType::Im,
+                    ],
+                    content_types: vec![slack::SearchContentType::Messages],
+                    context_channel_id: None,
+                    include_archived_channels: false,
+                    before: None,
+                    after: Some(after),
+                    include_bots: true,
+                    disable_semantic_search: true,
+                    sort: slack::SearchSort::Timestamp,
+                    sort_dir: slack::SearchSortDirection::Desc,
+                    include_context_messages: false,
+                    from_user: None,
+                    has: Vec::new(),
+                    is: Vec::new(),
+                    include_message_blocks: false,
+                    highlight: false,
+                };
+                let query = Inbox::mention_query(&me.user_id);
+                slack
+                    .search
+                    .context(&query, &options, None)
+                    .await?
+                    .0
+                    .messages
+            };
+
+            let inbox = Inbox::new(&me.user_id, states, mentions, |id| {
+                conversation_label(id, &channels, &cache)
+            });
+            format::print_inbox(&inbox, output);
+        }
+
+        Command::Stats {
+            channel,
+            since,
+            archive,
+            no_reply_times,
+        } => {
+            let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
+            let since = parse_unix_seconds(&since)?;
+            let oldest = since.to_string();
+            let messages = if archive {
+                cache.archived_messages(&id, &oldest)?
+            } else {
+                ensure_users_cache(&slack, &cache, cli.json).await?;
+                let mut messages = Vec::new();
+                slack
+                    .messages
+                    .history_pages(&id, None, Some(&oldest), None, None, async |page| {
+                        if config.cache.archive_messages {
+                            archive_messages(&cache, &id, None, &page);
+                        }
+                        messages.extend(page);
+                        Ok(())
+                    })
+                    .await?;
+                if !no_reply_times {
+                    slack
+                        .messages
+                        .attach_first_replies(&id, &mut messages)
+                        .await?;
+                }
+                if config.cache.archive_messages {
+                    for msg in &messages {
+                        if let Some(replies) = &msg.replies {
+                            archive_messages(&cache, &id, Some(&msg.ts), replies);
+                        }
+                    }
+                }
+                messages
+            };
+            let stats = activity::ChannelActivity::compute(
+                &id,
+                since,
+                &messages,
+                time::display().zone,
+                |user| {
+                    cache
+                        .get_user_by_id(user)
+                        .ok()
+                        .flatten()
+                        .and_then(|u| u.profile.and_then(|p| p.real_name))
+                },
+            );
+            format::print_channel_activity(&stats, output);
+        }
+
+        Command::HistoryOf { channel, ts } => {
+            let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
+            match cache.message_history(&id, &ts)? {
+                Some(history) => format::print_message_history(&history, output),
+                None if config.cache.archive_messages => anyhow::bail!(
+                    "Message {ts} in {channel} is not archived yet; read it once with `slack-cli messages` or `slack-cli thread`"
+                ),
+                None => anyhow::bail!(
+                    "No archived history for {ts}: set `cache.archive_messages = true` so `messages` and `thread` keep revisions"
+                ),
+            }
+        }
+
+        Command::Members { channel, query } => {
+            let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
+            // A fresh full sync answers without calling Slack; channels it
+            // skipped (not a member, archived) still go live.
+            let synced = if cache.is_member_sync_fresh(
+                config.cache.ttl_channels_hours,
+                config.cache.refresh_threshold_percent,
+            )? {
+                cache.get_member_ids(&id)?
+            } else {
+                None
+            };
+            let members = match synced {
+                Some(members) => members,
+                None => match slack.channels.members(&id).await {
+                    Ok(members) => {
+                        if let Err(err) = cache.save_members_of(&id, &members).await {
+                            tracing::warn!("Could not cache members of {id}: {err:#}");
+                        }
+                        members
+                    }
+                    Err(err) => match cache.get_member_ids(&id)? {
+                        Some(members) => {
+                            tracing::warn!("Showing cached members of {id}: {err:#}");
+                            members
+                        }
+                        None => return Err(err),
+                    },
+                },
+            };
+            match query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+                Some(query) => {
+                    ensure_users_cache(&slack, &cache, cli.json).await?;
+                    let template = text_template(
+                        cli.format_template.as_deref(),
+                        config.output.users_template.as_deref(),
+                        output,
+                        format::USER_FIELDS,
+                    )?;
+                    format::print_users(
+                        &cache.search_members(&id, query)?,
+                        &config.output.users_fields,
+                        output,
+                        template.as_ref(),
+                    );
+                }
+                None => format::print_members(&members, &cache, output),
+            }
+        }
+
+        Command::UserChannels {
+            user,
+            include_archived,
+            expand,
+        } => {
+            let user_id = resolve_user(&user, &slack, &cache, cli.json).await?;
+            ensure_channels_cache(&slack, &cache, cli.json).await?;
+            if !cache.has_member_sync()? {
+                if !cli.json {
+                    eprint!("Fetching channel members... ");
+                }
+                let written = sync_channel_members(&slack, &cache, &cache.get_channels()?).await?;
+                if !cli.json {
+                    eprintln!("✓ {written} memberships");
+                }
+            }
+            let fields = merge_fields(&config.output.channels_fields, expand.as_deref());
+            let template = text_template(
+                cli.format_template.as_deref(),
+                config.output.channels_template.as_deref(),
+                output,
+                format::CHANNEL_FIELDS,
+            )?;
+            format::print_channels(
+                &cache.get_user_channels(&user_id, include_archived)?,
+                &fields,
+                output,
+                template.as_ref(),
+            );
         }
 
         Command::Search {
+            action: Some(action),
+            ..
+        } => match action {
+            SearchAction::Run { name, limit } => {
+                let saved = cache.get_saved_search(&name)?.with_context(|| {
+                    format!("no saved search named '{name}'. see: slack-cli search saved")
+                })?;
+                let mut options = saved.options;
+                if let Some(limit) = limit {
+                    options.limit = limit;
+                }
+                options.include_message_blocks = cli.json;
+                options.highlight = !cli.json;
+
+                let (results, next_cursor) =
+                    slack.search.context(&saved.query, &options, None).await?;
+                format::print_search_results(&results, next_cursor.as_deref(), output);
+                record_search(
+                    &cache,
+                    &saved.query,
+                    &options,
+                    Some(&saved.name),
+                    results.total_len(),
+                );
+            }
+            SearchAction::Saved => {
+                format::print_saved_searches(&cache.list_saved_searches()?, output);
+            }
+            SearchAction::Delete { name } => {
+                let found = cache.delete_saved_search(&name)?;
+                if cli.json {
+                    format::print_json_line(&serde_json::json!({"name": name, "deleted": found}));
+                } else if found {
+                    println!("✓ Deleted saved search {name}");
+                } else {
+                    println!("No saved search named {name}");
+                }
+            }
+            SearchAction::History { limit } => {
+                format::print_search_history(&cache.recent_searches(limit)?, output);
+            }
+        },
+
+        Command::Search {
+            action: None,
             query,
             limit,
             channel_types,
@@ -251,7 +692,11 @@ async fn run(cli: Cli) -> Result<()> {
             disable_semantic_search,
             sort,
             sort_dir,
+            cursor,
+            all,
+            save,
         } => {
+            let query = query.context("search query is required")?;
             let context_channel_id = match channel {
                 Some(input) => Some(resolve_channel(&input, &slack, &cache, cli.json).await?),
                 None => None,
@@ -260,7 +705,7 @@ async fn run(cli: Cli) -> Result<()> {
             let before = before.as_deref().map(parse_unix_seconds).transpose()?;
             let after = after.as_deref().map(parse_unix_seconds).transpose()?;
 
-            let options = slack::SearchOptions {
+            let mut options = slack::SearchOptions {
                 limit,
                 channel_types,
                 content_types,
@@ -273,12 +718,69 @@ async fn run(cli: Cli) -> Result<()> {
                 sort,
                 sort_dir,
                 include_context_messages,
+                from_user: None,
+                has: Vec::new(),
+                is: Vec::new(),
                 include_message_blocks: cli.json,
                 highlight: !cli.json,
             };
-            let results = slack.search.context(&query, &options).await?;
-
-            format::print_search_results(&results, cli.json);
+            let query =
+                apply_search_modifiers(&query, &mut options, &slack, &cache, cli.json).await?;
+            if let Some(name) = &save {
+                cache.save_search(name, &query, &options)?;
+                if !cli.json && !all {
+                    eprintln!(
+                        "✓ Saved as '{}'. Replay with: slack-cli search run {}",
+                        name.trim(),
+                        name.trim()
+                    );
+                }
+            }
+            let saved_name = save.as_deref().map(str::trim);
+
+            if all {
+                let mut pager = slack.search.pages(&query, &options, cursor.as_deref());
+                let mut total = 0;
+                let tabular = matches!(
+                    output,
+                    format::OutputFormat::Csv
+                        | format::OutputFormat::Tsv
+                        | format::OutputFormat::Table
+                );
+                let mut rows = Vec::new();
+                loop {
+                    match pager.next_page().await {
+                        Ok(Some(page)) if tabular => {
+                            total += page.total_len();
+                            rows.extend(format::search_rows(&page));
+                        }
+                        Ok(Some(page)) => {
+                            total += page.total_len();
+                            format::print_search_ndjson(&page);
+                        }
+                        Ok(None) => break,
+                        Err(err) => {
+                            if let Some(cursor) = pager.cursor() {
+                                eprintln!(
+                                    "Stream interrupted: resume with --all --cursor {cursor}"
+                                );
+                            }
+                            return Err(err);
+                        }
+                    }
+                }
+                if tabular {
+                    format::print_search_rows(&rows, output);
+                }
+                record_search(&cache, &query, &options, saved_name, total);
+            } else {
+                let (results, next_cursor) = slack
+                    .search
+                    .context(&query, &options, cursor.as_deref())
+                    .await?;
+                format::print_search_results(&results, next_cursor.as_deref(), output);
+                record_search(&cache, &query, &options, saved_name, results.total_len());
+            }
         }
 
         Command::React { channel, ts, emoji } => {
@@ -286,7 +788,7 @@ async fn run(cli: Cli) -> Result<()> {
             slack.reactions.add(&id, &ts, &emoji).await?;
 
             if cli.json {
-                println!("{{\"ok\": true}}");
+                format::print_json_line(&serde_json::json!({ "ok": true }));
             } else {
                 println!("✓ Added :{}: reaction", emoji.trim_matches(':'));
             }
@@ -297,7 +799,7 @@ async fn run(cli: Cli) -> Result<()> {
             slack.reactions.remove(&id, &ts, &emoji).await?;
 
             if cli.json {
-                println!("{{\"ok\": true}}");
+                format::print_json_line(&serde_json::json!({ "ok": true }));
             } else {
                 println!("✓ Removed :{}: reaction", emoji.trim_matches(':'));
             }
@@ -306,7 +808,7 @@ async fn run(cli: Cli) -> Result<()> {
         Command::Reactions { channel, ts } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
             let reactions = slack.reactions.get(&id, &ts).await?;
-            format::print_reactions(&reactions, cli.json);
+            format::print_reactions(&reactions, output);
         }
 
         Command::Emoji { query } => {
@@ -315,7 +817,7 @@ async fn run(cli: Cli) -> Result<()> {
             } else {
                 slack.emoji.list().await?
             };
-            format::print_emoji(&emoji, cli.json);
+            format::print_emoji(&emoji, output);
         }
 
         Command::Pin { channel, ts } => {
@@ -323,7 +825,7 @@ async fn run(cli: Cli) -> Result<()> {
             slack.pins.add(&id, &ts).await?;
 
             if cli.json {
-                println!("{{\"ok\": true}}");
+                format::print_json_line(&serde_json::json!({ "ok": true }));
             } else {
                 println!("✓ Pinned message");
             }
@@ -334,7 +836,7 @@ async fn run(cli: Cli) -> Result<()> {
             slack.pins.remove(&id, &ts).await?;
 
             if cli.json {
-                println!("{{\"ok\": true}}");
+                format::print_json_line(&serde_json::json!({ "ok": true }));
             } else {
                 println!("✓ Unpinned message");
             }
@@ -343,7 +845,7 @@ async fn run(cli: Cli) -> Result<()> {
         Command::Pins { channel } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
             let pins = slack.pins.list(&id).await?;
-            format::print_pins(&pins, cli.json);
+            format::print_pins(&pins, output);
         }
 
         Command::Bookmark {
@@ -359,7 +861,7 @@ async fn run(cli: Cli) -> Result<()> {
                 .await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&bookmark)?);
+                format::print_json(&bookmark);
             } else {
                 println!("✓ Added bookmark: {} (id: {})", bookmark.title, bookmark.id);
             }
@@ -373,7 +875,7 @@ async fn run(cli: Cli) -> Result<()> {
             slack.bookmarks.remove(&id, &bookmark_id).await?;
 
             if cli.json {
-                println!("{{\"ok\": true}}");
+                format::print_json_line(&serde_json::json!({ "ok": true }));
             } else {
                 println!("✓ Removed bookmark");
             }
@@ -382,36 +884,269 @@ async fn run(cli: Cli) -> Result<()> {
         Command::Bookmarks { channel } => {
             let id = resolve_channel(&channel, &slack, &cache, cli.json).await?;
             let bookmarks = slack.bookmarks.list(&id).await?;
-            format::print_bookmarks(&bookmarks, cli.json);
+            format::print_bookmarks(&bookmarks, output);
+        }
+
+        Command::Remind {
+            action: Some(action),
+            ..
+        } => match action {
+            RemindAction::List { all } => {
+                let reminders: Vec<slack::Reminder> = slack
+                    .reminders
+                    .list()
+                    .await?
+                    .into_iter()
+                    .filter(|r| all || !r.is_complete())
+                    .collect();
+                format::print_reminders(&reminders, output);
+            }
+
+            RemindAction::Complete { id } => {
+                slack.reminders.complete(&id).await?;
+                if cli.json {
+                    format::print_json_line(&serde_json::json!({"id": id, "completed": true}));
+                } else {
+                    println!("✓ Completed reminder {id}");
+                }
+            }
+
+            RemindAction::Delete { id } => {
+                slack.reminders.delete(&id).await?;
+                if cli.json {
+                    format::print_json_line(&serde_json::json!({"id": id, "deleted": true}));
+                } else {
+                    println!("✓ Deleted reminder {id}");
+                }
+            }
+        },
+
+        Command::Remind {
+            action: None,
+            text,
+            at,
+            user,
+        } => {
+            let text = text.context("reminder text is required")?;
+            let at = at.context("--at is required")?;
+            let time = time::parse_future_time(&at, time::display().zone, Utc::now())?;
+            if time <= Utc::now().timestamp() {
+                anyhow::bail!(
+                    "--at {at} is in the past. Use a future time, e.g. --at 'tomorrow 15:00' or --at 'in 30m'"
+                );
+            }
+            let user = match user {
+                Some(input) => Some(resolve_user(&input, &slack, &cache, cli.json).await?),
+                None => None,
+            };
+
+            let reminder = slack.reminders.add(&text, time, user.as_deref()).await?;
+            if cli.json {
+                format::print_json(&reminder);
+            } else {
+                let when = time::display()
+                    .zone
+                    .format(time, false)
+                    .unwrap_or_else(|| time.to_string());
+                println!(
+                    "✓ Reminder {} set for {when}: {}",
+                    reminder.id, reminder.text
+                );
+            }
         }
 
         Command::Cache { action } => match action {
-            CacheAction::Refresh { target } => {
+            CacheAction::Refresh {
+                target,
+                background: false,
+            } => {
                 refresh_cache(&slack, &cache, target, cli.json).await?;
             }
 
+            CacheAction::Refresh {
+                target,
+                background: true,
+            } => {
+                if cache.try_acquire_lock(BACKGROUND_REFRESH_LOCK)? {
+                    let result = tokio::select! {
+                        result = refresh_cache(&slack, &cache, target, true) => result,
+                        () = cache.keep_lock(BACKGROUND_REFRESH_LOCK) => unreachable!(),
+                    };
+                    cache.release_lock(BACKGROUND_REFRESH_LOCK).await?;
+                    result?;
+                }
+            }
+
             CacheAction::Stats => {
-                let (users, channels) = cache.get_counts()?;
+                let stats = cache.stats(
+                    config.cache.ttl_users_hours,
+                    config.cache.ttl_channels_hours,
+                    config.cache.refresh_threshold_percent,
+                )?;
+                format::print_cache_stats(&stats, output);
+            }
+
+            CacheAction::Path => {
+                println!("{}", db_path.display());
+            }
+
+            CacheAction::Check { repair } => {
+                let mut report = cache.check_integrity()?;
+                let repaired = repair && !report.ok && report.sqlite == ["ok"];
+                if repaired {
+                    cache.rebuild_fts()?;
+                    report = cache.check_integrity()?;
+                }
+                format::print_integrity_report(&report, repaired, output);
+                if !report.ok {
+                    anyhow::bail!(if report.sqlite == ["ok"] {
+                        "search index is out of sync; run `slack-cli cache check --repair`"
+                    } else {
+                        "cache database is corrupt; delete it (`slack-cli cache path`) and run `slack-cli cache refresh`"
+                    });
+                }
+            }
+
+            CacheAction::Optimize => {
+                cache.optimize()?;
+                if cli.json {
+                    format::print_json_line(&serde_json::json!({ "status": "ok" }));
+                } else {
+                    println!("✓ Search indexes optimized and statistics updated");
+                }
+            }
+
+            CacheAction::Vacuum => {
+                let (before, after) = cache.vacuum()?;
                 if cli.json {
+                    format::print_json_line(
+                        &serde_json::json!({ "bytes_before": before, "bytes_after": after }),
+                    );
+                } else {
                     println!(
-                        "{}",
-                        serde_json::json!({ "users": users, "channels": channels })
+                        "✓ Vacuumed: {} → {} ({} reclaimed)",
+                        format::human_bytes(before),
+                        format::human_bytes(after),
+                        format::human_bytes(before - after)
                     );
+                }
+            }
+
+            CacheAction::Clear { target } => {
+                let users = match target {
+                    RefreshTarget::Users | RefreshTarget::All => Some(cache.clear_users().await?),
+                    RefreshTarget::Channels | RefreshTarget::Members => None,
+                };
+                let channels = match target {
+                    RefreshTarget::Channels | RefreshTarget::All => {
+                        Some(cache.clear_channels().await?)
+                    }
+                    RefreshTarget::Users | RefreshTarget::Members => None,
+                };
+                let memberships = match target {
+                    RefreshTarget::Members => Some(cache.clear_members().await?),
+                    _ => None,
+                };
+                if cli.json {
+                    format::print_json_line(&serde_json::json!({
+                        "users_removed": users,
+                        "channels_removed": channels,
+                        "memberships_removed": memberships,
+                    }));
                 } else {
-                    println!("Users: {}, Channels: {}", users, channels);
+                    if let Some(users) = users {
+                        println!("✓ Removed {users} users");
+                    }
+                    if let Some(channels) = channels {
+                        println!("✓ Removed {channels} channels");
+                    }
+                    if let Some(memberships) = memberships {
+                        println!("✓ Removed {memberships} memberships");
+                    }
                 }
             }
 
-            CacheAction::Path => {
-                println!("{}", db_path.display());
+            CacheAction::Export { file } => {
+                let team_id = cache_team_id(&config, &authenticator).await;
+                // Written beside the target and renamed into place, so a
+                // failed export never leaves a truncated snapshot behind.
+                let dir = file
+                    .parent()
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .unwrap_or(std::path::Path::new("."));
+                let mut tmp = tempfile::NamedTempFile::new_in(dir)
+                    .with_context(|| format!("cannot create {}", file.display()))?;
+                let header = cache.export_snapshot(
+                    std::io::BufWriter::new(tmp.as_file_mut()),
+                    team_id.as_deref(),
+                )?;
+                tmp.as_file().sync_all()?;
+                tmp.persist(&file)
+                    .with_context(|| format!("cannot write {}", file.display()))?;
+                print_snapshot_summary("Exported", &header, &file, cli.json)?;
+            }
+
+            CacheAction::Import { file, force } => {
+                let team_id = cache_team_id(&config, &authenticator).await;
+                let input = std::fs::File::open(&file)
+                    .with_context(|| format!("cannot open {}", file.display()))?;
+                let header = cache
+                    .import_snapshot(std::io::BufReader::new(input), team_id.as_deref(), force)
+                    .await?;
+                print_snapshot_summary("Imported", &header, &file, cli.json)?;
             }
+
+            CacheAction::Locks {
+                break_key,
+                break_all,
+            } => match (break_key, break_all) {
+                (Some(key), _) => {
+                    let broken = cache.break_lock(&key)?;
+                    if cli.json {
+                        format::print_json_line(
+                            &serde_json::json!({ "key": key, "broken": broken }),
+                        );
+                    } else if broken {
+                        println!("✓ Broke lock '{key}'");
+                    } else {
+                        println!("No lock named '{key}'");
+                    }
+                }
+                (None, true) => {
+                    let broken = cache.break_all_locks()?;
+                    if cli.json {
+                        format::print_json_line(&serde_json::json!({ "broken": broken }));
+                    } else {
+                        println!("✓ Broke {broken} locks");
+                    }
+                }
+                (None, false) => format::print_locks(&cache.list_locks()?, output),
+            },
         },
 
         Command::Auth { .. } | Command::Config { .. } => unreachable!(),
     }
 
-    if cache_status == CacheStatus::NeedsRefresh && !cli.json {
-        eprintln!("Cache is stale. Run `slack-cli cache refresh` to update local lookup data.");
+    if cache_status == CacheStatus::NeedsRefresh {
+        match config.cache.stale_refresh {
+            StaleRefresh::Warn if !cli.json => eprintln!(
+                "Cache is stale. Run `slack-cli cache refresh` to update local lookup data."
+            ),
+            StaleRefresh::Background
+                // The command already printed its output; an unreadable lock
+                // only means skipping the refresh, never failing the run.
+                if !is_cache_command
+                    && !cache.is_locked(BACKGROUND_REFRESH_LOCK).unwrap_or_else(|err| {
+                        tracing::debug!("Could not check the background refresh lock: {err:#}");
+                        true
+                    }) =>
+            {
+                if let Err(err) = spawn_background_refresh(&refresh_args) {
+                    tracing::warn!("Could not start background cache refresh: {err:#}");
+                }
+            }
+            _ => {}
+        }
     }
 
     Ok(())
@@ -444,6 +1179,7 @@ fn classify_error(err: &anyhow::Error) -> (String, u8) {
                 (code.clone(), 3)
             }
             SlackApiError::Api { code } => (code.clone(), 1),
+            SlackApiError::MissingScope { .. } => ("missing_scope".to_string(), 3),
             SlackApiError::RateLimitExhausted { .. } => ("rate_limited".to_string(), 4),
             SlackApiError::Http { .. } => ("http_error".to_string(), 1),
             SlackApiError::Transport { .. } => ("network_error".to_string(), 1),
@@ -454,6 +1190,18 @@ fn classify_error(err: &anyhow::Error) -> (String, u8) {
         return ("auth_error".to_string(), 3);
     }
 
+    if err.downcast_ref::<QueryError>().is_some() {
+        return ("invalid_query".to_string(), 2);
+    }
+
+    if let Some(JqError::Compile(_)) = err.downcast_ref::<JqError>() {
+        return ("invalid_jq".to_string(), 2);
+    }
+
+    if err.downcast_ref::<TimeError>().is_some() {
+        return ("invalid_time".to_string(), 2);
+    }
+
     ("error".to_string(), 1)
 }
 
@@ -476,6 +1224,102 @@ fn handle_config_action(
     }
 }
 
+/// Records messages in the archive. The archive is a side feature, so a
+/// failure is logged and the command carries on.
+fn archive_messages(
+    cache: &cache::SqliteCache,
+    channel: &str,
+    thread: Option<&str>,
+    messages: &[slack::SlackMessage],
+) {
+    if let Err(err) = cache.archive_messages(channel, thread, messages) {
+        tracing::warn!("Failed to archive messages in {channel}: {err:#}");
+    }
+}
+
+/// Applies `--exclude-bots` and `--with-threads` to one page of history.
+/// Bot parents are dropped before their threads would be fetched. Fetched
+/// threads go to `archive` before bot replies are dropped.
+async fn filter_history(
+    slack: &slack::SlackClient,
+    archive: Option<&cache::SqliteCache>,
+    channel: &str,
+    messages: &mut Vec<slack::SlackMessage>,
+    exclude_bots: bool,
+    with_threads: bool,
+) -> Result<()> {
+    if exclude_bots {
+        messages.retain(|m| m.bot_id.is_none());
+    }
+    if with_threads {
+        slack.messages.attach_replies(channel, messages).await?;
+        if let Some(cache) = archive {
+            for msg in messages.iter() {
+                if let Some(replies) = &msg.replies {
+                    archive_messages(cache, channel, Some(&msg.ts), replies);
+                }
+            }
+        }
+        if exclude_bots {
+            for replies in messages.iter_mut().filter_map(|m| m.replies.as_mut()) {
+                replies.retain(|m| m.bot_id.is_none());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `#name` for a channel, `@person` for a DM (through the user cache).
+fn conversation_label(
+    id: &str,
+    channels: &[slack::SlackChannel],
+    cache: &cache::SqliteCache,
+) -> String {
+    let Some(channel) = channels.iter().find(|c| c.id == id) else {
+        return id.to_string();
+    };
+    match (&channel.name, &channel.user) {
+        (Some(name), _) if !channel.is_im => format!("#{name}"),
+        (_, Some(user)) => {
+            let name = cache
+                .get_user_by_id(user)
+                .ok()
+                .flatten()
+                .map(|u| u.name)
+                .unwrap_or_else(|| user.clone());
+            format!("@{name}")
+        }
+        _ => id.to_string(),
+    }
+}
+
+/// `--format-template` over the command's configured template. Only text
+/// output uses one, so a broken configured template never blocks `--json`.
+fn text_template(
+    flag: Option<&str>,
+    configured: Option<&str>,
+    output: format::OutputFormat,
+    vocabulary: &[&str],
+) -> Result<Option<format::Template>> {
+    if output != format::OutputFormat::Text {
+        return Ok(None);
+    }
+    flag.or(configured)
+        .map(|source| format::Template::parse(source, vocabulary))
+        .transpose()
+        .context("invalid output template")
+}
+
+/// `--author-tz` is only visible through `date`, so it brings the field in.
+fn message_fields(defaults: &[String], expand: Option<&[String]>, author_tz: bool) -> Vec<String> {
+    let fields = merge_fields(defaults, expand);
+    if author_tz {
+        merge_fields(&fields, Some(&["date".to_string()]))
+    } else {
+        fields
+    }
+}
+
 fn merge_fields(defaults: &[String], expand: Option<&[String]>) -> Vec<String> {
     let mut fields = defaults.to_vec();
     if let Some(extra) = expand {
@@ -546,7 +1390,166 @@ async fn resolve_channel(
         .copied()
         .or_else(|| channels.first())
         .map(|c| c.id.clone())
-        .context(format!("Channel not found: {}", input))
+        .with_context(|| {
+            let hint = did_you_mean(
+                cache
+                    .fuzzy_channels(name, 3)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|r| r.item.name.map(|n| format!("#{n}"))),
+            );
+            format!("Channel not found: {}{}", input, hint)
+        })
+}
+
+fn did_you_mean(candidates: impl Iterator<Item = String>) -> String {
+    let candidates: Vec<String> = candidates.collect();
+    if candidates.is_empty() {
+        String::new()
+    } else {
+        format!(". Did you mean: {}?", candidates.join(", "))
+    }
+}
+
+async fn resolve_user(
+    input: &str,
+    slack: &slack::SlackClient,
+    cache: &cache::SqliteCache,
+    json: bool,
+) -> Result<String> {
+    let name = strip_mention(input, "<@").trim_start_matches('@');
+    if is_slack_user_id(name) {
+        return Ok(name.to_string());
+    }
+
+    let mut users = cache.search_users(name, 5, true)?;
+    if users.is_empty() {
+        ensure_users_cache(slack, cache, json).await?;
+        users = cache.search_users(name, 5, true)?;
+    }
+
+    let exact = users.iter().find(|u| {
+        let profile = u.profile.as_ref();
+        [
+            Some(u.name.as_str()),
+            profile.and_then(|p| p.display_name.as_deref()),
+            profile.and_then(|p| p.real_name.as_deref()),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|n| n.eq_ignore_ascii_case(name))
+    });
+
+    match (exact, users.as_slice()) {
+        (Some(user), _) | (None, [user]) => Ok(user.id.clone()),
+        (None, []) => anyhow::bail!(
+            "User not found: {}{}",
+            input,
+            did_you_mean(
+                cache
+                    .fuzzy_users(name, 3, true)?
+                    .into_iter()
+                    .map(|r| format!("@{}", r.item.name))
+            )
+        ),
+        (None, candidates) => anyhow::bail!(
+            "User name is ambiguous: {}. Matches: {}",
+            input,
+            candidates
+                .iter()
+                .map(|u| format!("@{} ({})", u.name, u.id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// `<@U123|alice>` / `<#C123|general>` → the bare ID; other input unchanged.
+fn strip_mention<'a>(input: &'a str, open: &str) -> &'a str {
+    input
+        .strip_prefix(open)
+        .and_then(|rest| rest.strip_suffix('>'))
+        .map_or(input, |inner| inner.split('|').next().unwrap_or(inner))
+}
+
+/// Moves inline modifiers out of a search query and into `options`,
+/// resolving users and channels through the cache. Errors point at the
+/// offending token.
+async fn apply_search_modifiers(
+    query: &str,
+    options: &mut slack::SearchOptions,
+    slack: &slack::SlackClient,
+    cache: &cache::SqliteCache,
+    json: bool,
+) -> Result<String> {
+    let parsed = search_query::parse(query)?;
+
+    for modifier in parsed.modifiers {
+        let at = |message: String| QueryError::new(query, modifier.span.clone(), message);
+        let date = |value: &str| parse_unix_seconds(value).map_err(|e| at(e.to_string()));
+
+        match modifier.kind {
+            ModifierKind::From => {
+                if options.from_user.is_some() {
+                    return Err(
+                        at("only one author can be searched: repeated `from:`".into()).into(),
+                    );
+                }
+                let user = strip_mention(&modifier.value, "<@");
+                let user_id = resolve_user(user, slack, cache, json)
+                    .await
+                    .map_err(|e| at(e.to_string()))?;
+                options.from_user = Some(user_id);
+            }
+            ModifierKind::In => {
+                if options.context_channel_id.is_some() {
+                    return Err(at("only one channel can be searched: `in:` conflicts with --channel or another `in:`".into()).into());
+                }
+                let channel = strip_mention(&modifier.value, "<#");
+                let id = resolve_channel(channel, slack, cache, json)
+                    .await
+                    .map_err(|e| at(e.to_string()))?;
+                options.context_channel_id = Some(id);
+            }
+            ModifierKind::After | ModifierKind::Before | ModifierKind::On => {
+                let start = date(&modifier.value)?;
+                let (after, before) = match modifier.kind {
+                    ModifierKind::After => (Some(start), None),
+                    ModifierKind::Before => (None, Some(start)),
+                    _ => (Some(start), Some(start + 86_400)),
+                };
+                if (after.is_some() && options.after.is_some())
+                    || (before.is_some() && options.before.is_some())
+                {
+                    return Err(at(format!(
+                        "`{}:` conflicts with --after/--before or an earlier date modifier",
+                        modifier.kind.as_str()
+                    ))
+                    .into());
+                }
+                options.after = after.or(options.after);
+                options.before = before.or(options.before);
+            }
+            ModifierKind::Has => options.has.push(modifier.value.to_ascii_lowercase()),
+            ModifierKind::Is => options.is.push(modifier.value.to_ascii_lowercase()),
+        }
+    }
+
+    Ok(parsed.text)
+}
+
+/// Search history is a convenience: a locked or read-only cache must not
+/// turn a search that already printed its results into a failure.
+fn record_search(
+    cache: &cache::SqliteCache,
+    query: &str,
+    options: &slack::SearchOptions,
+    saved_name: Option<&str>,
+    results: usize,
+) {
+    if let Err(err) = cache.record_search(query, options, saved_name, results) {
+        tracing::warn!("Could not record search history: {err:#}");
+    }
 }
 
 fn is_slack_conversation_id(input: &str) -> bool {
@@ -604,25 +1607,30 @@ async fn ensure_channels_cache(
     Ok(())
 }
 
-fn parse_unix_seconds(input: &str) -> Result<i64> {
-    if let Ok(secs) = input.parse::<f64>() {
-        return Ok(secs as i64);
+/// Fetches members of every unarchived channel the caller is in and
+/// replaces the cached memberships. DMs are skipped, and a channel that
+/// fails (e.g. it was deleted mid-refresh) is logged and left out. Channels
+/// the caller is not in are never covered.
+async fn sync_channel_members(
+    slack: &slack::SlackClient,
+    cache: &cache::SqliteCache,
+    channels: &[slack::SlackChannel],
+) -> Result<usize> {
+    let mut memberships = Vec::new();
+    for channel in channels
+        .iter()
+        .filter(|c| c.is_member && !c.is_archived && !c.is_im)
+    {
+        match slack.channels.members(&channel.id).await {
+            Ok(members) => memberships.push((channel.id.clone(), members)),
+            Err(err) => tracing::warn!("Skipping members of {}: {err:#}", channel.id),
+        }
     }
+    Ok(cache.save_channel_members(memberships).await?)
+}
 
-    let date = NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| {
-        anyhow::anyhow!(
-            "Invalid date format: {} (expected Unix timestamp or YYYY-MM-DD)",
-            input
-        )
-    })?;
-    let dt = date
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| anyhow::anyhow!("Invalid time"))?;
-    let local = Local
-        .from_local_datetime(&dt)
-        .single()
-        .ok_or_else(|| anyhow::anyhow!("Invalid timezone conversion"))?;
-    Ok(local.timestamp())
+fn parse_unix_seconds(input: &str) -> Result<i64> {
+    Ok(time::parse_time(input, time::display().zone, Utc::now())?)
 }
 
 fn parse_timestamp(input: &str) -> Result<String> {
@@ -738,36 +1746,48 @@ async fn refresh_cache(
     target: RefreshTarget,
     json: bool,
 ) -> Result<()> {
-    match target {
-        RefreshTarget::Users | RefreshTarget::All => {
-            if !json {
-                eprint!("Fetching users... ");
-            }
-            let users = slack.users.list().await?;
-            cache.save_users(users).await?;
-            if !json {
-                eprintln!("✓");
-            }
+    let mut report = serde_json::Map::new();
+    report.insert("status".into(), "ok".into());
+
+    if matches!(target, RefreshTarget::Users | RefreshTarget::All) {
+        if !json {
+            eprint!("Fetching users... ");
+        }
+        let users = slack.users.list().await?;
+        let stats = cache.save_users(users).await?;
+        if !json {
+            eprintln!("✓ {}", format_sync_stats(&stats));
         }
-        _ => {}
+        report.insert("users".into(), serde_json::to_value(stats)?);
     }
 
-    match target {
-        RefreshTarget::Channels | RefreshTarget::All => {
-            if !json {
-                eprint!("Fetching channels... ");
-            }
-            let channels = slack.channels.list().await?;
-            cache.save_channels(channels).await?;
-            if !json {
-                eprintln!("✓");
-            }
+    if matches!(target, RefreshTarget::Channels | RefreshTarget::All) {
+        if !json {
+            eprint!("Fetching channels... ");
+        }
+        let channels = slack.channels.list().await?;
+        let stats = cache.save_channels(channels).await?;
+        if !json {
+            eprintln!("✓ {}", format_sync_stats(&stats));
+        }
+        report.insert("channels".into(), serde_json::to_value(stats)?);
+    }
+
+    // One call per channel, so only on request rather than with `all`.
+    if matches!(target, RefreshTarget::Members) {
+        ensure_channels_cache(slack, cache, json).await?;
+        if !json {
+            eprint!("Fetching channel members... ");
+        }
+        let written = sync_channel_members(slack, cache, &cache.get_channels()?).await?;
+        if !json {
+            eprintln!("✓ {written} memberships");
         }
-        _ => {}
+        report.insert("memberships".into(), written.into());
     }
 
     if json {
-        println!("{{\"status\": \"ok\"}}");
+        format::print_json_line(&report);
     } else {
         println!("✓ Cache refreshed");
     }
@@ -775,6 +1795,88 @@ async fn refresh_cache(
     Ok(())
 }
 
+/// Workspace the cache belongs to: the selected Enterprise Grid team, else
+/// the active profile's workspace.
+async fn cache_team_id(config: &config::Config, authenticator: &Authenticator) -> Option<String> {
+    match &config.connection.team_id {
+        Some(team) => Some(team.clone()),
+        None => authenticator.team_id().await,
+    }
+}
+
+fn print_snapshot_summary(
+    verb: &str,
+    header: &cache::SnapshotHeader,
+    file: &std::path::Path,
+    json: bool,
+) -> Result<()> {
+    if json {
+        format::print_json_line(header);
+    } else {
+        println!(
+            "✓ {verb} {} users, {} channels, {} memberships ({}, team {})",
+            header.users,
+            header.channels,
+            header.memberships,
+            file.display(),
+            header.team_id.as_deref().unwrap_or("-")
+        );
+    }
+    Ok(())
+}
+
+/// Global options the detached refresher needs to open the same profile,
+/// workspace and cache file as the current invocation.
+fn background_refresh_args(cli: &Cli) -> Vec<OsString> {
+    let mut args: Vec<OsString> = Vec::new();
+    let options = [
+        ("--config", cli.config.clone().map(OsString::from)),
+        ("--data-dir", cli.data_dir.clone().map(OsString::from)),
+        ("--profile", cli.profile.clone().map(OsString::from)),
+        ("--team", cli.team.clone().map(OsString::from)),
+        (
+            "--as",
+            cli.token_as
+                .and_then(|kind| kind.to_possible_value())
+                .map(|value| OsString::from(value.get_name())),
+        ),
+    ];
+    for (flag, value) in options {
+        if let Some(value) = value {
+            args.extend([flag.into(), value]);
+        }
+    }
+    args.extend(["cache", "refresh", "all", "--background"].map(OsString::from));
+    args
+}
+
+/// Re-runs this binary as `cache refresh --background` without waiting for
+/// it. The child takes `BACKGROUND_REFRESH_LOCK` itself, so a second spawn
+/// racing this one exits without calling Slack.
+fn spawn_background_refresh(args: &[OsString]) -> Result<()> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Own process group, so Ctrl-C on the parent's terminal spares it.
+        command.process_group(0);
+    }
+    command.spawn()?;
+    Ok(())
+}
+
+fn format_sync_stats(stats: &cache::SyncStats) -> String {
+    format!(
+        "{} added, {} changed, {} removed ({} unchanged)",
+        stats.added, stats.changed, stats.removed, stats.unchanged
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -796,6 +1898,15 @@ mod tests {
         assert_eq!(classify_error(&err), ("invalid_auth".to_string(), 3));
     }
 
+    #[test]
+    fn classify_error_maps_missing_scope_to_exit_3() {
+        let err = anyhow::Error::from(SlackApiError::MissingScope {
+            method: "chat.postMessage".to_string(),
+            needed: vec!["chat:write".to_string()],
+        });
+        assert_eq!(classify_error(&err), ("missing_scope".to_string(), 3));
+    }
+
     #[test]
     fn classify_error_maps_rate_limits_to_exit_4() {
         let exhausted = anyhow::Error::from(SlackApiError::RateLimitExhausted {
@@ -831,6 +1942,131 @@ mod tests {
         assert_eq!(classify_error(&err), ("error".to_string(), 1));
     }
 
+    #[test]
+    fn classify_error_maps_query_errors_to_exit_2() {
+        let err: anyhow::Error = QueryError::new("x foo:bar", 2..9, "unsupported").into();
+        assert_eq!(classify_error(&err), ("invalid_query".to_string(), 2));
+    }
+
+    #[test]
+    fn classify_error_maps_bad_jq_filters_to_exit_2() {
+        let err: anyhow::Error = jq::Jq::compile(".[] |").err().unwrap().into();
+        assert_eq!(classify_error(&err), ("invalid_jq".to_string(), 2));
+    }
+
+    #[test]
+    fn classify_error_maps_bad_times_and_zones_to_exit_2() {
+        let err: anyhow::Error = "Mars/Olympus".parse::<time::Zone>().unwrap_err().into();
+        assert_eq!(classify_error(&err), ("invalid_time".to_string(), 2));
+    }
+
+    #[test]
+    fn author_tz_brings_in_the_date_field() {
+        let defaults = vec!["ts".to_string(), "text".to_string()];
+        assert_eq!(message_fields(&defaults, None, false), defaults);
+        assert_eq!(
+            message_fields(&defaults, Some(&["date".to_string()]), true),
+            ["ts", "text", "date"]
+        );
+    }
+
+    #[test]
+    fn background_refresh_forwards_cache_selecting_options() {
+        let cli = Cli::parse_from([
+            "slack-cli",
+            "--profile",
+            "work",
+            "--team",
+            "T1",
+            "--as",
+            "bot",
+            "--json",
+            "users",
+            "john",
+        ]);
+        assert_eq!(
+            background_refresh_args(&cli),
+            [
+                "--profile",
+                "work",
+                "--team",
+                "T1",
+                "--as",
+                "bot",
+                "cache",
+                "refresh",
+                "all",
+                "--background"
+            ]
+            .map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn output_flag_selects_format_and_json_stays_an_alias() {
+        let mut cli = Cli::parse_from(["slack-cli", "-o", "csv", "users", "john"]);
+        cli.normalize_output();
+        assert_eq!(cli.output_format(), format::OutputFormat::Csv);
+        assert!(cli.json);
+
+        let mut cli = Cli::parse_from(["slack-cli", "--output", "table", "users", "john"]);
+        cli.normalize_output();
+        assert!(!cli.json);
+
+        let cli = Cli::parse_from(["slack-cli", "--json", "users", "john"]);
+        assert_eq!(cli.output_format(), format::OutputFormat::Json);
+
+        assert!(
+            Cli::try_parse_from(["slack-cli", "--json", "-o", "csv", "users", "john"]).is_err()
+        );
+    }
+
+    #[test]
+    fn jq_implies_json_next_to_subcommand_query_flags() {
+        let mut cli = Cli::parse_from([
+            "slack-cli",
+            "members",
+            "general",
+            "--query",
+            "ali",
+            "--jq",
+            ".[0]",
+        ]);
+        cli.normalize_output();
+        assert_eq!(cli.output_format(), format::OutputFormat::Json);
+        assert!(cli.json);
+        assert_eq!(cli.jq.as_deref(), Some(".[0]"));
+
+        let cli = Cli::parse_from(["slack-cli", "-o", "ndjson", "--jq", ".id", "users", "a"]);
+        assert_eq!(cli.output_format(), format::OutputFormat::Ndjson);
+    }
+
+    #[test]
+    fn format_template_flag_wins_over_config_in_text_mode_only() {
+        use format::{OutputFormat, USER_FIELDS};
+        let text = OutputFormat::Text;
+        let flag = text_template(Some("{{id}}"), Some("{{name}}"), text, USER_FIELDS).unwrap();
+        let configured = text_template(None, Some("{{name}}"), text, USER_FIELDS).unwrap();
+        assert_ne!(flag, configured);
+        assert_eq!(
+            configured,
+            Some(format::Template::parse("{{name}}", USER_FIELDS).unwrap())
+        );
+
+        // A bad configured template only matters when it would be used.
+        assert!(text_template(None, Some("{{bogus}}"), text, USER_FIELDS).is_err());
+        let json = text_template(None, Some("{{bogus}}"), OutputFormat::Json, USER_FIELDS);
+        assert_eq!(json.unwrap(), None);
+    }
+
+    #[test]
+    fn strip_mention_extracts_ids_from_slack_markup() {
+        assert_eq!(strip_mention("<@U123|alice>", "<@"), "U123");
+        assert_eq!(strip_mention("<#C123>", "<#"), "C123");
+        assert_eq!(strip_mention("#deploys", "<#"), "#deploys");
+        assert_eq!(strip_mention("@alice", "<@"), "@alice");
+    }
+
     #[test]
     fn build_payload_rejects_two_stdin_sources() {
         let err = build_payload(MessageContent {
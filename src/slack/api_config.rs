@@ -15,9 +15,32 @@ pub struct RatePolicy {
     pub max_page_limit: Option<usize>,
 }
 
+/// Scope alternatives for methods that accept the per-conversation-type
+/// variant of a scope: any one of them is enough for the call to succeed
+/// against conversations of that type.
+const HISTORY_SCOPES: &[&str] = &[
+    "channels:history",
+    "groups:history",
+    "im:history",
+    "mpim:history",
+];
+const READ_SCOPES: &[&str] = &["channels:read", "groups:read", "im:read", "mpim:read"];
+const WRITE_SCOPES: &[&str] = &["channels:write", "groups:write", "im:write", "mpim:write"];
+const SEARCH_SCOPES: &[&str] = &[
+    "search:read",
+    "search:read.public",
+    "search:read.private",
+    "search:read.im",
+    "search:read.mpim",
+];
+
 pub struct ApiConfig {
     pub encoding: RequestEncoding,
     pub token_policy: TokenPolicy,
+    /// Scopes that authorize this method. Any one entry is sufficient; the
+    /// first is the canonical one suggested to users. Empty means the method
+    /// needs no scope at all.
+    pub scopes: &'static [&'static str],
     pub rate_policy: RatePolicy,
 }
 
@@ -25,18 +48,25 @@ impl ApiConfig {
     pub const fn new(
         encoding: RequestEncoding,
         token_policy: TokenPolicy,
+        scopes: &'static [&'static str],
         requests_per_minute: u32,
         max_page_limit: Option<usize>,
     ) -> Self {
         Self {
             encoding,
             token_policy,
+            scopes,
             rate_policy: RatePolicy {
                 requests_per_minute,
                 max_page_limit,
             },
         }
     }
+
+    /// True when `granted` contains at least one of this method's scopes.
+    pub fn is_authorized_by(&self, granted: &[String]) -> bool {
+        self.scopes.is_empty() || self.scopes.iter().any(|s| granted.iter().any(|g| g == s))
+    }
 }
 
 pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::new(|| {
@@ -47,6 +77,7 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
         ApiConfig::new(
             RequestEncoding::Query,
             TokenPolicy::UserPreferred,
+            HISTORY_SCOPES,
             50,
             Some(999),
         ),
@@ -56,6 +87,7 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
         ApiConfig::new(
             RequestEncoding::Query,
             TokenPolicy::UserPreferred,
+            HISTORY_SCOPES,
             50,
             Some(1000),
         ),
@@ -65,15 +97,39 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
         ApiConfig::new(
             RequestEncoding::Query,
             TokenPolicy::UserPreferred,
+            READ_SCOPES,
             20,
             Some(1000),
         ),
     );
+    // Read cursors belong to a person, so these only make sense with a
+    // user token.
+    m.insert(
+        "conversations.info",
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::UserRequired,
+            READ_SCOPES,
+            50,
+            None,
+        ),
+    );
+    m.insert(
+        "conversations.mark",
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::UserRequired,
+            WRITE_SCOPES,
+            50,
+            None,
+        ),
+    );
     m.insert(
         "users.list",
         ApiConfig::new(
             RequestEncoding::Query,
             TokenPolicy::BotPreferred,
+            &["users:read"],
             20,
             Some(200),
         ),
@@ -83,6 +139,7 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
         ApiConfig::new(
             RequestEncoding::Query,
             TokenPolicy::UserPreferred,
+            READ_SCOPES,
             20,
             Some(1000),
         ),
@@ -90,63 +147,181 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
 
     m.insert(
         "chat.postMessage",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 60, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["chat:write"],
+            60,
+            None,
+        ),
     );
     m.insert(
         "chat.update",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 60, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["chat:write"],
+            60,
+            None,
+        ),
     );
     m.insert(
         "chat.delete",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 60, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["chat:write"],
+            60,
+            None,
+        ),
     );
     m.insert(
         "chat.getPermalink",
-        ApiConfig::new(RequestEncoding::Query, TokenPolicy::BotPreferred, 100, None),
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::BotPreferred,
+            &[],
+            100,
+            None,
+        ),
     );
 
     m.insert(
         "reactions.add",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["reactions:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "reactions.remove",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["reactions:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "reactions.get",
-        ApiConfig::new(RequestEncoding::Query, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::BotPreferred,
+            &["reactions:read"],
+            20,
+            None,
+        ),
     );
 
     m.insert(
         "pins.add",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["pins:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "pins.remove",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["pins:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "pins.list",
-        ApiConfig::new(RequestEncoding::Query, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::BotPreferred,
+            &["pins:read"],
+            20,
+            None,
+        ),
     );
 
     m.insert(
         "bookmarks.add",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["bookmarks:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "bookmarks.remove",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::BotPreferred,
+            &["bookmarks:write"],
+            20,
+            None,
+        ),
     );
     m.insert(
         "bookmarks.list",
-        ApiConfig::new(RequestEncoding::Query, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::BotPreferred,
+            &["bookmarks:read"],
+            20,
+            None,
+        ),
+    );
+
+    // Reminders belong to a person; Slack only accepts user tokens here.
+    m.insert(
+        "reminders.add",
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::UserRequired,
+            &["reminders:write"],
+            20,
+            None,
+        ),
+    );
+    for method in ["reminders.complete", "reminders.delete"] {
+        m.insert(
+            method,
+            ApiConfig::new(
+                RequestEncoding::Json,
+                TokenPolicy::UserRequired,
+                &["reminders:write"],
+                20,
+                None,
+            ),
+        );
+    }
+    m.insert(
+        "reminders.list",
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::UserRequired,
+            &["reminders:read"],
+            20,
+            None,
+        ),
     );
 
     m.insert(
         "emoji.list",
-        ApiConfig::new(RequestEncoding::Query, TokenPolicy::BotPreferred, 20, None),
+        ApiConfig::new(
+            RequestEncoding::Query,
+            TokenPolicy::BotPreferred,
+            &["emoji:read"],
+            20,
+            None,
+        ),
     );
 
     m.insert(
@@ -154,18 +329,46 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
         ApiConfig::new(
             RequestEncoding::Json,
             TokenPolicy::UserRequired,
+            SEARCH_SCOPES,
             10,
             Some(20),
         ),
     );
 
+    // Legacy fallback for `search` when the Real-time Search API is not
+    // enabled; these predate the granular search:read.* scopes.
+    for method in ["search.messages", "search.files", "search.all"] {
+        m.insert(
+            method,
+            ApiConfig::new(
+                RequestEncoding::Query,
+                TokenPolicy::UserRequired,
+                &["search:read"],
+                20,
+                None,
+            ),
+        );
+    }
+
     m.insert(
         "auth.test",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::UserPreferred, 60, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::UserPreferred,
+            &[],
+            60,
+            None,
+        ),
     );
     m.insert(
         "auth.revoke",
-        ApiConfig::new(RequestEncoding::Json, TokenPolicy::UserPreferred, 60, None),
+        ApiConfig::new(
+            RequestEncoding::Json,
+            TokenPolicy::UserPreferred,
+            &[],
+            60,
+            None,
+        ),
     );
 
     m
@@ -174,3 +377,37 @@ pub static API_CONFIGS: LazyLock<HashMap<&'static str, ApiConfig>> = LazyLock::n
 pub fn get_api_config(method: &str) -> Option<&'static ApiConfig> {
     API_CONFIGS.get(method)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn granted(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn scopeless_method_is_always_authorized() {
+        let config = get_api_config("auth.test").unwrap();
+        assert!(config.is_authorized_by(&[]));
+    }
+
+    #[test]
+    fn any_alternative_scope_authorizes() {
+        let config = get_api_config("conversations.history").unwrap();
+        assert!(config.is_authorized_by(&granted(&["im:history"])));
+        assert!(!config.is_authorized_by(&granted(&["channels:read"])));
+    }
+
+    #[test]
+    fn every_method_declares_scopes_known_to_slack() {
+        for (method, config) in API_CONFIGS.iter() {
+            for scope in config.scopes {
+                assert!(
+                    scope.contains(':'),
+                    "{method} lists malformed scope {scope}"
+                );
+            }
+        }
+    }
+}
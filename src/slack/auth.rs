@@ -44,6 +44,12 @@ impl SlackAuthClient {
         serde_json::from_value(response).context("auth.test response did not match expected shape")
     }
 
+    /// `auth.test` with the token commands use, to learn who "me" is.
+    pub async fn whoami(&self) -> Result<SlackAuthIdentity> {
+        let response = self.core.api_call("auth.test", json!({})).await?;
+        serde_json::from_value(response).context("auth.test response did not match expected shape")
+    }
+
     /// Calls `auth.revoke` against Slack with an explicit token.
     pub async fn revoke(&self, token: &str) -> Result<()> {
         self.core
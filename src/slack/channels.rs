@@ -1,12 +1,27 @@
 use anyhow::Result;
+use serde::Serialize;
 use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 use super::core::SlackCore;
 use crate::slack::SlackChannel;
 
 const PAGE_SIZE: u32 = 200;
 const MEMBERS_PAGE_SIZE: u32 = 1000;
+/// In-flight `conversations.info` calls for `inbox`; the method's rate
+/// limiter still paces the requests themselves.
+const INFO_FETCH_CONCURRENCY: usize = 4;
+
+/// Where the caller stopped reading a conversation (`conversations.info`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReadState {
+    pub channel_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_read: Option<String>,
+    pub unread_count: i64,
+}
 
 pub struct SlackChannelClient {
     pub(crate) core: Arc<SlackCore>,
@@ -30,6 +45,7 @@ impl SlackChannelClient {
             .map(|t| t.as_api_str())
             .collect::<Vec<_>>()
             .join(",");
+        let team_id = self.core.list_team_id().await?;
 
         loop {
             let mut params = json!({
@@ -37,6 +53,9 @@ impl SlackChannelClient {
                 "types": types,
                 "exclude_archived": false,
             });
+            if let Some(team) = &team_id {
+                params["team_id"] = json!(team);
+            }
             if let Some(c) = &cursor {
                 params["cursor"] = json!(c);
             }
@@ -116,4 +135,74 @@ impl SlackChannelClient {
 
         Ok(all_members)
     }
+
+    pub async fn read_state(&self, channel: &str) -> Result<ReadState> {
+        let response = self
+            .core
+            .api_call("conversations.info", json!({ "channel": channel }))
+            .await?;
+        let info = &response["channel"];
+        Ok(ReadState {
+            channel_id: channel.to_string(),
+            // Slack reports "never read" as a zero ts.
+            last_read: info["last_read"]
+                .as_str()
+                .filter(|ts| !ts.trim_start_matches(['0', '.']).is_empty())
+                .map(str::to_string),
+            unread_count: info["unread_count_display"]
+                .as_i64()
+                .or_else(|| info["unread_count"].as_i64())
+                .unwrap_or(0),
+        })
+    }
+
+    /// `read_state` of every channel, fetched concurrently, in input order.
+    /// A channel that fails (e.g. left or deleted since the cache refresh) is
+    /// logged and left out; only when every channel fails is that an error.
+    pub async fn read_states(&self, channels: &[String]) -> Result<Vec<ReadState>> {
+        let semaphore = Arc::new(Semaphore::new(INFO_FETCH_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for (index, channel) in channels.iter().enumerate() {
+            let client = Self {
+                core: Arc::clone(&self.core),
+            };
+            let channel = channel.clone();
+            let semaphore = Arc::clone(&semaphore);
+            tasks.spawn(async move {
+                let state = match semaphore.acquire_owned().await {
+                    Ok(_permit) => client.read_state(&channel).await,
+                    Err(err) => Err(err.into()),
+                };
+                (index, channel, state)
+            });
+        }
+
+        let mut states = vec![None; channels.len()];
+        let mut last_error = None;
+        while let Some(joined) = tasks.join_next().await {
+            match joined? {
+                (index, _, Ok(state)) => states[index] = Some(state),
+                (_, channel, Err(err)) => {
+                    tracing::warn!("Skipping read state of {channel}: {err:#}");
+                    last_error = Some(err);
+                }
+            }
+        }
+        let states: Vec<ReadState> = states.into_iter().flatten().collect();
+        match last_error {
+            Some(err) if states.is_empty() => Err(err),
+            _ => Ok(states),
+        }
+    }
+
+    /// Moves the caller's read cursor in `channel` to `ts`.
+    pub async fn mark(&self, channel: &str, ts: &str) -> Result<()> {
+        self.core
+            .api_call(
+                "conversations.mark",
+                json!({ "channel": channel, "ts": ts }),
+            )
+            .await?;
+        Ok(())
+    }
 }
@@ -13,6 +13,7 @@ use super::emoji::SlackEmojiClient;
 use super::messages::SlackMessageClient;
 use super::pins::SlackPinClient;
 use super::reactions::SlackReactionClient;
+use super::reminders::SlackReminderClient;
 use super::search::SlackSearchClient;
 use super::users::SlackUserClient;
 
@@ -25,6 +26,7 @@ pub struct SlackClient {
     pub emoji: SlackEmojiClient,
     pub pins: SlackPinClient,
     pub bookmarks: SlackBookmarkClient,
+    pub reminders: SlackReminderClient,
     pub search: SlackSearchClient,
 }
 
@@ -41,6 +43,7 @@ impl SlackClient {
             emoji: SlackEmojiClient::new(core.clone()),
             pins: SlackPinClient::new(core.clone()),
             bookmarks: SlackBookmarkClient::new(core.clone()),
+            reminders: SlackReminderClient::new(core.clone()),
             search: SlackSearchClient::new(core),
         })
     }
@@ -74,9 +74,31 @@ impl SlackCore {
         })
     }
 
+    /// `team_id` for workspace-scoped list methods (`users.list`,
+    /// `conversations.list`). Org-wide tokens cannot list without one.
+    pub(crate) async fn list_team_id(&self) -> Result<Option<String>> {
+        if let Some(team) = &self.config.connection.team_id {
+            return Ok(Some(team.clone()));
+        }
+        if self.auth.is_enterprise_install().await {
+            anyhow::bail!(
+                "this profile holds an org-wide Enterprise Grid token; \
+                 select a workspace with --team T... or connection.team_id"
+            );
+        }
+        Ok(None)
+    }
+
     pub async fn api_call(&self, method: &str, params: Value) -> Result<Value> {
         let api_config = lookup_config(method)?;
-        let token = self.auth.token_for(api_config.token_policy).await?;
+        let token = self.auth.token_for(method, api_config.token_policy).await?;
+        if let Some(granted) = self
+            .auth
+            .granted_scopes(method, api_config.token_policy)
+            .await
+        {
+            check_scopes(method, api_config, &granted)?;
+        }
         self.dispatch(method, api_config, params, token.expose_secret())
             .await
     }
@@ -207,6 +229,15 @@ impl SlackCore {
                     .get("error")
                     .and_then(|e| e.as_str())
                     .unwrap_or("unknown_error");
+                if code == "missing_scope"
+                    && let Some(needed) = json_response.get("needed").and_then(|n| n.as_str())
+                {
+                    return Err(SlackApiError::MissingScope {
+                        method: method.to_string(),
+                        needed: needed.split(',').map(|s| s.trim().to_string()).collect(),
+                    }
+                    .into());
+                }
                 return Err(SlackApiError::Api {
                     code: code.to_string(),
                 }
@@ -235,6 +266,19 @@ impl SlackCore {
     }
 }
 
+/// Fails fast with `MissingScope` instead of spending a rate-limited request
+/// on a call Slack is certain to reject.
+fn check_scopes(method: &str, api_config: &ApiConfig, granted: &[String]) -> Result<()> {
+    if api_config.is_authorized_by(granted) {
+        return Ok(());
+    }
+    Err(SlackApiError::MissingScope {
+        method: method.to_string(),
+        needed: api_config.scopes.iter().map(|s| s.to_string()).collect(),
+    }
+    .into())
+}
+
 fn lookup_config(method: &str) -> Result<&'static ApiConfig> {
     get_api_config(method).ok_or_else(|| anyhow::anyhow!("Unknown API method: {}", method))
 }
@@ -260,6 +304,21 @@ mod tests {
         assert_eq!(policy.max_page_limit, Some(15));
     }
 
+    #[test]
+    fn check_scopes_rejects_missing_scope_before_dispatch() {
+        let config = lookup_config("chat.postMessage").unwrap();
+        let err =
+            check_scopes("chat.postMessage", config, &["users:read".to_string()]).unwrap_err();
+        match err.downcast_ref::<SlackApiError>() {
+            Some(SlackApiError::MissingScope { method, needed }) => {
+                assert_eq!(method, "chat.postMessage");
+                assert_eq!(needed, &vec!["chat:write".to_string()]);
+            }
+            other => panic!("expected MissingScope, got {other:?}"),
+        }
+        assert!(check_scopes("chat.postMessage", config, &["chat:write".to_string()]).is_ok());
+    }
+
     #[test]
     fn marketplace_or_internal_keeps_tiered_history_limits() {
         let base = RatePolicy {
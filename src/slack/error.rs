@@ -10,6 +10,15 @@ pub enum SlackApiError {
     #[error("Slack API error: {code}")]
     Api { code: String },
 
+    /// The token lacks every scope that authorizes `method`. Raised before
+    /// dispatch when the profile's granted scopes are known, and mapped from
+    /// Slack's own `missing_scope` answer (with its `needed` list) otherwise.
+    #[error(
+        "{method} requires one of the scopes [{}]. run `slack-cli auth doctor` to see what the current token can do",
+        needed.join(", ")
+    )]
+    MissingScope { method: String, needed: Vec<String> },
+
     /// Every retry of a 429 response was consumed.
     #[error("Rate limit exceeded for {method} after {attempts} attempts")]
     RateLimitExhausted { method: String, attempts: u32 },
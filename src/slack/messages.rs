@@ -2,11 +2,21 @@ use anyhow::{Result, anyhow};
 use serde::Deserialize;
 use serde_json::{Value, json};
 use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 use super::core::SlackCore;
 use crate::slack::{MessageMetadata, SlackMessage};
 
 const REPLIES_PAGE_SIZE: usize = 1000;
+/// Page size when reading a thread only up to its first answer: the parent
+/// and its earliest replies, enough to look past the author's own
+/// follow-ups in one call.
+const FIRST_REPLY_PAGE_SIZE: usize = 5;
+const HISTORY_PAGE_SIZE: usize = 200;
+/// In-flight `conversations.replies` calls for `messages --with-threads`;
+/// the method's rate limiter still paces the requests themselves.
+const THREAD_FETCH_CONCURRENCY: usize = 4;
 
 #[derive(Debug, serde::Serialize, Deserialize)]
 pub struct MessageResponse {
@@ -212,6 +222,122 @@ impl SlackMessageClient {
         Ok((messages, next_cursor))
     }
 
+    /// Follows `next_cursor` from `cursor`, handing each page to `on_page`
+    /// as soon as it arrives. Stops after `max` messages when given and
+    /// returns the cursor to resume from, or `None` once history is
+    /// exhausted.
+    pub async fn history_pages(
+        &self,
+        channel: &str,
+        cursor: Option<&str>,
+        oldest: Option<&str>,
+        latest: Option<&str>,
+        max: Option<usize>,
+        mut on_page: impl AsyncFnMut(Vec<SlackMessage>) -> Result<()>,
+    ) -> Result<Option<String>> {
+        let mut cursor = cursor.map(str::to_string);
+        let mut remaining = max.unwrap_or(usize::MAX);
+        while remaining > 0 {
+            let (messages, next_cursor) = self
+                .history(
+                    channel,
+                    HISTORY_PAGE_SIZE.min(remaining),
+                    cursor.as_deref(),
+                    oldest,
+                    latest,
+                )
+                .await?;
+            remaining = remaining.saturating_sub(messages.len());
+            on_page(messages).await?;
+            cursor = next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(cursor)
+    }
+
+    /// Fills `replies` on every message with `reply_count > 0`, fetching
+    /// whole threads concurrently. The parent itself is dropped from each
+    /// thread.
+    pub async fn attach_replies(&self, channel: &str, messages: &mut [SlackMessage]) -> Result<()> {
+        self.attach_threads(channel, messages, false).await
+    }
+
+    /// Like `attach_replies`, but reads each thread in short pages only
+    /// until a reply from someone other than the parent's author shows up,
+    /// which is all a first-reply time needs. Most threads cost one call.
+    pub async fn attach_first_replies(
+        &self,
+        channel: &str,
+        messages: &mut [SlackMessage],
+    ) -> Result<()> {
+        self.attach_threads(channel, messages, true).await
+    }
+
+    async fn attach_threads(
+        &self,
+        channel: &str,
+        messages: &mut [SlackMessage],
+        until_answered: bool,
+    ) -> Result<()> {
+        let semaphore = Arc::new(Semaphore::new(THREAD_FETCH_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for (index, msg) in messages.iter().enumerate() {
+            if msg.reply_count.unwrap_or(0) == 0 {
+                continue;
+            }
+            let client = Self {
+                core: Arc::clone(&self.core),
+            };
+            let (channel, ts, author) = (channel.to_string(), msg.ts.clone(), msg.user.clone());
+            let semaphore = Arc::clone(&semaphore);
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let mut thread = if until_answered {
+                    client
+                        .replies_until_answered(&channel, &ts, author.as_deref())
+                        .await?
+                } else {
+                    client.replies(&channel, &ts, usize::MAX).await?
+                };
+                thread.retain(|reply| reply.ts != ts);
+                anyhow::Ok((index, thread))
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            let (index, thread) = joined??;
+            messages[index].replies = Some(thread);
+        }
+        Ok(())
+    }
+
+    /// Thread pages up to and including the first holding a reply not by
+    /// `author`, or the whole thread when there is none.
+    async fn replies_until_answered(
+        &self,
+        channel: &str,
+        thread_ts: &str,
+        author: Option<&str>,
+    ) -> Result<Vec<SlackMessage>> {
+        let mut thread = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (mut page, next) = self
+                .replies_page(channel, thread_ts, FIRST_REPLY_PAGE_SIZE, cursor.as_deref())
+                .await?;
+            let answered = page.iter().any(|reply| {
+                reply.ts != thread_ts && (author.is_none() || reply.user.as_deref() != author)
+            });
+            thread.append(&mut page);
+            match next {
+                Some(next) if !answered => cursor = Some(next),
+                _ => return Ok(thread),
+            }
+        }
+    }
+
     pub async fn replies(
         &self,
         channel: &str,
@@ -223,36 +349,11 @@ impl SlackMessageClient {
         let page_limit = limit.min(REPLIES_PAGE_SIZE);
 
         loop {
-            let mut params = json!({
-                "channel": channel,
-                "ts": thread_ts,
-                "limit": page_limit,
-                "include_all_metadata": true,
-            });
-
-            if let Some(c) = &cursor {
-                params["cursor"] = json!(c);
-            }
-
-            let mut response = self.core.api_call("conversations.replies", params).await?;
-
-            let raw_messages = response
-                .get_mut("messages")
-                .and_then(|v| v.as_array_mut())
-                .map(std::mem::take)
-                .ok_or_else(|| anyhow!("Missing messages in conversations.replies response"))?;
-
-            let mut page_messages = raw_messages
-                .into_iter()
-                .map(serde_json::from_value)
-                .collect::<Result<Vec<SlackMessage>, _>>()?;
-
+            let (mut page_messages, next) = self
+                .replies_page(channel, thread_ts, page_limit, cursor.as_deref())
+                .await?;
             all_messages.append(&mut page_messages);
-
-            cursor = response["response_metadata"]["next_cursor"]
-                .as_str()
-                .filter(|c| !c.is_empty())
-                .map(ToOwned::to_owned);
+            cursor = next;
 
             if cursor.is_none() || all_messages.len() >= limit {
                 break;
@@ -262,6 +363,45 @@ impl SlackMessageClient {
         all_messages.truncate(limit);
         Ok(all_messages)
     }
+
+    /// One `conversations.replies` page and the cursor for the next.
+    async fn replies_page(
+        &self,
+        channel: &str,
+        thread_ts: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> Result<(Vec<SlackMessage>, Option<String>)> {
+        let mut params = json!({
+            "channel": channel,
+            "ts": thread_ts,
+            "limit": limit,
+            "include_all_metadata": true,
+        });
+
+        if let Some(c) = cursor {
+            params["cursor"] = json!(c);
+        }
+
+        let mut response = self.core.api_call("conversations.replies", params).await?;
+
+        let raw_messages = response
+            .get_mut("messages")
+            .and_then(|v| v.as_array_mut())
+            .map(std::mem::take)
+            .ok_or_else(|| anyhow!("Missing messages in conversations.replies response"))?;
+
+        let messages = raw_messages
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<SlackMessage>, _>>()?;
+
+        let next = response["response_metadata"]["next_cursor"]
+            .as_str()
+            .filter(|c| !c.is_empty())
+            .map(ToOwned::to_owned);
+        Ok((messages, next))
+    }
 }
 
 #[cfg(test)]
@@ -9,20 +9,25 @@ pub mod error;
 pub mod messages;
 pub mod pins;
 pub mod reactions;
+pub mod reminders;
 pub mod search;
+mod search_legacy;
+pub mod search_query;
 pub mod types;
 pub mod users;
 
 pub use auth::SlackAuthIdentity;
 pub use bookmarks::Bookmark;
+pub use channels::ReadState;
 pub use client::SlackClient;
 pub use emoji::CustomEmoji;
 pub use error::SlackApiError;
 pub use messages::{MessagePayload, MessageResponse};
 pub use pins::PinnedMessage;
 pub use reactions::MessageReactions;
+pub use reminders::Reminder;
 pub use search::{
-    SearchChannelType, SearchContentType, SearchOptions, SearchResults, SearchSort,
-    SearchSortDirection,
+    SearchChannelType, SearchContentType, SearchMessageResult, SearchOptions, SearchPager,
+    SearchResults, SearchSort, SearchSortDirection,
 };
 pub use types::*;
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use super::core::SlackCore;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    /// Who gets reminded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub recurring: bool,
+    /// When it fires, Unix seconds. Recurring reminders have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<i64>,
+    /// When it was completed; Slack sends 0 while it is open.
+    #[serde(default)]
+    pub complete_ts: i64,
+}
+
+impl Reminder {
+    pub fn is_complete(&self) -> bool {
+        self.complete_ts > 0
+    }
+}
+
+pub struct SlackReminderClient {
+    core: Arc<SlackCore>,
+}
+
+impl SlackReminderClient {
+    pub fn new(core: Arc<SlackCore>) -> Self {
+        Self { core }
+    }
+
+    /// Reminds `user` (the caller when `None`) of `text` at `time`.
+    pub async fn add(&self, text: &str, time: i64, user: Option<&str>) -> Result<Reminder> {
+        let mut params = json!({
+            "text": text,
+            "time": time,
+        });
+        if let Some(user) = user {
+            params["user"] = json!(user);
+        }
+
+        let response = self.core.api_call("reminders.add", params).await?;
+        let reminder = response
+            .get("reminder")
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Missing reminder in reminders.add response"))?;
+        Ok(serde_json::from_value(reminder)?)
+    }
+
+    /// Every reminder the caller created or receives, completed ones included.
+    pub async fn list(&self) -> Result<Vec<Reminder>> {
+        let mut response = self.core.api_call("reminders.list", json!({})).await?;
+        let reminders = response
+            .get_mut("reminders")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| anyhow::anyhow!("Missing reminders in reminders.list response"))?;
+        Ok(serde_json::from_value(reminders)?)
+    }
+
+    pub async fn complete(&self, id: &str) -> Result<()> {
+        self.core
+            .api_call("reminders.complete", json!({ "reminder": id }))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.core
+            .api_call("reminders.delete", json!({ "reminder": id }))
+            .await?;
+        Ok(())
+    }
+}
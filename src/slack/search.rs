@@ -2,12 +2,25 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use super::core::SlackCore;
+use super::error::SlackApiError;
+use super::search_legacy::{self, LegacyCursor};
 
 const PAGE_SIZE: usize = 20;
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+/// `assistant.search.context` answers that mean the method is not available
+/// to this app or token at all, as opposed to a bad request.
+const UNAVAILABLE_CODES: &[&str] = &[
+    "not_allowed",
+    "not_allowed_token_type",
+    "unknown_method",
+    "feature_not_enabled",
+];
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchChannelType {
     #[value(name = "public_channel")]
     PublicChannel,
@@ -28,7 +41,8 @@ impl SearchChannelType {
     }
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchContentType {
     Messages,
     Files,
@@ -47,7 +61,8 @@ impl SearchContentType {
     }
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchSort {
     Score,
     Timestamp,
@@ -62,7 +77,8 @@ impl SearchSort {
     }
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchSortDirection {
     Asc,
     Desc,
@@ -77,7 +93,9 @@ impl SearchSortDirection {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Persisted verbatim for saved searches and history; the two presentation
+/// flags follow the current output mode instead of the stored one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchOptions {
     pub limit: usize,
     pub channel_types: Vec<SearchChannelType>,
@@ -91,12 +109,30 @@ pub struct SearchOptions {
     pub sort: SearchSort,
     pub sort_dir: SearchSortDirection,
     pub include_context_messages: bool,
+    /// `from:` author. `assistant.search.context` has no parameter for this
+    /// or `has`/`is`, so searches using any of them go to `search.messages`,
+    /// whose query syntax covers all three.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_user: Option<String>,
+    /// `has:` values (`link`, `file`, `pin`, `reaction`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub has: Vec<String>,
+    /// `is:` values (`thread`, `saved`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub is: Vec<String>,
+    #[serde(skip)]
     pub include_message_blocks: bool,
+    #[serde(skip)]
     pub highlight: bool,
 }
 
 impl SearchOptions {
     pub const MAX_LIMIT: usize = 100;
+
+    /// Whether only the legacy backend can honour these options.
+    fn needs_legacy(&self) -> bool {
+        self.from_user.is_some() || !self.has.is_empty() || !self.is.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -131,7 +167,7 @@ impl SearchResults {
         self.users.extend(other.users);
     }
 
-    fn total_len(&self) -> usize {
+    pub fn total_len(&self) -> usize {
         self.messages.len() + self.files.len() + self.channels.len() + self.users.len()
     }
 
@@ -243,50 +279,221 @@ pub struct SearchUserResult {
 
 pub struct SlackSearchClient {
     core: Arc<SlackCore>,
+    /// Set once `assistant.search.context` turns out to be unavailable, so
+    /// later calls go straight to the `search.messages` family.
+    legacy: AtomicBool,
 }
 
 impl SlackSearchClient {
     pub fn new(core: Arc<SlackCore>) -> Self {
-        Self { core }
+        Self {
+            core,
+            legacy: AtomicBool::new(false),
+        }
     }
 
-    pub async fn context(&self, query: &str, options: &SearchOptions) -> Result<SearchResults> {
+    /// Collects up to `options.limit` results starting at `cursor`. The
+    /// returned cursor resumes right after the last result handed back: the
+    /// final page is requested at exactly the remaining size, so nothing is
+    /// skipped between calls.
+    ///
+    /// Falls back to `search.messages`/`search.files` when the Real-time
+    /// Search API is not enabled for the app or token, or when the options
+    /// use `from`/`has`/`is`. Cursors from either backend are only valid for
+    /// that backend.
+    pub async fn context(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        cursor: Option<&str>,
+    ) -> Result<(SearchResults, Option<String>)> {
         let limit = options.limit.clamp(1, SearchOptions::MAX_LIMIT);
+        if self.prefers_legacy(cursor) || options.needs_legacy() {
+            return self.fetch_legacy(query, options, limit, cursor).await;
+        }
+
+        match self.context_rts(query, options, limit, cursor).await {
+            Err(err) if cursor.is_none() && self.fall_back(&err) => {
+                self.fetch_legacy(query, options, limit, None).await
+            }
+            other => other,
+        }
+    }
+
+    async fn context_rts(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> Result<(SearchResults, Option<String>)> {
         let mut params = build_request_params(query, options);
 
         let mut results = SearchResults::default();
-        let mut cursor: Option<String> = None;
+        let mut cursor = cursor.map(str::to_string);
 
         loop {
             let remaining = limit.saturating_sub(results.total_len());
             let page_size = remaining.clamp(1, PAGE_SIZE);
-            params["limit"] = json!(page_size);
+            let (page, next_cursor) = self
+                .fetch_page(&mut params, page_size, cursor.as_deref())
+                .await?;
 
-            if let Some(cursor) = &cursor {
-                params["cursor"] = json!(cursor);
+            results.extend(page);
+            cursor = next_cursor;
+
+            if cursor.is_none() || results.total_len() >= limit {
+                break;
             }
+        }
 
-            let response = self
-                .core
-                .api_call("assistant.search.context", params.clone())
-                .await
-                .context("Search requires a user token (xoxp-...) with search:read.* scopes")?;
-            let response: SearchContextResponse = serde_json::from_value(response)?;
+        results.truncate(limit);
+        Ok((results, cursor))
+    }
 
-            results.extend(response.results);
+    /// Unbounded pagination for `search --all`: no `MAX_LIMIT` cap, one
+    /// page per `next_page` call.
+    pub fn pages(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        cursor: Option<&str>,
+    ) -> SearchPager<'_> {
+        SearchPager {
+            client: self,
+            query: query.to_string(),
+            options: options.clone(),
+            params: build_request_params(query, options),
+            cursor: cursor.map(str::to_string),
+            exhausted: false,
+        }
+    }
 
-            cursor = response
-                .response_metadata
-                .map(|m| m.next_cursor)
-                .filter(|c| !c.is_empty());
+    fn prefers_legacy(&self, cursor: Option<&str>) -> bool {
+        cursor.map_or_else(
+            || self.legacy.load(Ordering::Relaxed),
+            search_legacy::is_legacy_cursor,
+        )
+    }
 
-            if cursor.is_none() || results.total_len() >= limit {
-                break;
+    /// Switches this client to the legacy backend if `err` says the Real-time
+    /// Search API is unavailable.
+    fn fall_back(&self, err: &anyhow::Error) -> bool {
+        let unavailable = matches!(
+            err.downcast_ref::<SlackApiError>(),
+            Some(SlackApiError::Api { code }) if UNAVAILABLE_CODES.contains(&code.as_str())
+        );
+        if unavailable {
+            tracing::warn!(
+                "assistant.search.context is unavailable ({err:#}); falling back to search.messages"
+            );
+            self.legacy.store(true, Ordering::Relaxed);
+        }
+        unavailable
+    }
+
+    async fn fetch_legacy(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        page_size: usize,
+        cursor: Option<&str>,
+    ) -> Result<(SearchResults, Option<String>)> {
+        let cursor = match cursor {
+            Some(raw) => {
+                LegacyCursor::parse(raw).with_context(|| format!("Invalid search cursor: {raw}"))?
+            }
+            None => LegacyCursor {
+                count: page_size.clamp(1, search_legacy::MAX_COUNT),
+                page: 1,
+            },
+        };
+        search_legacy::fetch(&self.core, query, options, cursor)
+            .await
+            .context("Search requires a user token (xoxp-...) with the search:read scope")
+    }
+
+    async fn fetch_page(
+        &self,
+        params: &mut Value,
+        page_size: usize,
+        cursor: Option<&str>,
+    ) -> Result<(SearchResults, Option<String>)> {
+        params["limit"] = json!(page_size);
+        match cursor {
+            Some(cursor) => params["cursor"] = json!(cursor),
+            None => {
+                if let Some(map) = params.as_object_mut() {
+                    map.remove("cursor");
+                }
             }
         }
 
-        results.truncate(limit);
-        Ok(results)
+        let response = self
+            .core
+            .api_call("assistant.search.context", params.clone())
+            .await
+            .context("Search requires a user token (xoxp-...) with search:read.* scopes")?;
+        let response: SearchContextResponse = serde_json::from_value(response)?;
+
+        let next_cursor = response
+            .response_metadata
+            .map(|m| m.next_cursor)
+            .filter(|c| !c.is_empty());
+        Ok((response.results, next_cursor))
+    }
+}
+
+pub struct SearchPager<'a> {
+    client: &'a SlackSearchClient,
+    query: String,
+    options: SearchOptions,
+    params: Value,
+    cursor: Option<String>,
+    exhausted: bool,
+}
+
+impl SearchPager<'_> {
+    /// `Ok(None)` once the last page has been returned.
+    pub async fn next_page(&mut self) -> Result<Option<SearchResults>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+        let cursor = self.cursor.take();
+        let cursor = cursor.as_deref();
+        let fetched = if self.client.prefers_legacy(cursor) || self.options.needs_legacy() {
+            self.client
+                .fetch_legacy(&self.query, &self.options, search_legacy::MAX_COUNT, cursor)
+                .await
+        } else {
+            match self
+                .client
+                .fetch_page(&mut self.params, PAGE_SIZE, cursor)
+                .await
+            {
+                Err(err) if cursor.is_none() && self.client.fall_back(&err) => {
+                    self.client
+                        .fetch_legacy(&self.query, &self.options, search_legacy::MAX_COUNT, None)
+                        .await
+                }
+                other => other,
+            }
+        };
+        let (page, next_cursor) = match fetched {
+            Ok(fetched) => fetched,
+            Err(err) => {
+                self.cursor = cursor.map(str::to_string);
+                return Err(err);
+            }
+        };
+        self.exhausted = next_cursor.is_none();
+        self.cursor = next_cursor;
+        Ok(Some(page))
+    }
+
+    /// Cursor of the next unfetched page; resumes an interrupted stream.
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
     }
 }
 
@@ -347,11 +554,30 @@ mod tests {
             sort: SearchSort::Score,
             sort_dir: SearchSortDirection::Desc,
             include_context_messages: false,
+            from_user: None,
+            has: Vec::new(),
+            is: Vec::new(),
             include_message_blocks: false,
             highlight: false,
         }
     }
 
+    #[test]
+    fn options_round_trip_without_presentation_flags() {
+        let mut options = sample_options();
+        options.include_message_blocks = true;
+        options.highlight = true;
+        options.before = Some(1_700_000_000);
+
+        let stored = serde_json::to_value(&options).unwrap();
+        assert_eq!(stored["channel_types"], json!(["public_channel"]));
+        assert!(stored.get("highlight").is_none());
+
+        let restored: SearchOptions = serde_json::from_value(stored).unwrap();
+        assert_eq!(restored.before, Some(1_700_000_000));
+        assert!(!restored.highlight && !restored.include_message_blocks);
+    }
+
     #[test]
     fn parses_real_time_search_message_response() {
         let response: SearchContextResponse = serde_json::from_value(json!({
@@ -0,0 +1,525 @@
+//! Fallback search backend for tokens or apps that cannot call
+//! `assistant.search.context`: the classic `search.messages`,
+//! `search.files` and `search.all` methods. `SearchOptions` are rendered as
+//! Slack query modifiers where the legacy syntax has one; channel types and
+//! archived channels have none, so matches are filtered on the channel flags
+//! Slack returns with them. The matches are mapped into `SearchResults`.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Days};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use super::core::SlackCore;
+use super::search::{
+    SearchChannelType, SearchContentType, SearchFileResult, SearchMessageResult, SearchOptions,
+    SearchResults,
+};
+
+/// Largest `count` the legacy methods accept per page.
+pub(super) const MAX_COUNT: usize = 100;
+
+const CURSOR_PREFIX: &str = "legacy:";
+
+/// Legacy pagination is page-numbered, so the page size has to travel with
+/// the cursor: resuming with a different `count` would shift every offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct LegacyCursor {
+    pub count: usize,
+    pub page: u32,
+}
+
+impl LegacyCursor {
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let (count, page) = cursor.strip_prefix(CURSOR_PREFIX)?.split_once(':')?;
+        Some(Self {
+            count: count.parse().ok().filter(|c| (1..=MAX_COUNT).contains(c))?,
+            page: page.parse().ok().filter(|p| *p >= 1)?,
+        })
+    }
+
+    pub fn encode(self) -> String {
+        format!("{CURSOR_PREFIX}{}:{}", self.count, self.page)
+    }
+}
+
+pub(super) fn is_legacy_cursor(cursor: &str) -> bool {
+    cursor.starts_with(CURSOR_PREFIX)
+}
+
+/// One page of legacy results plus the cursor for the next page, if any.
+pub(super) async fn fetch(
+    core: &SlackCore,
+    query: &str,
+    options: &SearchOptions,
+    cursor: LegacyCursor,
+) -> Result<(SearchResults, Option<String>)> {
+    let method = method_for(&options.content_types)?;
+    let response = core
+        .api_call(method, build_params(method, query, options, cursor))
+        .await?;
+    let response: LegacyResponse = serde_json::from_value(response)?;
+
+    let next = response.has_more().then(|| {
+        LegacyCursor {
+            page: cursor.page + 1,
+            ..cursor
+        }
+        .encode()
+    });
+    Ok((response.into_results(options, cursor.count), next))
+}
+
+fn method_for(content_types: &[SearchContentType]) -> Result<&'static str> {
+    let messages = content_types.is_empty()
+        || content_types
+            .iter()
+            .any(|t| matches!(t, SearchContentType::Messages));
+    let files = content_types
+        .iter()
+        .any(|t| matches!(t, SearchContentType::Files));
+
+    Ok(match (messages, files) {
+        (true, true) => "search.all",
+        (true, false) => "search.messages",
+        (false, true) => "search.files",
+        (false, false) => bail!(
+            "the search.messages fallback only covers messages and files; \
+             channel and user search need assistant.search.context"
+        ),
+    })
+}
+
+/// `search.all` applies `count` to messages and files separately, so each
+/// section gets half the page to keep the merged page within `cursor.count`.
+fn build_params(method: &str, query: &str, options: &SearchOptions, cursor: LegacyCursor) -> Value {
+    let count = if method == "search.all" {
+        (cursor.count / 2).max(1)
+    } else {
+        cursor.count
+    };
+    json!({
+        "query": build_query(query, options),
+        "count": count,
+        "page": cursor.page,
+        "sort": options.sort.as_api_str(),
+        "sort_dir": options.sort_dir.as_api_str(),
+    })
+}
+
+/// `before:`/`after:` only have day precision and are exclusive, so the
+/// window is widened by a day on each side and trimmed back to the exact
+/// timestamps in `into_results`.
+fn build_query(query: &str, options: &SearchOptions) -> String {
+    let mut modifiers = vec![query.trim().to_string()];
+
+    if let Some(channel_id) = &options.context_channel_id {
+        modifiers.push(format!("in:<#{channel_id}>"));
+    }
+    if let Some(user_id) = &options.from_user {
+        modifiers.push(format!("from:<@{user_id}>"));
+    }
+    modifiers.extend(options.has.iter().map(|v| format!("has:{v}")));
+    modifiers.extend(options.is.iter().map(|v| format!("is:{v}")));
+    if let Some(date) = options.after.and_then(|ts| day_offset(ts, -1)) {
+        modifiers.push(format!("after:{date}"));
+    }
+    if let Some(date) = options.before.and_then(|ts| day_offset(ts, 1)) {
+        modifiers.push(format!("before:{date}"));
+    }
+
+    modifiers.retain(|m| !m.is_empty());
+    modifiers.join(" ")
+}
+
+fn day_offset(ts: i64, days: i64) -> Option<String> {
+    let date = DateTime::from_timestamp(ts, 0)?.date_naive();
+    let shifted = if days < 0 {
+        date.checked_sub_days(Days::new(days.unsigned_abs()))
+    } else {
+        date.checked_add_days(Days::new(days as u64))
+    }?;
+    Some(shifted.format("%Y-%m-%d").to_string())
+}
+
+fn within(ts: f64, options: &SearchOptions) -> bool {
+    options.after.is_none_or(|after| ts >= after as f64)
+        && options.before.is_none_or(|before| ts < before as f64)
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyResponse {
+    #[serde(default)]
+    messages: Option<Section<LegacyMessage>>,
+    #[serde(default)]
+    files: Option<Section<LegacyFile>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Section<T> {
+    #[serde(default = "Vec::new")]
+    matches: Vec<T>,
+    #[serde(default)]
+    paging: Option<Paging>,
+}
+
+impl<T> Section<T> {
+    fn has_more(&self) -> bool {
+        self.paging.as_ref().is_some_and(|p| p.page < p.pages)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Paging {
+    #[serde(default)]
+    page: u32,
+    #[serde(default)]
+    pages: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyMessage {
+    ts: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    team: Option<String>,
+    #[serde(default)]
+    channel: Option<LegacyChannel>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    bot_id: Option<String>,
+    #[serde(default)]
+    permalink: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyChannel {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    is_im: bool,
+    #[serde(default)]
+    is_mpim: bool,
+    #[serde(default)]
+    is_private: bool,
+    #[serde(default)]
+    is_group: bool,
+    #[serde(default)]
+    is_archived: bool,
+}
+
+impl LegacyChannel {
+    fn channel_type(&self) -> SearchChannelType {
+        if self.is_im {
+            SearchChannelType::Im
+        } else if self.is_mpim {
+            SearchChannelType::Mpim
+        } else if self.is_private || self.is_group {
+            SearchChannelType::PrivateChannel
+        } else {
+            SearchChannelType::PublicChannel
+        }
+    }
+
+    fn matches(&self, options: &SearchOptions) -> bool {
+        (options.include_archived_channels || !self.is_archived)
+            && allows(options, self.channel_type())
+    }
+}
+
+/// An empty `channel_types` means every type, as on the RTS backend.
+fn allows(options: &SearchOptions, channel_type: SearchChannelType) -> bool {
+    options.channel_types.is_empty()
+        || options
+            .channel_types
+            .iter()
+            .any(|t| t.as_api_str() == channel_type.as_api_str())
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyFile {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    mimetype: Option<String>,
+    #[serde(default)]
+    preview: Option<String>,
+    #[serde(default)]
+    permalink: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    created: Option<i64>,
+    #[serde(default)]
+    timestamp: Option<i64>,
+    #[serde(default)]
+    channels: Vec<String>,
+    #[serde(default)]
+    groups: Vec<String>,
+    #[serde(default)]
+    ims: Vec<String>,
+}
+
+impl LegacyFile {
+    /// Files carry where they were shared but no archive flag. `groups`
+    /// holds both private channels and group DMs, so either type admits
+    /// them; a file shared nowhere is kept.
+    fn matches(&self, options: &SearchOptions) -> bool {
+        use SearchChannelType::*;
+        let mut types = Vec::new();
+        if !self.channels.is_empty() {
+            types.push(PublicChannel);
+        }
+        if !self.groups.is_empty() {
+            types.extend([PrivateChannel, Mpim]);
+        }
+        if !self.ims.is_empty() {
+            types.push(Im);
+        }
+        types.is_empty() || types.into_iter().any(|t| allows(options, t))
+    }
+}
+impl LegacyResponse {
+    fn has_more(&self) -> bool {
+        self.messages.as_ref().is_some_and(Section::has_more)
+            || self.files.as_ref().is_some_and(Section::has_more)
+    }
+
+    /// Trims to the exact time window and channel filters, and caps the
+    /// merged page at `count` (messages first).
+    fn into_results(self, options: &SearchOptions, count: usize) -> SearchResults {
+        let messages: Vec<SearchMessageResult> = self
+            .messages
+            .map(|s| s.matches)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| options.include_bots || m.bot_id.is_none())
+            .filter(|m| m.ts.parse().is_ok_and(|ts| within(ts, options)))
+            .filter(|m| m.channel.as_ref().is_none_or(|c| c.matches(options)))
+            .take(count)
+            .map(|m| {
+                let (channel_id, channel_name) =
+                    m.channel.map(|c| (c.id, c.name)).unwrap_or_default();
+                SearchMessageResult {
+                    is_author_bot: m.bot_id.is_some(),
+                    ts: m.ts,
+                    text: m.text,
+                    team_id: m.team,
+                    channel_id,
+                    channel_name,
+                    author_user_id: m.user,
+                    author_name: m.username,
+                    permalink: m.permalink,
+                    blocks: None,
+                    context_messages: None,
+                }
+            })
+            .collect();
+
+        let files = self
+            .files
+            .map(|s| s.matches)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| f.created.is_none_or(|ts| within(ts as f64, options)))
+            .filter(|f| f.matches(options))
+            .take(count - messages.len())
+            .map(|f| SearchFileResult {
+                file_id: f.id,
+                title: f.title,
+                file_type: f.mimetype,
+                content: f.preview,
+                permalink: f.permalink,
+                author_user_id: f.user,
+                author_name: f.username,
+                date_created: f.created,
+                date_updated: f.timestamp,
+            })
+            .collect();
+
+        SearchResults {
+            messages,
+            files,
+            channels: Vec::new(),
+            users: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack::search::{SearchChannelType, SearchSort, SearchSortDirection};
+
+    fn options() -> SearchOptions {
+        SearchOptions {
+            limit: 10,
+            channel_types: vec![SearchChannelType::PublicChannel],
+            content_types: vec![SearchContentType::Messages],
+            context_channel_id: None,
+            include_archived_channels: false,
+            before: None,
+            after: None,
+            include_bots: false,
+            disable_semantic_search: false,
+            sort: SearchSort::Timestamp,
+            sort_dir: SearchSortDirection::Asc,
+            include_context_messages: false,
+            from_user: None,
+            has: Vec::new(),
+            is: Vec::new(),
+            include_message_blocks: false,
+            highlight: false,
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips_and_rejects_foreign_values() {
+        let cursor = LegacyCursor { count: 20, page: 3 };
+        assert_eq!(cursor.encode(), "legacy:20:3");
+        assert_eq!(LegacyCursor::parse("legacy:20:3"), Some(cursor));
+        assert!(LegacyCursor::parse("dXNlcjpVMDYx").is_none());
+        assert!(LegacyCursor::parse("legacy:0:1").is_none());
+        assert!(LegacyCursor::parse("legacy:20:0").is_none());
+        assert!(is_legacy_cursor("legacy:20:3"));
+    }
+
+    #[test]
+    fn options_become_query_modifiers() {
+        let mut opts = options();
+        opts.context_channel_id = Some("C123".into());
+        // 2026-01-10T12:00:00Z and 2026-01-20T00:00:00Z
+        opts.after = Some(1_768_046_400);
+        opts.before = Some(1_768_867_200);
+
+        assert_eq!(
+            build_query(" deploy failed ", &opts),
+            "deploy failed in:<#C123> after:2026-01-09 before:2026-01-21"
+        );
+
+        let mut filtered = options();
+        filtered.from_user = Some("U1".into());
+        filtered.has = vec!["link".into()];
+        filtered.is = vec!["thread".into()];
+        assert_eq!(
+            build_query("deploy", &filtered),
+            "deploy from:<@U1> has:link is:thread"
+        );
+        assert_eq!(build_query("deploy", &options()), "deploy");
+
+        let cursor = LegacyCursor { count: 50, page: 2 };
+        let params = build_params("search.messages", "deploy", &opts, cursor);
+        assert_eq!(params["count"], json!(50));
+        assert_eq!(params["page"], json!(2));
+        assert_eq!(params["sort"], json!("timestamp"));
+        assert_eq!(params["sort_dir"], json!("asc"));
+
+        // search.all pages messages and files separately.
+        let params = build_params("search.all", "deploy", &opts, cursor);
+        assert_eq!(params["count"], json!(25));
+    }
+
+    #[test]
+    fn content_types_pick_the_method() {
+        use SearchContentType::*;
+        assert_eq!(method_for(&[Messages]).unwrap(), "search.messages");
+        assert_eq!(method_for(&[Files]).unwrap(), "search.files");
+        assert_eq!(method_for(&[Messages, Files, Users]).unwrap(), "search.all");
+        assert!(method_for(&[Channels, Users]).is_err());
+    }
+
+    #[test]
+    fn maps_matches_and_trims_to_exact_window() {
+        let response: LegacyResponse = serde_json::from_value(json!({
+            "ok": true,
+            "messages": {
+                "matches": [
+                    {
+                        "ts": "1768046500.000100",
+                        "text": "deploy failed",
+                        "team": "T1",
+                        "user": "U1",
+                        "username": "alice",
+                        "channel": {"id": "C1", "name": "ops"},
+                        "permalink": "https://example.slack.com/archives/C1/p1"
+                    },
+                    {"ts": "1768000000.000100", "text": "too early"},
+                    {"ts": "1768046600.000100", "text": "bot", "bot_id": "B1"}
+                ],
+                "paging": {"count": 20, "total": 45, "page": 1, "pages": 3}
+            },
+            "files": {
+                "matches": [{"id": "F1", "title": "log.txt", "mimetype": "text/plain", "created": 1768046500}],
+                "paging": {"count": 20, "total": 1, "page": 1, "pages": 1}
+            }
+        }))
+        .unwrap();
+
+        let mut opts = options();
+        opts.after = Some(1_768_046_400);
+        assert!(response.has_more());
+        let results = response.into_results(&opts, 20);
+
+        assert_eq!(results.messages.len(), 1);
+        let message = &results.messages[0];
+        assert_eq!(message.channel_id.as_deref(), Some("C1"));
+        assert_eq!(message.channel_name.as_deref(), Some("ops"));
+        assert_eq!(message.author_name.as_deref(), Some("alice"));
+        assert_eq!(results.files[0].file_id.as_deref(), Some("F1"));
+        assert_eq!(results.files[0].file_type.as_deref(), Some("text/plain"));
+    }
+
+    #[test]
+    fn filters_channel_types_and_archived_channels() {
+        let response = || -> LegacyResponse {
+            serde_json::from_value(json!({
+                "messages": {"matches": [
+                    {"ts": "1.0", "text": "public", "channel": {"id": "C1", "is_channel": true}},
+                    {"ts": "2.0", "text": "dm", "channel": {"id": "D1", "is_im": true}},
+                    {"ts": "3.0", "text": "group dm", "channel": {"id": "G1", "is_mpim": true, "is_private": true}},
+                    {"ts": "4.0", "text": "archived", "channel": {"id": "C2", "is_archived": true}}
+                ]},
+                "files": {"matches": [
+                    {"id": "F1", "channels": ["C1"]},
+                    {"id": "F2", "ims": ["D1"]},
+                    {"id": "F3"}
+                ]}
+            }))
+            .unwrap()
+        };
+
+        let mut opts = options();
+        opts.channel_types = vec![SearchChannelType::Im];
+        let results = response().into_results(&opts, 20);
+        let texts: Vec<&str> = results.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["dm"]);
+        let files: Vec<_> = results
+            .files
+            .iter()
+            .filter_map(|f| f.file_id.as_deref())
+            .collect();
+        assert_eq!(files, ["F2", "F3"]);
+
+        opts.channel_types = vec![SearchChannelType::PublicChannel];
+        opts.include_archived_channels = true;
+        let results = response().into_results(&opts, 20);
+        let texts: Vec<&str> = results.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["public", "archived"]);
+
+        // The merged page never exceeds the requested count.
+        opts.channel_types = Vec::new();
+        let results = response().into_results(&opts, 5);
+        assert_eq!(results.messages.len(), 4);
+        assert_eq!(results.files.len(), 1);
+    }
+}
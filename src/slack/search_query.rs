@@ -0,0 +1,278 @@
+//! Inline modifiers in `search` queries (`from:@alice in:#deploys
+//! after:2026-01-01 has:link is:thread`).
+//!
+//! Parsing is purely syntactic: values are returned with their byte span so
+//! the caller can resolve users, channels and dates and still point errors
+//! at the offending token.
+
+use std::fmt;
+use std::ops::Range;
+
+use thiserror::Error;
+
+const HAS_VALUES: &[&str] = &["link", "file", "pin", "reaction"];
+const IS_VALUES: &[&str] = &["thread", "saved"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierKind {
+    From,
+    In,
+    After,
+    Before,
+    On,
+    Has,
+    Is,
+}
+
+impl ModifierKind {
+    const ALL: [Self; 7] = [
+        Self::From,
+        Self::In,
+        Self::After,
+        Self::Before,
+        Self::On,
+        Self::Has,
+        Self::Is,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::From => "from",
+            Self::In => "in",
+            Self::After => "after",
+            Self::Before => "before",
+            Self::On => "on",
+            Self::Has => "has",
+            Self::Is => "is",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(key))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub kind: ModifierKind,
+    pub value: String,
+    /// Byte range of the whole `key:value` token in the original query.
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// Free text, including quoted phrases, with modifiers removed.
+    pub text: String,
+    pub modifiers: Vec<Modifier>,
+}
+
+/// A problem with one token of a search query, rendered with the query and
+/// a caret line underneath the token.
+#[derive(Debug, Error)]
+pub struct QueryError {
+    message: String,
+    query: String,
+    span: Range<usize>,
+}
+
+impl QueryError {
+    pub fn new(query: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            query: query.to_string(),
+            span,
+        }
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = self.query[..self.span.start].chars().count();
+        let width = self.query[self.span.clone()].chars().count().max(1);
+        write!(
+            f,
+            "{} (column {})\n  {}\n  {}{}",
+            self.message,
+            column + 1,
+            self.query,
+            " ".repeat(column),
+            "^".repeat(width)
+        )
+    }
+}
+
+/// Splits `query` into free text and modifiers. Tokens inside double quotes
+/// are always free text, as are tokens that only look like `key:value`
+/// (times such as `10:30`, `:emoji:`, URLs) and `key:value` tokens whose key
+/// is not a modifier (`error:timeout`).
+pub fn parse(query: &str) -> Result<ParsedQuery, QueryError> {
+    let mut parsed = ParsedQuery::default();
+    let mut text = Vec::new();
+
+    for (span, token) in tokenize(query)? {
+        let Some((key, value)) = token.split_once(':') else {
+            text.push(token);
+            continue;
+        };
+        let looks_like_modifier = !key.is_empty()
+            && key.chars().all(|c| c.is_ascii_alphabetic())
+            && !value.is_empty()
+            && !value.starts_with('/');
+        if token.starts_with('"') || !looks_like_modifier {
+            text.push(token);
+            continue;
+        }
+
+        let Some(kind) = ModifierKind::from_key(key) else {
+            text.push(token);
+            continue;
+        };
+
+        let allowed = match kind {
+            ModifierKind::Has => HAS_VALUES,
+            ModifierKind::Is => IS_VALUES,
+            _ => &[][..],
+        };
+        if !allowed.is_empty() && !allowed.contains(&value.to_ascii_lowercase().as_str()) {
+            return Err(QueryError::new(
+                query,
+                span,
+                format!(
+                    "unsupported value for `{key}:` (expected one of: {})",
+                    allowed.join(", ")
+                ),
+            ));
+        }
+
+        parsed.modifiers.push(Modifier {
+            kind,
+            value: value.to_string(),
+            span,
+        });
+    }
+
+    parsed.text = text.join(" ");
+    Ok(parsed)
+}
+
+/// Whitespace-separated tokens with their byte spans; a double-quoted
+/// phrase is one token, quotes included.
+fn tokenize(query: &str) -> Result<Vec<(Range<usize>, &str)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut in_quotes = false;
+
+    for (i, c) in query.char_indices() {
+        match (c, start) {
+            ('"', None) => {
+                start = Some(i);
+                in_quotes = true;
+            }
+            ('"', Some(_)) => in_quotes = !in_quotes,
+            (c, Some(s)) if c.is_whitespace() && !in_quotes => {
+                tokens.push((s..i, &query[s..i]));
+                start = None;
+            }
+            (c, None) if !c.is_whitespace() => start = Some(i),
+            _ => {}
+        }
+    }
+
+    if let Some(s) = start {
+        if in_quotes {
+            return Err(QueryError::new(
+                query,
+                s..query.len(),
+                "unterminated quoted phrase",
+            ));
+        }
+        tokens.push((s..query.len(), &query[s..]));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_modifiers_from_free_text() {
+        let query =
+            r#"deploy from:@alice in:#deploys "rolled back" after:2026-01-01 has:link is:thread"#;
+        let parsed = parse(query).unwrap();
+
+        assert_eq!(parsed.text, r#"deploy "rolled back""#);
+        let kinds: Vec<_> = parsed.modifiers.iter().map(|m| m.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ModifierKind::From,
+                ModifierKind::In,
+                ModifierKind::After,
+                ModifierKind::Has,
+                ModifierKind::Is
+            ]
+        );
+        assert_eq!(parsed.modifiers[0].value, "@alice");
+        assert_eq!(&query[parsed.modifiers[1].span.clone()], "in:#deploys");
+    }
+
+    #[test]
+    fn leaves_colon_tokens_that_are_not_modifiers_alone() {
+        let parsed = parse("standup at 10:30 :tada: https://example.com \"note:x\"").unwrap();
+        assert!(parsed.modifiers.is_empty());
+        assert_eq!(
+            parsed.text,
+            "standup at 10:30 :tada: https://example.com \"note:x\""
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_free_text() {
+        let parsed = parse("error:timeout status:ok from:@bob note:foo").unwrap();
+        assert_eq!(parsed.text, "error:timeout status:ok note:foo");
+        assert_eq!(parsed.modifiers.len(), 1);
+        assert_eq!(parsed.modifiers[0].kind, ModifierKind::From);
+    }
+
+    #[test]
+    fn unsupported_value_points_at_token() {
+        let err = parse("deploy has:today").unwrap_err();
+        assert_eq!(err.span(), 7..16);
+
+        let rendered = err.to_string();
+        assert!(rendered.starts_with("unsupported value for `has:`"));
+        assert!(rendered.contains("(column 8)"));
+        assert!(rendered.ends_with("\n  deploy has:today\n         ^^^^^^^^^"));
+    }
+
+    #[test]
+    fn rejects_unknown_has_and_is_values() {
+        assert!(
+            parse("has:cats")
+                .unwrap_err()
+                .to_string()
+                .contains("expected one of: link, file, pin, reaction")
+        );
+        assert_eq!(parse("x is:pinned").unwrap_err().span(), 2..11);
+    }
+
+    #[test]
+    fn reports_unterminated_quotes() {
+        let err = parse(r#"deploy "rolled back"#).unwrap_err();
+        assert_eq!(err.span(), 7..19);
+    }
+
+    #[test]
+    fn caret_column_counts_characters_not_bytes() {
+        let err = parse("배포 is:foo").unwrap_err();
+        assert!(err.to_string().ends_with("\n  배포 is:foo\n     ^^^^^^"));
+    }
+}
@@ -76,6 +76,12 @@ pub struct SlackChannel {
     pub is_group: bool,
     #[serde(default)]
     pub is_member: bool,
+    /// Shared with another organization through Slack Connect.
+    #[serde(default)]
+    pub is_ext_shared: bool,
+    /// Shared across workspaces of the same Enterprise Grid org.
+    #[serde(default)]
+    pub is_org_shared: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -171,6 +177,10 @@ pub struct SlackMessage {
     pub permalink: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub metadata: Option<MessageMetadata>,
+    /// Thread replies fetched for `messages --with-threads`. Never read from
+    /// Slack, whose legacy `replies` field is only `{user, ts}` stubs.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub replies: Option<Vec<SlackMessage>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,9 +19,13 @@ impl SlackUserClient {
     pub async fn list(&self) -> Result<Vec<SlackUser>> {
         let mut all_users = Vec::new();
         let mut cursor: Option<String> = None;
+        let team_id = self.core.list_team_id().await?;
 
         loop {
             let mut params = json!({ "limit": PAGE_SIZE });
+            if let Some(team) = &team_id {
+                params["team_id"] = json!(team);
+            }
             if let Some(c) = &cursor {
                 params["cursor"] = json!(c);
             }
@@ -0,0 +1,381 @@
+//! Time zones for reading time arguments (`--oldest`, `--before`, search
+//! `after:`, ...) and printing timestamps. The zone is `--tz`, else
+//! `output.timezone`, else the system zone.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{
+    DateTime, Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TimeError {
+    #[error("unknown time zone `{0}` (expected an IANA name like Asia/Seoul, or `local`)")]
+    UnknownZone(String),
+
+    #[error(
+        "invalid time `{0}` (expected a Unix timestamp, YYYY-MM-DD [HH:MM], HH:MM, an offset like 2h/3d/1w or in 30m, today, yesterday, tomorrow, last <weekday> or next <weekday>)"
+    )]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Zone {
+    #[default]
+    Local,
+    Named(Tz),
+}
+
+impl FromStr for Zone {
+    type Err = TimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+        s.parse::<Tz>()
+            .map(Self::Named)
+            .map_err(|_| TimeError::UnknownZone(s.to_string()))
+    }
+}
+
+impl Zone {
+    /// Unix seconds of a wall-clock time here. A time skipped by a DST
+    /// change is an error; a repeated one resolves to its first instant.
+    fn timestamp(self, dt: NaiveDateTime) -> Option<i64> {
+        match self {
+            Self::Local => Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .map(|t| t.timestamp()),
+            Self::Named(tz) => tz
+                .from_local_datetime(&dt)
+                .earliest()
+                .map(|t| t.timestamp()),
+        }
+    }
+
+    fn today(self, now: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Self::Local => now.with_timezone(&Local).date_naive(),
+            Self::Named(tz) => now.with_timezone(&tz).date_naive(),
+        }
+    }
+
+    /// `secs` as `YYYY-MM-DD HH:MM:SS` here, with the zone abbreviation
+    /// when `labelled`.
+    pub fn format(self, secs: i64, labelled: bool) -> Option<String> {
+        if labelled {
+            self.format_with(secs, "%Y-%m-%d %H:%M:%S %Z")
+        } else {
+            self.format_with(secs, "%Y-%m-%d %H:%M:%S")
+        }
+    }
+
+    pub fn format_date(self, secs: i64) -> Option<String> {
+        self.format_with(secs, "%Y-%m-%d")
+    }
+
+    /// Hour of day (0-23) of `secs` here.
+    pub fn hour(self, secs: i64) -> Option<u32> {
+        self.format_with(secs, "%H")?.parse().ok()
+    }
+
+    fn format_with(self, secs: i64, pattern: &str) -> Option<String> {
+        let utc = DateTime::from_timestamp(secs, 0)?;
+        Some(match self {
+            Self::Local => utc.with_timezone(&Local).format(pattern).to_string(),
+            Self::Named(tz) => utc.with_timezone(&tz).format(pattern).to_string(),
+        })
+    }
+}
+
+/// Unix seconds for a time argument. Dates and weekdays mean midnight in
+/// `zone` unless followed by `HH:MM`, and a bare `HH:MM` is today; offsets
+/// like `2h` count back from `now`, `in 2h` forward.
+pub fn parse_time(input: &str, zone: Zone, now: DateTime<Utc>) -> Result<i64, TimeError> {
+    parse(input, zone, now, false)
+}
+
+/// `parse_time` for times that must lie ahead, like reminders: a bare
+/// `HH:MM` already past today means tomorrow, a bare weekday means its
+/// next occurrence, today included while the time is still ahead, and a
+/// bare offset like `30m` counts forward as if written `in 30m`.
+pub fn parse_future_time(input: &str, zone: Zone, now: DateTime<Utc>) -> Result<i64, TimeError> {
+    parse(input, zone, now, true)
+}
+
+fn parse(input: &str, zone: Zone, now: DateTime<Utc>, ahead: bool) -> Result<i64, TimeError> {
+    let invalid = || TimeError::Invalid(input.to_string());
+    let text = input.trim().to_ascii_lowercase();
+
+    if let Ok(secs) = text.parse::<f64>() {
+        return Ok(secs as i64);
+    }
+    if let Some(secs) = parse_offset(&text) {
+        return Ok(if ahead {
+            now.timestamp() + secs
+        } else {
+            now.timestamp() - secs
+        });
+    }
+    if let Some(secs) = text
+        .strip_prefix("in ")
+        .and_then(|t| parse_offset(t.trim()))
+    {
+        return Ok(now.timestamp() + secs);
+    }
+    if text == "now" {
+        return Ok(now.timestamp());
+    }
+
+    let (day_text, clock) = split_clock(&text);
+    let today = zone.today(now);
+    let at = |day: NaiveDate| zone.timestamp(day.and_time(clock.unwrap_or(NaiveTime::MIN)));
+    if ahead {
+        let floating = match (day_text, clock) {
+            ("", Some(_)) => Some((today, 1)),
+            _ => day_text
+                .parse::<Weekday>()
+                .ok()
+                .map(|weekday| (weekday_on_or_after(today, weekday), 7)),
+        };
+        if let Some((day, step)) = floating {
+            let day = match at(day) {
+                Some(secs) if secs > now.timestamp() => day,
+                _ => day.checked_add_days(Days::new(step)).ok_or_else(invalid)?,
+            };
+            return at(day).ok_or_else(invalid);
+        }
+    }
+    let day = match (day_text, clock) {
+        ("", Some(_)) => Some(today),
+        _ => parse_day(day_text, today),
+    };
+    day.and_then(at).ok_or_else(invalid)
+}
+
+/// Splits a trailing `HH:MM` off `text`: `"tomorrow 09:30"` gives
+/// `("tomorrow", 09:30)`, `"15:00"` gives `("", 15:00)`.
+fn split_clock(text: &str) -> (&str, Option<NaiveTime>) {
+    let clock = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+    if let Some(time) = clock(text) {
+        return ("", Some(time));
+    }
+    match text.rsplit_once(' ') {
+        Some((day, time)) if clock(time).is_some() => (day.trim_end(), clock(time)),
+        _ => (text, None),
+    }
+}
+
+fn parse_day(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match text {
+        "today" => Some(today),
+        "yesterday" => today.checked_sub_days(Days::new(1)),
+        "tomorrow" => today.checked_add_days(Days::new(1)),
+        _ if text.starts_with("next ") => {
+            let weekday = text["next ".len()..].parse::<Weekday>().ok()?;
+            Some(weekday_on_or_after(today + Days::new(1), weekday))
+        }
+        _ => match text
+            .strip_prefix("last ")
+            .unwrap_or(text)
+            .parse::<Weekday>()
+        {
+            Ok(weekday) => Some(last_weekday(today, weekday)),
+            Err(_) => NaiveDate::parse_from_str(text, "%Y-%m-%d").ok(),
+        },
+    }
+}
+
+/// `90s`, `30m`, `2h`, `3d`, `1w` as seconds.
+fn parse_offset(text: &str) -> Option<i64> {
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = text.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let unit = match unit {
+        "s" => 1,
+        "m" | "min" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return None,
+    };
+    amount.checked_mul(unit)
+}
+
+/// The most recent `weekday` strictly before `today`, so `last monday`
+/// on a Monday is a week ago.
+fn last_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    let back = if back == 0 { 7 } else { back };
+    today - Days::new(u64::from(back))
+}
+
+/// `from` itself when it falls on `weekday`, else the next such day; `next
+/// monday` is this on the day after today, so on a Monday it is a week on.
+fn weekday_on_or_after(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    from + Days::new(u64::from(ahead))
+}
+
+/// How timestamps are printed, chosen once at startup.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Display {
+    pub zone: Zone,
+    /// Show each message in its author's profile zone (`--author-tz`).
+    pub author_zones: bool,
+}
+
+static DISPLAY: OnceLock<Display> = OnceLock::new();
+
+pub fn set_display(display: Display) {
+    let _ = DISPLAY.set(display);
+}
+
+pub fn display() -> Display {
+    DISPLAY.get().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seoul() -> Zone {
+        "Asia/Seoul".parse().unwrap()
+    }
+
+    // Wednesday 2024-03-13 15:00:00 UTC, 2024-03-14 00:00 in Seoul.
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 13, 15, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn dates_are_midnight_in_the_zone() {
+        let utc: Zone = "UTC".parse().unwrap();
+        assert_eq!(parse_time("2024-03-01", utc, now()), Ok(1_709_251_200));
+        assert_eq!(
+            parse_time("2024-03-01", seoul(), now()),
+            Ok(1_709_251_200 - 9 * 3600)
+        );
+        assert_eq!(
+            parse_time("2024-03-01 09:30", seoul(), now()),
+            Ok(1_709_251_200 + 30 * 60)
+        );
+        assert_eq!(
+            parse_time("1700000000.5", seoul(), now()),
+            Ok(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn relative_forms_count_from_now_in_the_zone() {
+        let now_secs = now().timestamp();
+        assert_eq!(parse_time("2h", seoul(), now()), Ok(now_secs - 7200));
+        assert_eq!(parse_time("1w", seoul(), now()), Ok(now_secs - 604_800));
+
+        // It is already Thursday the 14th in Seoul.
+        let midnight = |d| {
+            seoul().timestamp(
+                NaiveDate::from_ymd_opt(2024, 3, d)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            )
+        };
+        assert_eq!(parse_time("today", seoul(), now()).ok(), midnight(14));
+        assert_eq!(parse_time("Yesterday", seoul(), now()).ok(), midnight(13));
+        assert_eq!(parse_time("last monday", seoul(), now()).ok(), midnight(11));
+        assert_eq!(parse_time("thursday", seoul(), now()).ok(), midnight(7));
+    }
+
+    #[test]
+    fn clock_times_and_future_offsets() {
+        let at = |d, h, m| {
+            seoul().timestamp(
+                NaiveDate::from_ymd_opt(2024, 3, d)
+                    .unwrap()
+                    .and_hms_opt(h, m, 0)
+                    .unwrap(),
+            )
+        };
+        assert_eq!(parse_time("15:00", seoul(), now()).ok(), at(14, 15, 0));
+        assert_eq!(
+            parse_time("tomorrow 09:30", seoul(), now()).ok(),
+            at(15, 9, 30)
+        );
+        assert_eq!(
+            parse_time("Last Monday 18:05", seoul(), now()).ok(),
+            at(11, 18, 5)
+        );
+        assert_eq!(parse_time("tomorrow", seoul(), now()).ok(), at(15, 0, 0));
+        assert_eq!(
+            parse_time("in 30m", seoul(), now()),
+            Ok(now().timestamp() + 1800)
+        );
+        assert!(parse_time("25:00", seoul(), now()).is_err());
+        assert!(parse_time("in soon", seoul(), now()).is_err());
+    }
+
+    #[test]
+    fn future_times_roll_forward_to_the_next_occurrence() {
+        // 00:00 Thursday the 14th in Seoul.
+        let at = |d, h, m| {
+            seoul().timestamp(
+                NaiveDate::from_ymd_opt(2024, 3, d)
+                    .unwrap()
+                    .and_hms_opt(h, m, 0)
+                    .unwrap(),
+            )
+        };
+        let future = |input| parse_future_time(input, seoul(), now()).ok();
+        assert_eq!(future("15:00"), at(14, 15, 0));
+        assert_eq!(future("00:00"), at(15, 0, 0));
+        assert_eq!(future("friday 15:00"), at(15, 15, 0));
+        assert_eq!(future("thursday 09:00"), at(14, 9, 0));
+        assert_eq!(future("thursday"), at(21, 0, 0));
+        assert_eq!(future("next thursday"), at(21, 0, 0));
+        assert_eq!(future("tomorrow 09:30"), at(15, 9, 30));
+        assert_eq!(future("30m"), at(14, 0, 30));
+        assert_eq!(future("30m"), future("in 30m"));
+        assert_eq!(
+            parse_time("30m", seoul(), now()).ok(),
+            Some(now().timestamp() - 1800)
+        );
+        // Other forms read as in `parse_time`.
+        assert_eq!(future("last monday"), at(11, 0, 0));
+        assert_eq!(
+            parse_time("next friday 08:00", seoul(), now()).ok(),
+            at(15, 8, 0)
+        );
+        assert!(parse_time("next week", seoul(), now()).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_zones_and_inputs() {
+        assert_eq!(
+            "Mars/Olympus".parse::<Zone>(),
+            Err(TimeError::UnknownZone("Mars/Olympus".into()))
+        );
+        assert!(matches!(
+            parse_time("next week", Zone::Local, now()),
+            Err(TimeError::Invalid(_))
+        ));
+        assert!(parse_time("5y", Zone::Local, now()).is_err());
+    }
+
+    #[test]
+    fn formats_in_the_zone_with_optional_label() {
+        assert_eq!(
+            seoul().format(1_709_251_200, false).as_deref(),
+            Some("2024-03-01 09:00:00")
+        );
+        assert_eq!(
+            seoul().format(1_709_251_200, true).as_deref(),
+            Some("2024-03-01 09:00:00 KST")
+        );
+    }
+}
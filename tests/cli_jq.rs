@@ -0,0 +1,25 @@
+//! Runs the built binary to check that `--jq` reaches JSON printed by
+//! commands that report a single object rather than a list.
+
+use std::process::Command;
+
+#[test]
+fn jq_filters_single_object_output() {
+    let home = tempfile::tempdir().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_slack-cli"))
+        .env_clear()
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .env("SLACK_USER_TOKEN", "xoxp-test-user")
+        .args(["--jq", "has(\"bytes_before\") and has(\"bytes_after\")"])
+        .args(["cache", "vacuum"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+}
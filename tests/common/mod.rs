@@ -0,0 +1,58 @@
+//! Shared fixtures for the mock-Slack integration tests: a `SlackClient`
+//! pointed at a wiremock server, authenticated from env-style overrides.
+//! Each test crate uses a subset, hence the `dead_code` allowance.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use secrecy::SecretString;
+use slack_cli::auth::{AuthLoadOptions, Authenticator, EnvOverrides};
+use slack_cli::config::Config;
+use slack_cli::slack::SlackClient;
+use wiremock::MockServer;
+
+pub fn secret(value: &str) -> SecretString {
+    SecretString::new(value.to_string().into_boxed_str())
+}
+
+pub fn user_token() -> EnvOverrides {
+    EnvOverrides {
+        user_token: Some(secret("xoxp-test-user")),
+        bot_token: None,
+    }
+}
+
+pub fn user_and_bot_tokens() -> EnvOverrides {
+    EnvOverrides {
+        bot_token: Some(secret("xoxb-test-bot")),
+        ..user_token()
+    }
+}
+
+/// A user-token client with the default config.
+pub async fn test_client(server: &MockServer) -> (SlackClient, tempfile::TempDir) {
+    client(server, Config::default(), user_token()).await
+}
+
+/// Returns the `SlackClient` plus the tempdir backing the (unused) auth store.
+/// Bind the tempdir for the test's lifetime — dropping it cleans the fs.
+pub async fn client(
+    server: &MockServer,
+    mut config: Config,
+    tokens: EnvOverrides,
+) -> (SlackClient, tempfile::TempDir) {
+    config.connection.api_base_url = server.uri();
+    config.connection.rate_limit_per_minute = 600;
+
+    let store_dir = tempfile::tempdir().unwrap();
+    let authenticator = Authenticator::load(AuthLoadOptions {
+        store_path: store_dir.path().join("auth.json"),
+        overrides: tokens,
+        explicit_profile: None,
+        token_overrides: Default::default(),
+    })
+    .unwrap();
+
+    let client = SlackClient::new(config, Arc::new(authenticator)).unwrap();
+    (client, store_dir)
+}
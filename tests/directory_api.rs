@@ -0,0 +1,179 @@
+//! Integration tests for the users/channels directory clients against a mock
+//! Slack API: Enterprise Grid `team_id` scoping, shared-channel flags and
+//! read cursors (`conversations.info` / `conversations.mark`).
+
+mod common;
+
+use serde_json::json;
+use slack_cli::config::Config;
+use slack_cli::slack::SlackClient;
+use wiremock::matchers::{body_partial_json, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn test_client(server: &MockServer, team: Option<&str>) -> (SlackClient, tempfile::TempDir) {
+    let mut config = Config::default();
+    config.connection.team_id = team.map(str::to_string);
+    common::client(server, config, common::user_token()).await
+}
+
+#[tokio::test]
+async fn channels_list_injects_team_and_reads_shared_flags() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.list"))
+        .and(query_param("team_id", "T2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "channels": [
+                {"id": "C1", "name": "partners", "is_channel": true, "is_ext_shared": true},
+                {"id": "C2", "name": "all-hands", "is_channel": true, "is_org_shared": true},
+            ],
+            "response_metadata": {"next_cursor": ""},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, Some("T2")).await;
+    let channels = client.channels.list().await.unwrap();
+
+    assert!(channels[0].is_ext_shared && !channels[0].is_org_shared);
+    assert!(channels[1].is_org_shared && !channels[1].is_ext_shared);
+}
+
+#[tokio::test]
+async fn users_list_injects_team() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users.list"))
+        .and(query_param("team_id", "T2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "members": [{"id": "U1", "name": "alice"}],
+            "response_metadata": {"next_cursor": ""},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, Some("T2")).await;
+    let users = client.users.list().await.unwrap();
+    assert_eq!(users[0].id, "U1");
+}
+
+#[tokio::test]
+async fn list_calls_omit_team_without_selection() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users.list"))
+        .respond_with(|req: &wiremock::Request| {
+            assert!(!req.url.query_pairs().any(|(k, _)| k == "team_id"));
+            ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "members": [],
+                "response_metadata": {"next_cursor": ""},
+            }))
+        })
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, None).await;
+    assert!(client.users.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn read_states_keep_order_and_treat_zero_last_read_as_unread() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.info"))
+        .and(query_param("channel", "C1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "channel": {"id": "C1", "last_read": "1700000000.000100", "unread_count": 9, "unread_count_display": 3},
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.info"))
+        .and(query_param("channel", "D1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "channel": {"id": "D1", "last_read": "0000000000.000000", "unread_count": 1},
+        })))
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, None).await;
+    let states = client
+        .channels
+        .read_states(&["C1".to_string(), "D1".to_string()])
+        .await
+        .unwrap();
+
+    assert_eq!(states[0].channel_id, "C1");
+    assert_eq!(states[0].last_read.as_deref(), Some("1700000000.000100"));
+    assert_eq!(states[0].unread_count, 3);
+    assert_eq!(states[1].last_read, None);
+    assert_eq!(states[1].unread_count, 1);
+}
+
+#[tokio::test]
+async fn read_states_skip_channels_that_fail() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.info"))
+        .and(query_param("channel", "C1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "channel": {"id": "C1", "last_read": "1700000000.000100", "unread_count": 2},
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.info"))
+        .and(query_param("channel", "C2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"ok": false, "error": "channel_not_found"})),
+        )
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, None).await;
+    let states = client
+        .channels
+        .read_states(&["C2".to_string(), "C1".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].channel_id, "C1");
+
+    assert!(
+        client
+            .channels
+            .read_states(&["C2".to_string()])
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn mark_posts_channel_and_ts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/conversations.mark"))
+        .and(body_partial_json(
+            json!({"channel": "C1", "ts": "1700000000.000100"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server, None).await;
+    client
+        .channels
+        .mark("C1", "1700000000.000100")
+        .await
+        .unwrap();
+}
@@ -5,20 +5,14 @@
 //! `include_all_metadata=true` invariant, plus end-to-end metadata
 //! round-tripping through `SlackMessage`.
 
-use std::sync::Arc;
+mod common;
 
-use secrecy::SecretString;
 use serde_json::{Value, json};
-use slack_cli::auth::{AuthLoadOptions, Authenticator, EnvOverrides};
-use slack_cli::config::Config;
-use slack_cli::slack::{MessageMetadata, MessagePayload, SlackClient};
-use wiremock::matchers::{body_partial_json, method, path, query_param};
+use slack_cli::config::{Config, SlackAppDistribution};
+use slack_cli::slack::{MessageMetadata, MessagePayload, SlackClient, SlackMessage};
+use wiremock::matchers::{body_partial_json, method, path, query_param, query_param_is_missing};
 use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
 
-fn secret(value: &str) -> SecretString {
-    SecretString::new(value.to_string().into_boxed_str())
-}
-
 /// Slack expects POST bodies as JSON; capture the last request via a hook.
 struct CaptureBody {
     sink: tokio::sync::mpsc::UnboundedSender<Value>,
@@ -45,29 +39,20 @@ fn capture(
     (CaptureBody { sink: tx, response }, rx)
 }
 
-/// Returns the `SlackClient` plus the tempdir backing the (unused) auth store.
-/// Bind the tempdir for the test's lifetime — dropping it cleans the fs.
 async fn test_client(server: &MockServer) -> (SlackClient, tempfile::TempDir) {
-    let mut config = Config::default();
-    config.connection.api_base_url = server.uri();
-    config.connection.rate_limit_per_minute = 600;
-
-    let store_dir = tempfile::tempdir().unwrap();
-    let store_path = store_dir.path().join("auth.json");
+    test_client_with(server, Config::default()).await
+}
 
-    let overrides = EnvOverrides {
-        user_token: Some(secret("xoxp-test-user")),
-        bot_token: Some(secret("xoxb-test-bot")),
-    };
-    let authenticator = Authenticator::load(AuthLoadOptions {
-        store_path,
-        overrides,
-        explicit_profile: None,
-    })
-    .unwrap();
+/// Commercially distributed apps get one `conversations.history` call per
+/// minute; paging tests need the tiered limit.
+async fn internal_app_client(server: &MockServer) -> (SlackClient, tempfile::TempDir) {
+    let mut config = Config::default();
+    config.connection.app_distribution = SlackAppDistribution::MarketplaceOrInternal;
+    test_client_with(server, config).await
+}
 
-    let client = SlackClient::new(config, Arc::new(authenticator)).unwrap();
-    (client, store_dir)
+async fn test_client_with(server: &MockServer, config: Config) -> (SlackClient, tempfile::TempDir) {
+    common::client(server, config, common::user_and_bot_tokens()).await
 }
 
 #[tokio::test]
@@ -321,6 +306,217 @@ async fn history_surfaces_next_cursor() {
     assert_eq!(next_cursor.as_deref(), Some("dXNlcjpVMDYxTkZUVDI="));
 }
 
+async fn mount_two_history_pages(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/conversations.history"))
+        .and(query_param_is_missing("cursor"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": [
+                {"ts": "1700000000.000300", "user": "U123", "text": "third"},
+                {"ts": "1700000000.000200", "user": "U123", "text": "second"}
+            ],
+            "response_metadata": {"next_cursor": "page2"},
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.history"))
+        .and(query_param("cursor", "page2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": [
+                {"ts": "1700000000.000100", "user": "U123", "text": "first"}
+            ],
+            "response_metadata": {"next_cursor": ""},
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn history_pages_follow_cursor_to_the_end() {
+    let server = MockServer::start().await;
+    mount_two_history_pages(&server).await;
+
+    let (client, _store) = internal_app_client(&server).await;
+    let mut pages = Vec::new();
+    let next_cursor = client
+        .messages
+        .history_pages("C123", None, None, None, None, async |page| {
+            pages.push(page.into_iter().map(|m| m.text).collect::<Vec<_>>());
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(pages, [vec!["third", "second"], vec!["first"]]);
+    assert_eq!(next_cursor, None);
+}
+
+#[tokio::test]
+async fn history_pages_stop_at_max_with_resume_cursor() {
+    let server = MockServer::start().await;
+    mount_two_history_pages(&server).await;
+
+    let (client, _store) = internal_app_client(&server).await;
+    let mut fetched = 0;
+    let next_cursor = client
+        .messages
+        .history_pages("C123", None, None, None, Some(2), async |page| {
+            fetched += page.len();
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(fetched, 2);
+    assert_eq!(next_cursor.as_deref(), Some("page2"));
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].url.query().unwrap().contains("limit=2"));
+}
+
+#[tokio::test]
+async fn attach_replies_fetches_only_threaded_parents() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/conversations.replies"))
+        .and(query_param("ts", "1700000000.000100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": [
+                {"ts": "1700000000.000100", "user": "U123", "text": "parent", "reply_count": 2},
+                {"ts": "1700000000.000150", "user": "U456", "text": "first reply"},
+                {"ts": "1700000000.000160", "user": "U123", "text": "second reply"}
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = internal_app_client(&server).await;
+    let mut messages: Vec<SlackMessage> = serde_json::from_value(json!([
+        {"ts": "1700000000.000200", "user": "U123", "text": "no thread"},
+        {"ts": "1700000000.000100", "user": "U123", "text": "parent", "reply_count": 2}
+    ]))
+    .unwrap();
+    client
+        .messages
+        .attach_replies("C123", &mut messages)
+        .await
+        .unwrap();
+
+    assert!(messages[0].replies.is_none());
+    let replies = messages[1].replies.as_ref().unwrap();
+    let texts: Vec<&str> = replies.iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(texts, ["first reply", "second reply"]);
+}
+
+/// Thread pages keyed by cursor: none -> "p2" -> end. The first page has
+/// only the author's own follow-up.
+async fn mount_thread_pages(server: &MockServer, second_page_user: &str) {
+    let second_page_user = second_page_user.to_string();
+    Mock::given(method("GET"))
+        .and(path("/conversations.replies"))
+        .respond_with(move |req: &Request| {
+            let cursor = req
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "cursor")
+                .map(|(_, value)| value.into_owned());
+            let (messages, next) = match cursor.as_deref() {
+                None => (
+                    json!([
+                        {"ts": "1700000000.000100", "user": "U123", "text": "parent", "reply_count": 3},
+                        {"ts": "1700000000.000150", "user": "U123", "text": "more detail"}
+                    ]),
+                    "p2",
+                ),
+                Some("p2") => (
+                    json!([{"ts": "1700000000.000300", "user": second_page_user, "text": "answer"}]),
+                    "",
+                ),
+                Some(other) => panic!("unexpected cursor {other}"),
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "messages": messages,
+                "response_metadata": {"next_cursor": next}
+            }))
+        })
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn attach_first_replies_reads_past_author_follow_ups() {
+    let server = MockServer::start().await;
+    mount_thread_pages(&server, "U456").await;
+
+    let (client, _store) = internal_app_client(&server).await;
+    let mut messages: Vec<SlackMessage> = serde_json::from_value(json!([
+        {"ts": "1700000000.000100", "user": "U123", "text": "parent", "reply_count": 3}
+    ]))
+    .unwrap();
+    client
+        .messages
+        .attach_first_replies("C123", &mut messages)
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].url.query().unwrap().contains("limit=5"));
+    let texts: Vec<&str> = messages[0]
+        .replies
+        .iter()
+        .flatten()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(texts, ["more detail", "answer"]);
+}
+
+#[tokio::test]
+async fn attach_first_replies_stops_at_the_first_answer() {
+    let server = MockServer::start().await;
+    mount_thread_pages(&server, "U456").await;
+
+    let (client, _store) = internal_app_client(&server).await;
+    let mut messages: Vec<SlackMessage> = serde_json::from_value(json!([
+        // Someone else started it, so the first page already answers it.
+        {"ts": "1700000000.000100", "user": "U999", "text": "parent", "reply_count": 3}
+    ]))
+    .unwrap();
+    client
+        .messages
+        .attach_first_replies("C123", &mut messages)
+        .await
+        .unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    assert_eq!(messages[0].replies.as_ref().unwrap().len(), 1);
+}
+
+/// Commercially distributed apps get 15-message pages at one call per
+/// minute, so this test waits out the limiter once.
+#[tokio::test]
+async fn replies_follow_the_cursor_on_commercial_page_limits() {
+    let server = MockServer::start().await;
+    mount_thread_pages(&server, "U456").await;
+
+    let (client, _store) = test_client(&server).await;
+    let thread = client
+        .messages
+        .replies("C123", "1700000000.000100", 100)
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].url.query().unwrap().contains("limit=15"));
+    assert_eq!(thread.len(), 3);
+}
+
 #[tokio::test]
 async fn api_error_surfaces_slack_error_code_as_typed_error() {
     let server = MockServer::start().await;
@@ -352,6 +548,36 @@ async fn api_error_surfaces_slack_error_code_as_typed_error() {
     }
 }
 
+#[tokio::test]
+async fn missing_scope_error_carries_needed_scopes() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat.postMessage"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": false,
+            "error": "missing_scope",
+            "needed": "chat:write",
+            "provided": "users:read",
+        })))
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let payload = MessagePayload {
+        text: Some("hi".into()),
+        ..Default::default()
+    };
+    let err = client.messages.send("C1", payload, None).await.unwrap_err();
+
+    match err.downcast_ref::<slack_cli::slack::SlackApiError>() {
+        Some(slack_cli::slack::SlackApiError::MissingScope { method, needed }) => {
+            assert_eq!(method, "chat.postMessage");
+            assert_eq!(needed, &vec!["chat:write".to_string()]);
+        }
+        other => panic!("expected SlackApiError::MissingScope, got {other:?}"),
+    }
+}
+
 #[tokio::test]
 async fn replies_always_requests_include_all_metadata() {
     let server = MockServer::start().await;
@@ -80,6 +80,7 @@ async fn pkce_flow_completes_end_to_end() {
             PkceRunOptions {
                 no_browser: true,
                 callback_timeout: Duration::from_secs(5),
+                write_scopes: false,
             },
             verifier,
             state,
@@ -126,6 +127,7 @@ async fn pkce_flow_rejects_mismatched_state() {
             PkceRunOptions {
                 no_browser: true,
                 callback_timeout: Duration::from_secs(5),
+                write_scopes: false,
             },
             verifier,
             "expected-state".to_string(),
@@ -172,6 +174,7 @@ async fn pkce_flow_surfaces_token_exchange_error() {
             PkceRunOptions {
                 no_browser: true,
                 callback_timeout: Duration::from_secs(5),
+                write_scopes: false,
             },
             verifier,
             state,
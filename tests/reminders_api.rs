@@ -0,0 +1,77 @@
+//! Integration tests for the reminders client against a mock Slack API:
+//! `reminders.add` / `reminders.complete` request shapes and
+//! `reminders.list` parsing of one-off, recurring and completed reminders.
+
+mod common;
+
+use common::test_client;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn add_posts_text_time_and_user() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/reminders.add"))
+        .and(body_partial_json(
+            json!({"text": "check deploy", "time": 1900000000, "user": "U2"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "reminder": {
+                "id": "Rm1", "creator": "U1", "user": "U2", "text": "check deploy",
+                "recurring": false, "time": 1900000000, "complete_ts": 0
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let reminder = client
+        .reminders
+        .add("check deploy", 1900000000, Some("U2"))
+        .await
+        .unwrap();
+
+    assert_eq!(reminder.id, "Rm1");
+    assert_eq!(reminder.time, Some(1900000000));
+    assert!(!reminder.is_complete());
+}
+
+#[tokio::test]
+async fn list_reads_recurring_and_completed_reminders() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/reminders.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "reminders": [
+                {"id": "Rm1", "creator": "U1", "user": "U1", "text": "standup",
+                 "recurring": true},
+                {"id": "Rm2", "creator": "U1", "user": "U1", "text": "check deploy",
+                 "recurring": false, "time": 1900000000, "complete_ts": 1900000100}
+            ]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/reminders.complete"))
+        .and(body_partial_json(json!({"reminder": "Rm1"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let reminders = client.reminders.list().await.unwrap();
+
+    assert_eq!(reminders.len(), 2);
+    assert!(reminders[0].recurring);
+    assert_eq!(reminders[0].time, None);
+    assert!(!reminders[0].is_complete());
+    assert!(reminders[1].is_complete());
+
+    client.reminders.complete("Rm1").await.unwrap();
+}
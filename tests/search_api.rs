@@ -0,0 +1,225 @@
+//! Integration tests for `assistant.search.context` pagination against a mock
+//! Slack API: resuming from a cursor and streaming every page.
+
+mod common;
+
+use common::test_client;
+use serde_json::{Value, json};
+use slack_cli::slack::{
+    SearchChannelType, SearchContentType, SearchOptions, SearchSort, SearchSortDirection,
+};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Three pages keyed by cursor: none -> "p2" -> "p3" -> end.
+async fn mount_pages(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/assistant.search.context"))
+        .respond_with(|req: &Request| {
+            let body: Value = serde_json::from_slice(&req.body).unwrap();
+            let (ts, next) = match body.get("cursor").and_then(Value::as_str) {
+                None => ("1.0", "p2"),
+                Some("p2") => ("2.0", "p3"),
+                Some("p3") => ("3.0", ""),
+                Some(other) => panic!("unexpected cursor {other}"),
+            };
+            ResponseTemplate::new(200).set_body_json(json!({
+                "ok": true,
+                "results": {"messages": [{"message_ts": ts, "content": ts}]},
+                "response_metadata": {"next_cursor": next},
+            }))
+        })
+        .mount(server)
+        .await;
+}
+
+fn options(limit: usize) -> SearchOptions {
+    SearchOptions {
+        limit,
+        channel_types: vec![SearchChannelType::PublicChannel],
+        content_types: vec![SearchContentType::Messages],
+        context_channel_id: None,
+        include_archived_channels: false,
+        before: None,
+        after: None,
+        include_bots: false,
+        disable_semantic_search: false,
+        sort: SearchSort::Score,
+        sort_dir: SearchSortDirection::Desc,
+        include_context_messages: false,
+        from_user: None,
+        has: Vec::new(),
+        is: Vec::new(),
+        include_message_blocks: false,
+        highlight: false,
+    }
+}
+
+#[tokio::test]
+async fn context_resumes_from_cursor_and_returns_next() {
+    let server = MockServer::start().await;
+    mount_pages(&server).await;
+    let (client, _store) = test_client(&server).await;
+
+    let (results, next) = client
+        .search
+        .context("deploy", &options(1), Some("p2"))
+        .await
+        .unwrap();
+
+    assert_eq!(results.messages.len(), 1);
+    assert_eq!(results.messages[0].ts, "2.0");
+    assert_eq!(next.as_deref(), Some("p3"));
+}
+
+#[tokio::test]
+async fn context_reports_no_cursor_on_last_page() {
+    let server = MockServer::start().await;
+    mount_pages(&server).await;
+    let (client, _store) = test_client(&server).await;
+
+    let (results, next) = client
+        .search
+        .context("deploy", &options(10), None)
+        .await
+        .unwrap();
+
+    assert_eq!(results.messages.len(), 3);
+    assert!(next.is_none());
+}
+
+#[tokio::test]
+async fn pager_streams_every_page() {
+    let server = MockServer::start().await;
+    mount_pages(&server).await;
+    let (client, _store) = test_client(&server).await;
+
+    let mut pager = client.search.pages("deploy", &options(1), None);
+    let mut seen = Vec::new();
+    while let Some(page) = pager.next_page().await.unwrap() {
+        seen.extend(page.messages.into_iter().map(|m| m.ts));
+    }
+
+    assert_eq!(seen, ["1.0", "2.0", "3.0"]);
+    assert!(pager.cursor().is_none());
+}
+
+#[tokio::test]
+async fn falls_back_to_search_messages_when_rts_is_not_allowed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/assistant.search.context"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"ok": false, "error": "not_allowed"})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search.messages"))
+        .and(query_param("query", "deploy in:<#C1>"))
+        .and(query_param("count", "5"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": {
+                "matches": [{
+                    "ts": "1.0",
+                    "text": "deploy done",
+                    "channel": {"id": "C1", "name": "ops"}
+                }],
+                "paging": {"count": 5, "total": 6, "page": 1, "pages": 2}
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search.messages"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": {
+                "matches": [{"ts": "2.0", "text": "deploy again"}],
+                "paging": {"count": 5, "total": 6, "page": 2, "pages": 2}
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let mut opts = options(5);
+    opts.context_channel_id = Some("C1".into());
+
+    let (results, next) = client.search.context("deploy", &opts, None).await.unwrap();
+    assert_eq!(results.messages[0].channel_name.as_deref(), Some("ops"));
+    assert_eq!(next.as_deref(), Some("legacy:5:2"));
+
+    // The legacy cursor resumes on the legacy backend without retrying RTS.
+    let (results, next) = client
+        .search
+        .context("deploy", &opts, Some("legacy:5:2"))
+        .await
+        .unwrap();
+    assert_eq!(results.messages[0].ts, "2.0");
+    assert!(next.is_none());
+}
+
+#[tokio::test]
+async fn other_search_errors_do_not_fall_back() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/assistant.search.context"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"ok": false, "error": "invalid_arguments"})),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(path("/search.messages"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let err = client
+        .search
+        .context("deploy", &options(5), None)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("invalid_arguments"));
+}
+
+#[tokio::test]
+async fn author_and_has_filters_go_to_search_messages() {
+    let server = MockServer::start().await;
+    Mock::given(path("/assistant.search.context"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search.messages"))
+        .and(query_param("query", "deploy from:<@U1> has:link"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "ok": true,
+            "messages": {
+                "matches": [{"ts": "1.0", "text": "deploy https://ci", "user": "U1"}],
+                "paging": {"count": 5, "total": 1, "page": 1, "pages": 1}
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (client, _store) = test_client(&server).await;
+    let mut opts = options(5);
+    opts.from_user = Some("U1".into());
+    opts.has = vec!["link".into()];
+
+    let (results, next) = client.search.context("deploy", &opts, None).await.unwrap();
+    assert_eq!(results.messages[0].author_user_id.as_deref(), Some("U1"));
+    assert!(next.is_none());
+}